@@ -1,25 +1,82 @@
 use crate::components::main::MainComponent;
 use crate::components::Component;
-use crate::config::Config;
+use crate::config::{Config, ConfigFile, GlobalAction, KeyBuffer, Keymap, Theme};
 use crate::tui::{io, Tui};
 use crate::types::{Action, Event};
+use chrono::Utc;
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use tokio::time;
 
 pub struct App {
     config: Config,
+    config_file: ConfigFile,
+    config_error: Option<String>,
     components: Vec<Box<dyn Component>>,
+    keymap: Keymap<GlobalAction>,
+    pending_keys: KeyBuffer,
+    theme: Theme,
+    /// Minutes since the epoch at the last tick, to redraw relative timestamps.
+    minute: i64,
+    frames: Frames,
+}
+
+/// Combines the requests to render into at most one draw per frame interval.
+struct Frames {
+    interval: Duration,
+    /// A render is requested and not drawn yet.
+    dirty: bool,
+    last: Option<Instant>,
+}
+
+impl Frames {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            dirty: false,
+            last: None,
+        }
+    }
+    fn request(&mut self) {
+        self.dirty = true;
+    }
+    /// When to draw the requested render, if any.
+    fn deadline(&self) -> Option<Instant> {
+        self.dirty
+            .then(|| self.last.map(|last| last + self.interval))
+            .map(|deadline| deadline.unwrap_or_else(Instant::now))
+    }
+    /// Returns whether to draw now, and counts it as drawn if so.
+    fn take(&mut self, now: Instant) -> bool {
+        if self.dirty && self.last.is_none_or(|last| last + self.interval <= now) {
+            self.dirty = false;
+            self.last = Some(now);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl App {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, config_file: ConfigFile) -> Self {
         log::debug!("App::new({config:?})");
         Self {
+            keymap: config.keybindings.global_keymap(),
+            pending_keys: config.keybindings.key_buffer(),
+            theme: Theme::from(&config.theme),
+            frames: Frames::new(config.frame_interval()),
             config,
+            config_file,
+            config_error: None,
             components: Vec::new(),
+            minute: 0,
         }
     }
     pub async fn run(&mut self) -> Result<()> {
@@ -30,6 +87,7 @@ impl App {
         log::debug!("terminal size: {}", terminal.size()?);
         let mut tui = Tui::new(terminal);
         tui.start()?;
+        tui.set_mouse(self.config.mouse)?;
 
         // Create main component
         let mut main_component = MainComponent::new(self.config.clone(), action_tx.clone());
@@ -56,20 +114,30 @@ impl App {
         #[cfg(not(windows))]
         let mut should_suspend = false;
         loop {
-            if let Some(e) = tui.next_event().await {
-                if let Some(action) = self.handle_events(e.clone()) {
-                    action_tx.send(action)?;
-                }
-                if let Some(action) = main_component.handle_events(Some(e.clone()))? {
-                    action_tx.send(action)?;
-                }
-                for component in self.components.iter_mut() {
-                    if let Some(action) = component.handle_events(Some(e.clone()))? {
-                        action_tx.send(action)?;
+            // wait for an event, an action, or the frame of a requested render
+            let mut next = None;
+            let deadline = self.frames.deadline();
+            let frame = time::sleep_until(deadline.unwrap_or_else(Instant::now).into());
+            tokio::select! {
+                e = tui.next_event() => {
+                    if let Some(e) = e {
+                        if let Some(action) = self.handle_events(e.clone()) {
+                            action_tx.send(action)?;
+                        }
+                        if let Some(action) = main_component.handle_events(Some(e.clone()))? {
+                            action_tx.send(action)?;
+                        }
+                        for component in self.components.iter_mut() {
+                            if let Some(action) = component.handle_events(Some(e.clone()))? {
+                                action_tx.send(action)?;
+                            }
+                        }
                     }
                 }
+                action = action_rx.recv() => next = action,
+                _ = frame, if deadline.is_some() => {}
             }
-            while let Ok(action) = action_rx.try_recv() {
+            while let Some(action) = next.take().or_else(|| action_rx.try_recv().ok()) {
                 if !matches!(action, Action::Tick(_) | Action::Render) {
                     log::info!("Action {action:?}");
                 }
@@ -87,24 +155,30 @@ impl App {
                         if i % 60 == 0 {
                             main_component.save().await?;
                         }
-                    }
-                    Action::Render => {
-                        tui.draw(|f| {
-                            // render main components to the left side
-                            if let Err(e) = main_component.draw(f, f.area()) {
-                                action_tx
-                                    .send(Action::Error(format!("failed to draw: {e}")))
-                                    .ok();
+                        if self.pending_keys.expire() {
+                            action_tx.send(Action::Render)?;
+                        }
+                        let minute = Utc::now().timestamp() / 60;
+                        if minute != self.minute {
+                            self.minute = minute;
+                            if self.config.timestamp.is_relative() {
+                                main_component.invalidate();
+                                action_tx.send(Action::Render)?;
                             }
-                            for component in self.components.iter_mut() {
-                                if let Err(e) = component.draw(f, f.area()) {
-                                    action_tx
-                                        .send(Action::Error(format!("failed to draw: {e}")))
-                                        .ok();
-                                }
+                        }
+                        if self.config_file.is_modified() {
+                            let mouse = self.config.mouse;
+                            self.reload_config(&mut main_component)?;
+                            if self.config.mouse != mouse {
+                                tui.set_mouse(self.config.mouse)?;
                             }
-                        })?;
+                            action_tx.send(Action::Render)?;
+                        }
+                        if let Some(action) = main_component.update(action.clone())? {
+                            action_tx.send(action)?;
+                        }
                     }
+                    Action::Render => self.frames.request(),
                     _ => {
                         if let Some(action) = main_component.update(action.clone())? {
                             action_tx.send(action)?;
@@ -117,6 +191,27 @@ impl App {
                     }
                 }
             }
+            if self.frames.take(Instant::now()) {
+                main_component.set_global_keys(self.pending_keys.keys());
+                tui.draw(|f| {
+                    // render main components to the left side
+                    if let Err(e) = main_component.draw(f, f.area()) {
+                        action_tx
+                            .send(Action::Error(format!("failed to draw: {e}")))
+                            .ok();
+                    }
+                    for component in self.components.iter_mut() {
+                        if let Err(e) = component.draw(f, f.area()) {
+                            action_tx
+                                .send(Action::Error(format!("failed to draw: {e}")))
+                                .ok();
+                        }
+                    }
+                    if let Some(message) = &self.config_error {
+                        draw_config_error(f, message, &self.theme);
+                    }
+                })?;
+            }
             #[cfg(not(windows))]
             if should_suspend {
                 tui.suspend()?;
@@ -131,9 +226,39 @@ impl App {
         tui.end()?;
         Ok(())
     }
+    /// Apply the modified config file to all components, or keep the current config if it fails to load.
+    fn reload_config(&mut self, main_component: &mut MainComponent) -> Result<()> {
+        let config = match self.config_file.load() {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("failed to reload config: {e}");
+                self.config_error = Some(format!(
+                    "failed to reload {}: {e}",
+                    self.config_file.path().display()
+                ));
+                return Ok(());
+            }
+        };
+        self.config_error = None;
+        if config == self.config {
+            return Ok(());
+        }
+        log::info!("reloaded config from {:?}", self.config_file.path());
+        self.keymap = config.keybindings.global_keymap();
+        self.pending_keys = config.keybindings.key_buffer();
+        self.theme = Theme::from(&config.theme);
+        self.frames.interval = config.frame_interval();
+        main_component.register_config_handler(config.clone())?;
+        for component in self.components.iter_mut() {
+            component.register_config_handler(config.clone())?;
+        }
+        self.config = config;
+        Ok(())
+    }
     fn handle_events(&mut self, event: Event) -> Option<Action> {
         match event {
             Event::Tick(i) => return Some(Action::Tick(i)),
+            Event::Resize(width, height) => return Some(Action::Resize(width, height)),
             Event::Key(key_event) => {
                 if let Some(action) = self.handle_key_events(key_event) {
                     return Some(action);
@@ -145,10 +270,58 @@ impl App {
         None
     }
     fn handle_key_events(&mut self, key_event: KeyEvent) -> Option<Action> {
-        self.config
-            .keybindings
-            .global
-            .get(&key_event.into())
-            .map(Into::into)
+        let pending = !self.pending_keys.keys().is_empty();
+        let action = self
+            .keymap
+            .feed(&mut self.pending_keys, key_event.into())
+            .map(Into::into);
+        if action.is_none() && (pending || !self.pending_keys.keys().is_empty()) {
+            Some(Action::Render)
+        } else {
+            action
+        }
+    }
+}
+
+fn draw_config_error(f: &mut Frame<'_>, message: &str, theme: &Theme) {
+    let area = f.area();
+    let height = (message.lines().count() as u16 + 2).min(area.height / 2);
+    let area = Rect::new(
+        area.x,
+        area.bottom().saturating_sub(height),
+        area.width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(message)
+            .style(theme.invalid)
+            .wrap(Wrap::default())
+            .block(Block::bordered().title(" Config error ")),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames() {
+        let now = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut frames = Frames::new(interval);
+        assert_eq!(frames.deadline(), None);
+        assert!(!frames.take(now));
+
+        // the first render is drawn at once, and the following ones are combined until the next frame
+        frames.request();
+        assert!(frames.take(now));
+        frames.request();
+        frames.request();
+        assert_eq!(frames.deadline(), Some(now + interval));
+        assert!(!frames.take(now + interval / 2));
+        assert!(frames.take(now + interval));
+        assert!(!frames.take(now + interval * 3));
     }
 }
@@ -1,6 +1,13 @@
+pub mod client;
 pub mod config;
+pub mod download;
+#[cfg(test)]
+pub mod fake;
 pub mod types;
+mod moderator;
+mod status;
 mod watch;
 mod watches;
 
+pub use status::Status;
 pub use watch::{Watch, Watcher};
@@ -0,0 +1,71 @@
+use bsky_sdk::BskyAgent;
+use bsky_sdk::agent::BskyAtpAgentBuilder;
+use bsky_sdk::api::xrpc::http::{Request, Response};
+use bsky_sdk::api::xrpc::{HttpClient, XrpcClient};
+use futures_util::future::BoxFuture;
+use std::error::Error;
+use std::sync::Arc;
+
+/// Agent of the accounts, sending the XRPC requests through its [`Client`].
+pub type Agent = BskyAgent<Client>;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync + 'static>>;
+
+/// Sends the HTTP requests of a [`Client`].
+///
+/// Implemented for [`reqwest::Client`] to use the network,
+/// and can be replaced to serve the requests without it, such as in tests.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, Result<Response<Vec<u8>>>>;
+}
+
+impl Transport for reqwest::Client {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, Result<Response<Vec<u8>>>> {
+        Box::pin(async move {
+            let response = self.execute(request.try_into()?).await?;
+            let mut builder = Response::builder().status(response.status());
+            for (k, v) in response.headers() {
+                builder = builder.header(k, v);
+            }
+            Ok(builder.body(response.bytes().await?.to_vec())?)
+        })
+    }
+}
+
+/// XRPC client with a replaceable [`Transport`].
+#[derive(Clone)]
+pub struct Client {
+    transport: Arc<dyn Transport>,
+}
+
+impl Client {
+    pub fn new(transport: impl Transport + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(reqwest::Client::new())
+    }
+}
+
+impl HttpClient for Client {
+    async fn send_http(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>> {
+        self.transport.send(request).await
+    }
+}
+
+impl XrpcClient for Client {
+    // replaced by the endpoint of the agent
+    fn base_uri(&self) -> String {
+        String::from("https://bsky.social")
+    }
+}
+
+/// Builder of an [`Agent`] which uses the network.
+pub fn builder() -> BskyAtpAgentBuilder<Client> {
+    BskyAtpAgentBuilder::new(Client::default())
+}
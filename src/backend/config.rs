@@ -11,6 +11,7 @@ pub struct Intervals {
     pub preferences: u64,
     pub feed: u64,
     pub post_thread: u64,
+    pub notifications: u64,
 }
 
 impl Default for Intervals {
@@ -19,6 +20,7 @@ impl Default for Intervals {
             preferences: 600,
             feed: 30,
             post_thread: 60,
+            notifications: 30,
         }
     }
 }
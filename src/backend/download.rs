@@ -0,0 +1,329 @@
+use super::client::Agent;
+use bsky_sdk::api::app::bsky::embed::images;
+use bsky_sdk::api::app::bsky::embed::record_with_media::{MainMediaRefs, ViewMediaRefs};
+use bsky_sdk::api::app::bsky::feed::defs::{PostView, PostViewEmbedRefs};
+use bsky_sdk::api::app::bsky::feed::post::{self, RecordEmbedRefs};
+use bsky_sdk::api::types::string::Cid;
+use bsky_sdk::api::types::{BlobRef, TryFromUnknown, TypedBlobRef, Union};
+use color_eyre::{Result, eyre};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// An image attached to a post, resolved from both the embed view and the record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSource {
+    pub fullsize: String,
+    pub alt: String,
+    pub blob: Option<(Cid, String)>,
+}
+
+/// Collect the images of an `app.bsky.embed.images` or `app.bsky.embed.recordWithMedia` embed.
+pub fn image_sources(post_view: &PostView) -> Vec<ImageSource> {
+    let views = match &post_view.embed {
+        Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(images))) => &images.images,
+        Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(
+            record_with_media,
+        ))) => match &record_with_media.media {
+            Union::Refs(ViewMediaRefs::AppBskyEmbedImagesView(images)) => &images.images,
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    let blobs = post::Record::try_from_unknown(post_view.record.clone())
+        .ok()
+        .and_then(|record| match record.data.embed {
+            Some(Union::Refs(RecordEmbedRefs::AppBskyEmbedImagesMain(images))) => {
+                Some(images.data.images)
+            }
+            Some(Union::Refs(RecordEmbedRefs::AppBskyEmbedRecordWithMediaMain(
+                record_with_media,
+            ))) => match record_with_media.data.media {
+                Union::Refs(MainMediaRefs::AppBskyEmbedImagesMain(images)) => {
+                    Some(images.data.images)
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_default();
+    views
+        .iter()
+        .enumerate()
+        .map(|(i, view)| ImageSource {
+            fullsize: view.fullsize.clone(),
+            alt: view.alt.clone(),
+            blob: blobs.get(i).and_then(blob_cid),
+        })
+        .collect()
+}
+
+fn blob_cid(image: &images::Image) -> Option<(Cid, String)> {
+    match &image.image {
+        BlobRef::Typed(TypedBlobRef::Blob(blob)) => {
+            Some((Cid::new(blob.r#ref.0), blob.mime_type.clone()))
+        }
+        BlobRef::Untyped(blob) => blob
+            .cid
+            .parse()
+            .ok()
+            .map(|cid| (cid, blob.mime_type.clone())),
+    }
+}
+
+/// Download the full-size images of a post into `dir`.
+///
+/// Files are named `{handle}_{rkey}_{n}.{ext}`, and non-empty alt texts are written next to them as `{handle}_{rkey}_{n}.txt`.
+/// Existing files are kept, and the new ones are named `{handle}_{rkey}_{n}-{k}` instead.
+pub async fn download_images(
+    agent: &Agent,
+    post_view: &PostView,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let sources = image_sources(post_view);
+    if sources.is_empty() {
+        return Err(eyre::eyre!("no images to download"));
+    }
+    let rkey = post_view
+        .uri
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| eyre::eyre!("invalid post uri: {}", post_view.uri))?;
+    let prefix = format!("{}_{rkey}", post_view.author.handle.as_str());
+    fs::create_dir_all(dir).await?;
+
+    let client = reqwest::Client::new();
+    let mut paths = Vec::with_capacity(sources.len());
+    for (i, source) in sources.iter().enumerate() {
+        let (bytes, mime_type) = match fetch_cdn(&client, &source.fullsize).await {
+            Ok(result) => result,
+            Err(e) => {
+                let Some((cid, mime_type)) = &source.blob else {
+                    return Err(e);
+                };
+                log::warn!(
+                    "failed to fetch {}: {e}, falling back to getBlob",
+                    source.fullsize
+                );
+                (fetch_blob(agent, post_view, cid).await?, mime_type.clone())
+            }
+        };
+        let stem = format!("{prefix}_{}", i + 1);
+        let has_alt = !source.alt.is_empty();
+        let (stem, path, mut file) = create_new(dir, &stem, extension(&mime_type), has_alt).await?;
+        file.write_all(&bytes).await?;
+        if has_alt {
+            fs::write(dir.join(format!("{stem}.txt")), &source.alt).await?;
+        }
+        log::info!("downloaded image to {path:?}");
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Create the file of an image, numbering the name after the existing files instead of overwriting them.
+async fn create_new(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+    has_alt: bool,
+) -> Result<(String, PathBuf, File)> {
+    let mut k = 1;
+    loop {
+        let stem = if k == 1 {
+            stem.to_string()
+        } else {
+            format!("{stem}-{k}")
+        };
+        k += 1;
+        // the alt text is written next to the image, without overwriting another one either
+        if has_alt && fs::try_exists(dir.join(format!("{stem}.txt"))).await? {
+            continue;
+        }
+        let path = dir.join(format!("{stem}.{extension}"));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => return Ok((stem, path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+async fn fetch_cdn(client: &reqwest::Client, url: &str) -> Result<(Vec<u8>, String)> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    let mime_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("image/jpeg")
+        .to_string();
+    Ok((response.bytes().await?.to_vec(), mime_type))
+}
+
+async fn fetch_blob(agent: &Agent, post_view: &PostView, cid: &Cid) -> Result<Vec<u8>> {
+    Ok(agent
+        .api
+        .com
+        .atproto
+        .sync
+        .get_blob(
+            bsky_sdk::api::com::atproto::sync::get_blob::ParametersData {
+                cid: cid.clone(),
+                did: post_view.author.did.clone(),
+            }
+            .into(),
+        )
+        .await?)
+}
+
+fn extension(mime_type: &str) -> &str {
+    match mime_type.split(';').next().map(str::trim) {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("image/avif") => "avif",
+        Some("image/heic") => "heic",
+        _ => "jpg",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fake::{self, FakeServer};
+    use super::*;
+    use serde_json::json;
+    use std::{env, process};
+
+    const GET_BLOB: &str = "com.atproto.sync.getBlob";
+
+    /// A post with two images, whose full-size URLs do not answer so that they are fetched with getBlob.
+    fn post_with_images() -> PostView {
+        let mut post_view = fake::post_view(
+            fake::profile("bob.test", None),
+            "3k44deefqdk2g",
+            "",
+            "2024-10-01T00:00:00.000Z",
+        );
+        let cid = post_view.cid.as_ref().to_string();
+        let image = |alt: &str| {
+            json!({
+                "alt": alt,
+                "image": {
+                    "$type": "blob",
+                    "ref": { "$link": cid },
+                    "mimeType": "image/png",
+                    "size": 3,
+                },
+            })
+        };
+        let view = |alt: &str| {
+            json!({
+                "alt": alt,
+                "thumb": "http://127.0.0.1:9/thumb",
+                "fullsize": "http://127.0.0.1:9/fullsize",
+            })
+        };
+        post_view.embed = serde_json::from_value(json!({
+            "$type": "app.bsky.embed.images#view",
+            "images": [view("a cat"), view("")],
+        }))
+        .expect("invalid embed");
+        post_view.record = serde_json::from_value(json!({
+            "$type": "app.bsky.feed.post",
+            "text": "",
+            "createdAt": "2024-10-01T00:00:00.000Z",
+            "embed": {
+                "$type": "app.bsky.embed.images",
+                "images": [image("a cat"), image("")],
+            },
+        }))
+        .expect("invalid record");
+        post_view
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names = std::fs::read_dir(dir)
+            .expect("failed to read dir")
+            .map(|entry| {
+                entry
+                    .expect("failed to read entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn download() {
+        let dir = env::temp_dir().join(format!("tuisky-download-{}", process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        let server = FakeServer::default();
+        server.respond_bytes(GET_BLOB, "image/png", b"png");
+        let agent = server.agent().await;
+        let post_view = post_with_images();
+
+        let paths = download_images(&agent, &post_view, &dir)
+            .await
+            .expect("failed to download");
+        assert_eq!(paths.len(), 2);
+        assert_eq!(
+            file_names(&dir),
+            [
+                "bob.test_3k44deefqdk2g_1.png",
+                "bob.test_3k44deefqdk2g_1.txt",
+                "bob.test_3k44deefqdk2g_2.png",
+            ]
+        );
+        let calls = server.calls(GET_BLOB);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].param("did"), Some("did%3Afake%3Abob.test"));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("bob.test_3k44deefqdk2g_1.txt")).ok(),
+            Some(String::from("a cat"))
+        );
+
+        // downloaded again without overwriting the files
+        std::fs::write(dir.join("bob.test_3k44deefqdk2g_1.png"), b"kept").expect("failed to write");
+        download_images(&agent, &post_view, &dir)
+            .await
+            .expect("failed to download");
+        assert_eq!(
+            file_names(&dir),
+            [
+                "bob.test_3k44deefqdk2g_1-2.png",
+                "bob.test_3k44deefqdk2g_1-2.txt",
+                "bob.test_3k44deefqdk2g_1.png",
+                "bob.test_3k44deefqdk2g_1.txt",
+                "bob.test_3k44deefqdk2g_2-2.png",
+                "bob.test_3k44deefqdk2g_2.png",
+            ]
+        );
+        assert_eq!(
+            std::fs::read(dir.join("bob.test_3k44deefqdk2g_1.png")).ok(),
+            Some(b"kept".to_vec())
+        );
+        assert_eq!(
+            std::fs::read(dir.join("bob.test_3k44deefqdk2g_1-2.png")).ok(),
+            Some(b"png".to_vec())
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(extension("image/jpeg"), "jpg");
+        assert_eq!(extension("image/png"), "png");
+        assert_eq!(extension("image/webp; charset=binary"), "webp");
+        assert_eq!(extension("application/octet-stream"), "jpg");
+    }
+}
@@ -0,0 +1,270 @@
+//! In-process fake of the PDS and the AppView, as the [`Transport`] of the agents in tests.
+//!
+//! Methods are answered with the canned outputs given to the server, and all requests are
+//! recorded to inspect what was read and written.
+
+use super::Watcher;
+use super::client::{Agent, Client, Result, Transport};
+use super::config::Config;
+use atrium_common::store::Store;
+use bsky_sdk::agent::BskyAtpAgentBuilder;
+use bsky_sdk::api::agent::atp_agent::store::MemorySessionStore;
+use bsky_sdk::api::app::bsky::actor::defs::{ProfileViewBasic, ProfileViewBasicData};
+use bsky_sdk::api::app::bsky::feed::defs::{
+    FeedViewPost, FeedViewPostData, PostView, PostViewData,
+};
+use bsky_sdk::api::app::bsky::feed::post::RecordData;
+use bsky_sdk::api::com::atproto::server::create_session::OutputData;
+use bsky_sdk::api::types::TryIntoUnknown;
+use bsky_sdk::api::types::string::{Cid, Datetime};
+use bsky_sdk::api::xrpc::http::{Method, Request, Response, StatusCode, header};
+use futures_util::future::BoxFuture;
+use ipld_core::cid::{self, multihash::Multihash};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Did of the account signed in to the agents of the server.
+pub const DID: &str = "did:fake:alice.test";
+/// Handle of the account signed in to the agents of the server.
+pub const HANDLE: &str = "alice.test";
+
+const DAG_CBOR: u64 = 0x71;
+const IDENTITY: u64 = 0x00;
+
+#[derive(Debug, Clone)]
+enum Reply {
+    Output(Value),
+    /// Body of the methods whose output is not JSON, such as blobs.
+    Bytes(String, Vec<u8>),
+    Error(StatusCode, String),
+}
+
+/// A request received by the server.
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub method: Method,
+    pub nsid: String,
+    /// Query string of the parameters, as encoded.
+    pub query: Option<String>,
+    /// Input of the procedures.
+    pub input: Option<Value>,
+}
+
+impl Call {
+    /// Value of the parameter, as encoded.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.as_deref()?.split('&').find_map(|pair| {
+            pair.split_once('=')
+                .filter(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    replies: HashMap<String, VecDeque<Reply>>,
+    calls: Vec<Call>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeServer {
+    /// Answer the method with the output.
+    ///
+    /// Replies are given in the order they are added, and the last one is repeated.
+    /// Methods without replies fail as not implemented.
+    pub fn respond(&self, nsid: &str, output: impl Serialize) {
+        let output = serde_json::to_value(output).expect("failed to serialize output");
+        self.push(nsid, Reply::Output(output));
+    }
+    /// Answer the method with the bytes of the content type, such as the blobs of `com.atproto.sync.getBlob`.
+    pub fn respond_bytes(&self, nsid: &str, content_type: &str, bytes: &[u8]) {
+        self.push(nsid, Reply::Bytes(content_type.to_string(), bytes.to_vec()));
+    }
+    /// Answer the method with the XRPC error.
+    pub fn fail(&self, nsid: &str, status: StatusCode, error: &str) {
+        self.push(nsid, Reply::Error(status, error.to_string()));
+    }
+    fn push(&self, nsid: &str, reply: Reply) {
+        self.lock()
+            .replies
+            .entry(nsid.to_string())
+            .or_default()
+            .push_back(reply);
+    }
+    /// Requests received for the method.
+    pub fn calls(&self, nsid: &str) -> Vec<Call> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.nsid == nsid)
+            .cloned()
+            .collect()
+    }
+    /// Procedures received, such as the records created.
+    pub fn writes(&self) -> Vec<Call> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.method == Method::POST)
+            .cloned()
+            .collect()
+    }
+    /// An agent signed in to the server as [`HANDLE`].
+    pub async fn agent(&self) -> Agent {
+        let store = MemorySessionStore::default();
+        store
+            .set(
+                (),
+                OutputData {
+                    access_jwt: String::from("access"),
+                    active: None,
+                    did: DID.parse().expect("invalid did"),
+                    did_doc: None,
+                    email: None,
+                    email_auth_factor: None,
+                    email_confirmed: None,
+                    handle: HANDLE.parse().expect("invalid handle"),
+                    refresh_jwt: String::from("refresh"),
+                    status: None,
+                }
+                .into(),
+            )
+            .await
+            .expect("failed to set session");
+        BskyAtpAgentBuilder::new(Client::new(self.clone()))
+            .store(store)
+            .build()
+            .await
+            .expect("failed to build agent")
+    }
+    /// A watcher of [`agent`](Self::agent), with the default intervals.
+    pub async fn watcher(&self) -> Watcher {
+        Watcher::new(Arc::new(self.agent().await), Config::default())
+    }
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("failed to lock state")
+    }
+    fn reply(&self, request: &Request<Vec<u8>>) -> Reply {
+        let nsid = request
+            .uri()
+            .path()
+            .trim_start_matches("/xrpc/")
+            .to_string();
+        let mut state = self.lock();
+        state.calls.push(Call {
+            method: request.method().clone(),
+            nsid: nsid.clone(),
+            query: request.uri().query().map(String::from),
+            input: serde_json::from_slice(request.body()).ok(),
+        });
+        match state.replies.get_mut(&nsid) {
+            Some(replies) if replies.len() > 1 => replies.pop_front(),
+            Some(replies) => replies.front().cloned(),
+            None => None,
+        }
+        .unwrap_or_else(|| {
+            Reply::Error(
+                StatusCode::NOT_IMPLEMENTED,
+                String::from("MethodNotImplemented"),
+            )
+        })
+    }
+}
+
+impl Transport for FakeServer {
+    fn send(&self, request: Request<Vec<u8>>) -> BoxFuture<'_, Result<Response<Vec<u8>>>> {
+        let (status, content_type, body) = match self.reply(&request) {
+            Reply::Output(output) => (
+                StatusCode::OK,
+                String::from("application/json"),
+                serde_json::to_vec(&output),
+            ),
+            Reply::Bytes(content_type, bytes) => (StatusCode::OK, content_type, Ok(bytes)),
+            Reply::Error(status, error) => (
+                status,
+                String::from("application/json"),
+                serde_json::to_vec(&json!({ "error": error })),
+            ),
+        };
+        Box::pin(async move {
+            Ok(Response::builder()
+                .status(status)
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body?)?)
+        })
+    }
+}
+
+pub fn profile(handle: &str, display_name: Option<&str>) -> ProfileViewBasic {
+    ProfileViewBasicData {
+        associated: None,
+        avatar: None,
+        created_at: None,
+        did: format!("did:fake:{handle}").parse().expect("invalid did"),
+        display_name: display_name.map(String::from),
+        handle: handle.parse().expect("invalid handle"),
+        labels: None,
+        pronouns: None,
+        status: None,
+        verification: None,
+        viewer: None,
+    }
+    .into()
+}
+
+/// A post of the author at the time, with the counts of replies, reposts and likes.
+///
+/// The CID is made from the record key, to tell the posts apart.
+pub fn post_view(author: ProfileViewBasic, rkey: &str, text: &str, time: &str) -> PostView {
+    let created_at = time.parse::<Datetime>().expect("invalid datetime");
+    PostViewData {
+        uri: format!("at://{}/app.bsky.feed.post/{rkey}", author.did.as_ref()),
+        author,
+        bookmark_count: None,
+        cid: Cid::new(cid::Cid::new_v1(
+            DAG_CBOR,
+            Multihash::wrap(IDENTITY, rkey.as_bytes()).expect("invalid multihash"),
+        )),
+        embed: None,
+        indexed_at: created_at.clone(),
+        labels: None,
+        like_count: Some(3),
+        quote_count: Some(0),
+        record: RecordData {
+            created_at,
+            embed: None,
+            entities: None,
+            facets: None,
+            labels: None,
+            langs: None,
+            reply: None,
+            tags: None,
+            text: text.to_string(),
+        }
+        .try_into_unknown()
+        .expect("failed to convert record"),
+        reply_count: Some(1),
+        repost_count: Some(0),
+        threadgate: None,
+        viewer: None,
+    }
+    .into()
+}
+
+pub fn feed_view_post(post: PostView) -> FeedViewPost {
+    FeedViewPostData {
+        feed_context: None,
+        post,
+        reason: None,
+        reply: None,
+        req_id: None,
+    }
+    .into()
+}
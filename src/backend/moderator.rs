@@ -0,0 +1,72 @@
+use super::client::Agent;
+use bsky_sdk::Result;
+use bsky_sdk::moderation::{ModerationPrefs, Moderator};
+use bsky_sdk::preference::Preferences;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Moderator of the account, shared by the feed, search, thread and notification watches of all
+/// its columns, as they share the `Watcher` of the account and its reconfigured copies.
+///
+/// Built again only when the moderation preferences, including the labelers, have changed,
+/// so that the label definitions are not fetched on every poll.
+#[derive(Default)]
+pub struct ModeratorCache {
+    cached: Mutex<Option<(ModerationPrefs, Arc<Moderator>)>>,
+}
+
+impl ModeratorCache {
+    pub async fn get(&self, agent: &Agent, preferences: &Preferences) -> Result<Arc<Moderator>> {
+        // held while building, to build only once for the watches polling at the same time
+        let mut cached = self.cached.lock().await;
+        if let Some((prefs, moderator)) = cached.as_ref() {
+            if *prefs == preferences.moderation_prefs {
+                return Ok(moderator.clone());
+            }
+        }
+        let moderator = Arc::new(agent.moderator(preferences).await?);
+        cached.replace((preferences.moderation_prefs.clone(), moderator.clone()));
+        Ok(moderator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fake::FakeServer;
+    use super::*;
+    use bsky_sdk::moderation::ModerationPrefsLabeler;
+    use serde_json::json;
+
+    const GET_SERVICES: &str = "app.bsky.labeler.getServices";
+
+    #[tokio::test]
+    async fn rebuild_on_change() {
+        let server = FakeServer::default();
+        server.respond(GET_SERVICES, json!({ "views": [] }));
+        let agent = server.agent().await;
+        let cache = ModeratorCache::default();
+        let mut preferences = Preferences::default();
+        preferences
+            .moderation_prefs
+            .labelers
+            .push(ModerationPrefsLabeler::default());
+        cache
+            .get(&agent, &preferences)
+            .await
+            .expect("failed to get");
+        // other preferences do not matter
+        preferences.saved_feeds.clear();
+        preferences.feed_view_prefs.clear();
+        cache
+            .get(&agent, &preferences)
+            .await
+            .expect("failed to get");
+        assert_eq!(server.calls(GET_SERVICES).len(), 1);
+        preferences.moderation_prefs.adult_content_enabled = true;
+        cache
+            .get(&agent, &preferences)
+            .await
+            .expect("failed to get");
+        assert_eq!(server.calls(GET_SERVICES).len(), 2);
+    }
+}
@@ -0,0 +1,205 @@
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// State of the requests made by the watches of a `Watcher`, to show in the status line.
+#[derive(Debug, Default)]
+pub struct Status {
+    /// Incremented on every change, to find out whether to redraw.
+    version: AtomicU64,
+    in_flight: AtomicUsize,
+    unread: Mutex<Option<usize>>,
+    watches: Mutex<BTreeMap<String, Watched>>,
+    error: Mutex<Option<(DateTime<Local>, String)>>,
+}
+
+/// The running watches of a name, and the time of their last successful request.
+#[derive(Debug, Default)]
+struct Watched {
+    count: usize,
+    refreshed: Option<DateTime<Local>>,
+}
+
+/// Keeps the watch in the status while it runs, and removes its refresh time when the last one of the name is dropped.
+#[derive(Debug)]
+pub struct Watching {
+    status: Arc<Status>,
+    name: String,
+}
+
+impl Drop for Watching {
+    fn drop(&mut self) {
+        if let Ok(mut watches) = self.status.watches.lock() {
+            if let Some(watched) = watches.get_mut(&self.name) {
+                watched.count -= 1;
+                if watched.count == 0 {
+                    watches.remove(&self.name);
+                }
+            }
+        }
+        self.status.changed();
+    }
+}
+
+impl Status {
+    /// Mark the watch of the name as running until the returned guard is dropped.
+    pub fn watch(self: &Arc<Self>, name: &str) -> Watching {
+        if let Ok(mut watches) = self.watches.lock() {
+            watches.entry(name.to_string()).or_default().count += 1;
+        }
+        Watching {
+            status: self.clone(),
+            name: name.to_string(),
+        }
+    }
+    /// Count the request as in flight while it runs, and record when it succeeded or why it failed.
+    ///
+    /// The time is recorded only while a watch of the name runs, so that a request finishing
+    /// after its watch has stopped does not bring back its name.
+    pub async fn track<T, E, F>(&self, name: &str, request: F) -> Result<T, E>
+    where
+        E: Display,
+        F: Future<Output = Result<T, E>>,
+    {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.changed();
+        let result = request.await;
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        match &result {
+            Ok(_) => {
+                if let Ok(mut watches) = self.watches.lock() {
+                    if let Some(watched) = watches.get_mut(name) {
+                        watched.refreshed = Some(Local::now());
+                    }
+                }
+            }
+            Err(e) => {
+                if let Ok(mut error) = self.error.lock() {
+                    error.replace((Local::now(), format!("{name}: {e}")));
+                }
+            }
+        }
+        self.changed();
+        result
+    }
+    pub fn set_unread(&self, count: usize) {
+        if let Ok(mut unread) = self.unread.lock() {
+            if unread.replace(count) != Some(count) {
+                self.changed();
+            }
+        }
+    }
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+    pub fn unread(&self) -> Option<usize> {
+        self.unread.lock().ok().and_then(|unread| *unread)
+    }
+    /// Time of the last successful request of each running watch, by name.
+    ///
+    /// The feeds are named after their source, so that each one has its own time.
+    pub fn refreshed(&self) -> Vec<(String, DateTime<Local>)> {
+        self.watches
+            .lock()
+            .map(|watches| {
+                watches
+                    .iter()
+                    .filter_map(|(name, watched)| Some((name.clone(), watched.refreshed?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    pub fn error(&self) -> Option<(DateTime<Local>, String)> {
+        self.error.lock().ok().and_then(|error| error.clone())
+    }
+    fn changed(&self) {
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn track() {
+        let status = Arc::new(Status::default());
+        let _watching = ["feed", "notifications", "search tuisky"].map(|name| status.watch(name));
+        assert_eq!(status.version(), 0);
+        let result = status
+            .track("feed", async {
+                assert_eq!(status.in_flight(), 1);
+                Ok::<_, String>(42)
+            })
+            .await;
+        assert_eq!(result, Ok(42));
+        assert_eq!(status.in_flight(), 0);
+        assert_eq!(status.version(), 2);
+        assert_eq!(
+            status
+                .refreshed()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            vec!["feed"]
+        );
+        assert!(status.error().is_none());
+
+        let result = status
+            .track("notifications", async { Err::<(), _>("timed out") })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(status.refreshed().len(), 1);
+
+        // each name has its own time
+        let time = status.refreshed()[0].1;
+        status
+            .track("search tuisky", async { Ok::<_, String>(()) })
+            .await
+            .expect("failed");
+        let refreshed = status.refreshed();
+        assert_eq!(
+            refreshed.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec!["feed", "search tuisky"]
+        );
+        assert_eq!(refreshed[0].1, time);
+        assert_eq!(
+            status.error().map(|(_, message)| message),
+            Some(String::from("notifications: timed out"))
+        );
+    }
+
+    #[tokio::test]
+    async fn watch() {
+        let status = Arc::new(Status::default());
+        let (first, second) = (status.watch("following"), status.watch("following"));
+        let ok = || async { Ok::<_, String>(()) };
+        status.track("following", ok()).await.expect("failed");
+        // not recorded without a running watch
+        status.track("post thread", ok()).await.expect("failed");
+        assert_eq!(status.refreshed().len(), 1);
+        // kept until the last watch of the name stops
+        drop(first);
+        assert_eq!(status.refreshed().len(), 1);
+        drop(second);
+        assert!(status.refreshed().is_empty());
+        status.track("following", ok()).await.expect("failed");
+        assert!(status.refreshed().is_empty());
+    }
+
+    #[test]
+    fn unread() {
+        let status = Status::default();
+        assert_eq!(status.unread(), None);
+        status.set_unread(3);
+        status.set_unread(3);
+        assert_eq!(status.unread(), Some(3));
+        assert_eq!(status.version(), 1);
+    }
+}
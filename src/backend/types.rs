@@ -1,6 +1,7 @@
-use bsky_sdk::api::app::bsky::actor::defs::SavedFeed;
+use bsky_sdk::api::app::bsky::actor::defs::{ProfileViewDetailed, SavedFeed};
 use bsky_sdk::api::app::bsky::feed::defs::GeneratorView;
 use bsky_sdk::api::app::bsky::graph::defs::ListView;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct PinnedFeed {
@@ -9,9 +10,25 @@ pub struct PinnedFeed {
     pub info: FeedSourceInfo,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FeedSourceInfo {
     Feed(Box<GeneratorView>),
     List(Box<ListView>),
     Timeline(String),
+    Author(Box<ProfileViewDetailed>),
+    /// Posts matching a search query.
+    Search(String),
+}
+
+impl FeedSourceInfo {
+    /// Short name of the source, to tell the feeds apart in the status line.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Feed(generator_view) => generator_view.display_name.clone(),
+            Self::List(list_view) => list_view.name.clone(),
+            Self::Timeline(value) => value.clone(),
+            Self::Author(profile_view) => profile_view.handle.as_str().to_string(),
+            Self::Search(query) => format!("search {query}"),
+        }
+    }
 }
@@ -1,6 +1,10 @@
+use super::client::Agent;
 use super::config::Config;
-use bsky_sdk::BskyAgent;
+use super::moderator::ModeratorCache;
+use super::status::Status;
+use super::watches::{SharedPreferences, SharedUnreadCount};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::watch;
 
 pub trait Watch {
@@ -12,12 +16,58 @@ pub trait Watch {
 }
 
 pub struct Watcher {
-    pub agent: Arc<BskyAgent>,
+    pub agent: Arc<Agent>,
     pub(crate) config: Config,
+    pub status: Arc<Status>,
+    pub(super) preferences: Arc<SharedPreferences>,
+    pub(super) unread_count: Arc<SharedUnreadCount>,
+    pub(super) moderator: Arc<ModeratorCache>,
 }
 
 impl Watcher {
-    pub fn new(agent: Arc<BskyAgent>, config: Config) -> Self {
-        Self { agent, config }
+    pub fn new(agent: Arc<Agent>, config: Config) -> Self {
+        Self::with_state(agent, config, Arc::default(), Arc::default())
+    }
+    /// Number of the handles subscribed to the unread count of the watcher.
+    #[cfg(test)]
+    pub fn unread_count_subscribers(&self) -> usize {
+        self.unread_count.subscribers()
+    }
+    /// A watcher with the new config, keeping the status and the moderator.
+    ///
+    /// The preferences and the unread count are polled separately with the new intervals,
+    /// by the watches subscribed to the new watcher.
+    pub fn reconfigure(&self, config: Config) -> Self {
+        Self::with_state(
+            self.agent.clone(),
+            config,
+            self.status.clone(),
+            self.moderator.clone(),
+        )
+    }
+    fn with_state(
+        agent: Arc<Agent>,
+        config: Config,
+        status: Arc<Status>,
+        moderator: Arc<ModeratorCache>,
+    ) -> Self {
+        let preferences = Arc::new(SharedPreferences::new(
+            agent.clone(),
+            status.clone(),
+            Duration::from_secs(config.intervals.preferences),
+        ));
+        let unread_count = Arc::new(SharedUnreadCount::new(
+            agent.clone(),
+            status.clone(),
+            Duration::from_secs(config.intervals.notifications),
+        ));
+        Self {
+            agent,
+            config,
+            status,
+            preferences,
+            unread_count,
+            moderator,
+        }
     }
 }
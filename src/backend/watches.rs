@@ -1,4 +1,9 @@
 mod feed;
+mod notifications;
 mod pinned_feeds;
 mod post_thread;
 mod preferences;
+mod unread_count;
+
+pub(super) use preferences::SharedPreferences;
+pub(super) use unread_count::SharedUnreadCount;
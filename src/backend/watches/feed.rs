@@ -1,14 +1,16 @@
+use super::super::client::Agent;
+use super::super::moderator::ModeratorCache;
 use super::super::types::FeedSourceInfo;
-use super::super::{Watch, Watcher};
+use super::super::{Status, Watch, Watcher};
 use bsky_sdk::Result;
 use bsky_sdk::api::app::bsky::feed::defs::{
-    FeedViewPost, FeedViewPostReasonRefs, PostViewEmbedRefs, ReplyRefParentRefs,
+    FeedViewPost, FeedViewPostData, FeedViewPostReasonRefs, PostViewEmbedRefs, ReplyRefParentRefs,
 };
 use bsky_sdk::api::types::Union;
 use bsky_sdk::api::types::string::Cid;
 use bsky_sdk::moderation::decision::DecisionContext;
+use bsky_sdk::preference::Preferences;
 use bsky_sdk::preference::{FeedViewPreference, FeedViewPreferenceData};
-use bsky_sdk::{BskyAgent, preference::Preferences};
 use indexmap::IndexMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -24,6 +26,8 @@ impl Watcher {
         FeedWatcher {
             feed_info,
             agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
             preferences: self.preferences(),
             period: Duration::from_secs(self.config.intervals.feed),
             tx,
@@ -34,7 +38,9 @@ impl Watcher {
 
 pub struct FeedWatcher<W> {
     feed_info: FeedSourceInfo,
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
     preferences: W,
     period: Duration,
     tx: broadcast::Sender<()>,
@@ -51,13 +57,18 @@ where
         let (tx, rx) = watch::channel(Default::default());
         let updater = Updater {
             agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
             current: self.current.clone(),
             feed_info: Arc::new(self.feed_info.clone()),
             tx,
         };
         let (mut preferences, mut quit) = (self.preferences.subscribe(), self.tx.subscribe());
         let mut interval = time::interval(self.period);
+        let watching = self.status.watch(&self.feed_info.name());
         tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
             // skip the first tick
             interval.tick().await;
             loop {
@@ -103,7 +114,9 @@ where
 
 #[derive(Clone)]
 struct Updater {
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
     current: Arc<Mutex<IndexMap<Cid, FeedViewPost>>>,
     feed_info: Arc<FeedSourceInfo>,
     tx: watch::Sender<Vec<FeedViewPost>>,
@@ -111,7 +124,11 @@ struct Updater {
 
 impl Updater {
     async fn update(&self, preferences: &Preferences) {
-        match self.calculate_feed(preferences).await {
+        match self
+            .status
+            .track(&self.feed_info.name(), self.calculate_feed(preferences))
+            .await
+        {
             Ok(feed) => {
                 self.tx.send(feed).ok();
             }
@@ -121,8 +138,10 @@ impl Updater {
         }
     }
     async fn calculate_feed(&self, preferences: &Preferences) -> Result<Vec<FeedViewPost>> {
-        // TODO: It should not be necessary to get moderator every time unless moderation_prefs has been changed?
-        let (moderator, feed) = tokio::join!(self.agent.moderator(preferences), self.get_feed());
+        let (moderator, feed) = tokio::join!(
+            self.moderator.get(&self.agent, preferences),
+            self.get_feed()
+        );
         let moderator = moderator?;
         let mut feed = feed?;
         feed.reverse();
@@ -187,6 +206,64 @@ impl Updater {
                     .data
                     .feed
             }
+            FeedSourceInfo::Author(profile_view) => {
+                self.agent
+                    .api
+                    .app
+                    .bsky
+                    .feed
+                    .get_author_feed(
+                        bsky_sdk::api::app::bsky::feed::get_author_feed::ParametersData {
+                            actor: profile_view.did.clone().into(),
+                            cursor: None,
+                            filter: None,
+                            include_pins: None,
+                            limit: 30.try_into().ok(),
+                        }
+                        .into(),
+                    )
+                    .await?
+                    .data
+                    .feed
+            }
+            FeedSourceInfo::Search(query) => self
+                .agent
+                .api
+                .app
+                .bsky
+                .feed
+                .search_posts(
+                    bsky_sdk::api::app::bsky::feed::search_posts::ParametersData {
+                        author: None,
+                        cursor: None,
+                        domain: None,
+                        lang: None,
+                        limit: 30.try_into().ok(),
+                        mentions: None,
+                        q: query.clone(),
+                        since: None,
+                        sort: Some(String::from("latest")),
+                        tag: None,
+                        until: None,
+                        url: None,
+                    }
+                    .into(),
+                )
+                .await?
+                .data
+                .posts
+                .into_iter()
+                .map(|post| {
+                    FeedViewPostData {
+                        feed_context: None,
+                        post,
+                        reason: None,
+                        reply: None,
+                        req_id: None,
+                    }
+                    .into()
+                })
+                .collect(),
             FeedSourceInfo::Timeline(_) => {
                 self.agent
                     .api
@@ -275,11 +352,37 @@ fn filter_feed(feed_view_post: &FeedViewPost, pref: &FeedViewPreference) -> bool
 
 #[cfg(test)]
 mod tests {
+    use super::super::super::fake::{self, FakeServer, post_view, profile};
     use super::*;
     use bsky_sdk::api::app::bsky::actor::defs::{ProfileViewBasic, ProfileViewBasicData};
     use bsky_sdk::api::app::bsky::feed::defs::{FeedViewPostData, PostViewData, ReasonRepostData};
     use bsky_sdk::api::types::{Unknown, string::Datetime};
+    use bsky_sdk::api::xrpc::http::StatusCode;
+    use serde_json::json;
     use std::collections::BTreeMap;
+    use tokio::time::Instant;
+
+    const GET_TIMELINE: &str = "app.bsky.feed.getTimeline";
+
+    fn server() -> FakeServer {
+        let server = FakeServer::default();
+        server.respond(
+            "app.bsky.actor.getPreferences",
+            json!({ "preferences": [] }),
+        );
+        server.respond("app.bsky.labeler.getServices", json!({ "views": [] }));
+        server
+    }
+
+    fn post(rkey: &str, time: &str) -> FeedViewPost {
+        fake::feed_view_post(post_view(profile("bob.test", None), rkey, rkey, time))
+    }
+
+    fn rkeys(feed: &[FeedViewPost]) -> Vec<&str> {
+        feed.iter()
+            .filter_map(|feed_view_post| feed_view_post.post.uri.rsplit('/').next())
+            .collect()
+    }
 
     fn feed_view_post(cid: Cid, reason_indexed_at: Option<Datetime>) -> FeedViewPost {
         fn profile_view_basic() -> ProfileViewBasic {
@@ -414,4 +517,142 @@ mod tests {
         assert!(feed_map[1].reason.is_none());
         assert!(feed_map[2].reason.is_some());
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_timeline() {
+        let server = server();
+        let (first, second) = (
+            post("first", "2024-10-01T00:00:00.000Z"),
+            post("second", "2024-10-01T01:00:00.000Z"),
+        );
+        server.respond(GET_TIMELINE, json!({ "feed": [first] }));
+        server.respond(GET_TIMELINE, json!({ "feed": [second, first] }));
+        let watcher = server.watcher().await;
+        let watch = watcher.feed(FeedSourceInfo::Timeline(String::from("following")));
+        let mut rx = watch.subscribe();
+        let start = Instant::now();
+        // fetched as soon as the preferences are
+        rx.changed().await.expect("channel closed");
+        assert_eq!(rkeys(&rx.borrow_and_update()), ["first"]);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        // newer posts are merged on the next interval
+        rx.changed().await.expect("channel closed");
+        assert_eq!(rkeys(&rx.borrow_and_update()), ["second", "first"]);
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+        let calls = server.calls(GET_TIMELINE);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].param("limit"), Some("30"));
+        // each poll reads the newest posts, without paging back
+        assert!(calls.iter().all(|call| call.param("cursor").is_none()));
+        // the moderator is built once for the same preferences
+        assert_eq!(server.calls("app.bsky.labeler.getServices").len(), 1);
+        assert!(server.writes().is_empty());
+        // no more requests after unsubscribing
+        watch.unsubscribe();
+        time::sleep(Duration::from_secs(300)).await;
+        assert_eq!(server.calls(GET_TIMELINE).len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refreshed_while_watched() {
+        let server = server();
+        server.respond(
+            GET_TIMELINE,
+            json!({ "feed": [post("first", "2024-10-01T00:00:00.000Z")] }),
+        );
+        let watcher = server.watcher().await;
+        let names = || {
+            watcher
+                .status
+                .refreshed()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        let watch = watcher.feed(FeedSourceInfo::Timeline(String::from("following")));
+        let mut rx = watch.subscribe();
+        rx.changed().await.expect("channel closed");
+        assert_eq!(names(), ["following", "preferences"]);
+        // gone from the status line after leaving the feed
+        watch.unsubscribe();
+        time::sleep(Duration::from_secs(1)).await;
+        assert!(names().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn moderate_per_account() {
+        let server = server();
+        server.respond(
+            GET_TIMELINE,
+            json!({ "feed": [post("first", "2024-10-01T00:00:00.000Z")] }),
+        );
+        // feeds of the columns sharing the watcher of the account, even after reconfiguring it
+        let watcher = server.watcher().await;
+        let mut config = watcher.config.clone();
+        config.intervals.feed += 1;
+        let reconfigured = watcher.reconfigure(config);
+        let watches = [&watcher, &watcher, &reconfigured]
+            .map(|watcher| watcher.feed(FeedSourceInfo::Timeline(String::from("following"))));
+        for watch in &watches {
+            watch.subscribe().changed().await.expect("channel closed");
+        }
+        assert_eq!(server.calls(GET_TIMELINE).len(), 3);
+        // and the thread and the notifications of the account
+        let first = post("first", "2024-10-01T00:00:00.000Z");
+        server.respond(
+            "app.bsky.feed.getPostThread",
+            json!({
+                "thread": {
+                    "$type": "app.bsky.feed.defs#threadViewPost",
+                    "post": first.post,
+                },
+            }),
+        );
+        server.respond(
+            "app.bsky.notification.listNotifications",
+            json!({ "notifications": [] }),
+        );
+        let thread = watcher.post_thread(first.post.uri.clone());
+        thread.subscribe().changed().await.expect("channel closed");
+        let notifications = reconfigured.notifications();
+        notifications
+            .subscribe()
+            .changed()
+            .await
+            .expect("channel closed");
+        assert_eq!(server.calls("app.bsky.labeler.getServices").len(), 1);
+        for watch in &watches {
+            watch.unsubscribe();
+        }
+        thread.unsubscribe();
+        notifications.unsubscribe();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_after_error() {
+        let server = server();
+        server.fail(
+            GET_TIMELINE,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "InternalServerError",
+        );
+        server.respond(
+            GET_TIMELINE,
+            json!({ "feed": [post("first", "2024-10-01T00:00:00.000Z")] }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.feed(FeedSourceInfo::Timeline(String::from("following")));
+        let mut rx = watch.subscribe();
+        let start = Instant::now();
+        rx.changed().await.expect("channel closed");
+        assert_eq!(rkeys(&rx.borrow_and_update()), ["first"]);
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+        assert!(
+            watcher
+                .status
+                .error()
+                .is_some_and(|(_, message)| message.starts_with("following: "))
+        );
+        watch.unsubscribe();
+    }
 }
@@ -0,0 +1,219 @@
+use super::super::client::Agent;
+use super::super::moderator::ModeratorCache;
+use super::super::{Status, Watch, Watcher};
+use bsky_sdk::Result;
+use bsky_sdk::api::app::bsky::notification::list_notifications::Notification;
+use bsky_sdk::moderation::decision::DecisionContext;
+use bsky_sdk::preference::Preferences;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio::time;
+
+impl Watcher {
+    pub fn notifications(&self) -> impl Watch<Output = Vec<Notification>> + use<> {
+        let (tx, _) = broadcast::channel(1);
+        NotificationsWatcher {
+            agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
+            preferences: self.preferences(),
+            period: Duration::from_secs(self.config.intervals.notifications),
+            tx,
+        }
+    }
+}
+
+pub struct NotificationsWatcher<W> {
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
+    preferences: W,
+    period: Duration,
+    tx: broadcast::Sender<()>,
+}
+
+impl<W> Watch for NotificationsWatcher<W>
+where
+    W: Watch<Output = Preferences>,
+{
+    type Output = Vec<Notification>;
+
+    fn subscribe(&self) -> watch::Receiver<Self::Output> {
+        let (tx, rx) = watch::channel(Default::default());
+        let updater = Updater {
+            agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
+            tx,
+        };
+        let (mut preferences, mut quit) = (self.preferences.subscribe(), self.tx.subscribe());
+        let mut interval = time::interval(self.period);
+        let watching = self.status.watch("notifications");
+        tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
+            loop {
+                let tick = interval.tick();
+                tokio::select! {
+                    changed = preferences.changed() => {
+                        if changed.is_ok() {
+                            let preferences = preferences.borrow_and_update().clone();
+                            let updater = updater.clone();
+                            tokio::spawn(async move {
+                                updater.update(&preferences).await;
+                            });
+                        } else {
+                            break log::warn!("preferences channel closed");
+                        }
+                    }
+                    _ = tick => {
+                        let preferences = preferences.borrow().clone();
+                        let updater = updater.clone();
+                        tokio::spawn(async move {
+                            updater.update(&preferences).await;
+                        });
+                    }
+                    _ = quit.recv() => {
+                        break;
+                    }
+                }
+            }
+        });
+        rx
+    }
+    fn unsubscribe(&self) {
+        if let Err(e) = self.tx.send(()) {
+            log::error!("failed to send quit: {e}");
+        }
+        self.preferences.unsubscribe();
+    }
+    fn refresh(&self) {
+        self.preferences.refresh();
+    }
+}
+
+#[derive(Clone)]
+struct Updater {
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
+    tx: watch::Sender<Vec<Notification>>,
+}
+
+impl Updater {
+    async fn update(&self, preferences: &Preferences) {
+        match self
+            .status
+            .track("notifications", self.moderated_notifications(preferences))
+            .await
+        {
+            Ok(notifications) => {
+                self.tx.send(notifications).ok();
+            }
+            Err(e) => {
+                log::warn!("failed to list notifications: {e}");
+            }
+        }
+    }
+    /// The notifications without the ones that the moderation hides from lists.
+    async fn moderated_notifications(
+        &self,
+        preferences: &Preferences,
+    ) -> Result<Vec<Notification>> {
+        let (moderator, notifications) = tokio::join!(
+            self.moderator.get(&self.agent, preferences),
+            self.list_notifications()
+        );
+        let moderator = moderator?;
+        let mut notifications = notifications?;
+        notifications.retain(|notification| {
+            !moderator
+                .moderate_notification(notification)
+                .ui(DecisionContext::ContentList)
+                .filter()
+        });
+        Ok(notifications)
+    }
+    async fn list_notifications(&self) -> Result<Vec<Notification>> {
+        Ok(self
+            .agent
+            .api
+            .app
+            .bsky
+            .notification
+            .list_notifications(
+                bsky_sdk::api::app::bsky::notification::list_notifications::ParametersData {
+                    cursor: None,
+                    limit: 50.try_into().ok(),
+                    priority: None,
+                    reasons: None,
+                    seen_at: None,
+                }
+                .into(),
+            )
+            .await?
+            .data
+            .notifications)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fake::{FakeServer, profile};
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test(start_paused = true)]
+    async fn moderate_notifications() {
+        let server = FakeServer::default();
+        server.respond(
+            "app.bsky.actor.getPreferences",
+            json!({ "preferences": [] }),
+        );
+        server.respond("app.bsky.labeler.getServices", json!({ "views": [] }));
+        let notification = |handle: &str, muted: bool| {
+            let mut author =
+                serde_json::to_value(profile(handle, None)).expect("failed to serialize profile");
+            author["viewer"] = json!({ "muted": muted });
+            json!({
+                "uri": format!("at://did:fake:{handle}/app.bsky.feed.like/3klike"),
+                "cid": "bafyreiclp443lavogvhj3d2ob2cxbfuscni2k5jk7bebjzg7khl3esabwq",
+                "author": author,
+                "reason": "like",
+                "record": {
+                    "$type": "app.bsky.feed.like",
+                    "createdAt": "2024-10-01T00:00:00.000Z",
+                    "subject": {
+                        "uri": "at://did:fake:alice.test/app.bsky.feed.post/3kpost",
+                        "cid": "bafyreiclp443lavogvhj3d2ob2cxbfuscni2k5jk7bebjzg7khl3esabwq",
+                    },
+                },
+                "isRead": false,
+                "indexedAt": "2024-10-01T00:00:00.000Z",
+            })
+        };
+        server.respond(
+            "app.bsky.notification.listNotifications",
+            json!({
+                "notifications": [
+                    notification("bob.test", false),
+                    notification("carol.test", true),
+                ],
+            }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.notifications();
+        let mut rx = watch.subscribe();
+        rx.changed().await.expect("channel closed");
+        // the notification of the muted account is hidden
+        assert_eq!(
+            rx.borrow_and_update()
+                .iter()
+                .map(|notification| notification.author.handle.as_str())
+                .collect::<Vec<_>>(),
+            ["bob.test"]
+        );
+        watch.unsubscribe();
+    }
+}
@@ -1,8 +1,9 @@
+use super::super::client::Agent;
 use super::super::types::{FeedSourceInfo, PinnedFeed};
-use super::super::{Watch, Watcher};
+use super::super::{Status, Watch, Watcher};
+use bsky_sdk::Result;
 use bsky_sdk::api::app::bsky::actor::defs::SavedFeed;
 use bsky_sdk::preference::Preferences;
-use bsky_sdk::{BskyAgent, Result};
 use futures_util::future;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -14,6 +15,7 @@ impl Watcher {
         let (tx, _) = broadcast::channel(1);
         PinnedFeedsWatcher {
             agent: self.agent.clone(),
+            status: self.status.clone(),
             preferences: self.preferences(),
             tx,
         }
@@ -21,7 +23,8 @@ impl Watcher {
 }
 
 pub struct PinnedFeedsWatcher<W> {
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    status: Arc<Status>,
     preferences: W,
     tx: broadcast::Sender<()>,
 }
@@ -34,18 +37,21 @@ where
 
     fn subscribe(&self) -> tokio::sync::watch::Receiver<Self::Output> {
         let (tx, rx) = watch::channel(Default::default());
-        let agent = self.agent.clone();
+        let (agent, status) = (self.agent.clone(), self.status.clone());
         let mut quit = self.tx.subscribe();
         let mut preferences = self.preferences.subscribe();
+        let watching = self.status.watch("pinned feeds");
         tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
             loop {
                 tokio::select! {
                     changed = preferences.changed() => {
                         if changed.is_ok() {
                             let saved_feeds = preferences.borrow_and_update().saved_feeds.clone();
-                            let (agent, tx) = (agent.clone(), tx.clone());
+                            let (agent, status, tx) = (agent.clone(), status.clone(), tx.clone());
                             tokio::spawn(async move {
-                                update(&agent, &saved_feeds, &tx).await;
+                                update(&agent, &status, &saved_feeds, &tx).await;
                             });
                         } else {
                             break log::warn!("preferences channel closed");
@@ -70,8 +76,16 @@ where
     }
 }
 
-async fn update(agent: &BskyAgent, saved_feeds: &[SavedFeed], tx: &Sender<Vec<PinnedFeed>>) {
-    match collect_feeds(agent, saved_feeds).await {
+async fn update(
+    agent: &Agent,
+    status: &Status,
+    saved_feeds: &[SavedFeed],
+    tx: &Sender<Vec<PinnedFeed>>,
+) {
+    match status
+        .track("pinned feeds", collect_feeds(agent, saved_feeds))
+        .await
+    {
         Ok(feeds) => {
             tx.send(feeds).ok();
         }
@@ -81,7 +95,7 @@ async fn update(agent: &BskyAgent, saved_feeds: &[SavedFeed], tx: &Sender<Vec<Pi
     }
 }
 
-async fn collect_feeds(agent: &BskyAgent, saved_feeds: &[SavedFeed]) -> Result<Vec<PinnedFeed>> {
+async fn collect_feeds(agent: &Agent, saved_feeds: &[SavedFeed]) -> Result<Vec<PinnedFeed>> {
     let (mut feeds, mut lists) = (Vec::new(), Vec::new());
     for feed in saved_feeds.iter().filter(|feed| feed.pinned) {
         match feed.r#type.as_str() {
@@ -163,3 +177,47 @@ async fn collect_feeds(agent: &BskyAgent, saved_feeds: &[SavedFeed]) -> Result<V
     }
     Ok(ret)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fake::FakeServer;
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_pinned_feeds() {
+        let server = FakeServer::default();
+        server.respond(
+            "app.bsky.actor.getPreferences",
+            json!({
+                "preferences": [{
+                    "$type": "app.bsky.actor.defs#savedFeedsPrefV2",
+                    "items": [
+                        {
+                            "id": "3l6ovcmm2vd2j",
+                            "pinned": true,
+                            "type": "timeline",
+                            "value": "following",
+                        },
+                        {
+                            "id": "3l6ovcmm2vd2k",
+                            "pinned": false,
+                            "type": "feed",
+                            "value": "at://did:fake:bob.test/app.bsky.feed.generator/cats",
+                        },
+                    ],
+                }],
+            }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.pinned_feeds();
+        let mut rx = watch.subscribe();
+        rx.changed().await.expect("channel closed");
+        let feeds = rx.borrow_and_update().clone();
+        assert_eq!(feeds.len(), 1);
+        assert!(matches!(&feeds[0].info, FeedSourceInfo::Timeline(value) if value == "following"));
+        // feeds which are not pinned are not resolved
+        assert!(server.calls("app.bsky.feed.getFeedGenerators").is_empty());
+        watch.unsubscribe();
+    }
+}
@@ -1,9 +1,15 @@
-use super::super::{Watch, Watcher};
-use bsky_sdk::api::app::bsky::feed::defs::NotFoundPostData;
+use super::super::client::Agent;
+use super::super::moderator::ModeratorCache;
+use super::super::{Status, Watch, Watcher};
+use bsky_sdk::Result;
+use bsky_sdk::api::app::bsky::feed::defs::{
+    NotFoundPostData, ThreadViewPost, ThreadViewPostRepliesItem,
+};
 use bsky_sdk::api::app::bsky::feed::get_post_thread::OutputThreadRefs;
 use bsky_sdk::api::types::Union;
+use bsky_sdk::moderation::Moderator;
+use bsky_sdk::moderation::decision::DecisionContext;
 use bsky_sdk::preference::Preferences;
-use bsky_sdk::{BskyAgent, Result};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, watch};
@@ -15,6 +21,8 @@ impl Watcher {
         PostThreadWatcher {
             uri,
             agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
             preferences: self.preferences(),
             period: Duration::from_secs(self.config.intervals.post_thread),
             tx,
@@ -24,7 +32,9 @@ impl Watcher {
 
 pub struct PostThreadWatcher<W> {
     uri: String,
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
     preferences: W,
     period: Duration,
     tx: broadcast::Sender<()>,
@@ -47,29 +57,36 @@ where
         let (tx, rx) = watch::channel(init);
         let updater = Updater {
             agent: self.agent.clone(),
+            status: self.status.clone(),
+            moderator: self.moderator.clone(),
             uri: self.uri.clone(),
             tx: tx.clone(),
         };
         let (mut preferences, mut quit) = (self.preferences.subscribe(), self.tx.subscribe());
         let mut interval = time::interval(self.period);
+        let watching = self.status.watch("post thread");
         tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
             loop {
                 let tick = interval.tick();
                 tokio::select! {
                     changed = preferences.changed() => {
                         if changed.is_ok() {
+                            let preferences = preferences.borrow_and_update().clone();
                             let updater = updater.clone();
                             tokio::spawn(async move {
-                                updater.update().await;
+                                updater.update(&preferences).await;
                             });
                         } else {
                             break log::warn!("preferences channel closed");
                         }
                     }
                     _ = tick => {
+                        let preferences = preferences.borrow().clone();
                         let updater = updater.clone();
                         tokio::spawn(async move {
-                            updater.update().await;
+                            updater.update(&preferences).await;
                         });
                     }
                     _ = quit.recv() => {
@@ -93,14 +110,20 @@ where
 
 #[derive(Clone)]
 struct Updater {
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    moderator: Arc<ModeratorCache>,
     uri: String,
     tx: watch::Sender<Union<OutputThreadRefs>>,
 }
 
 impl Updater {
-    async fn update(&self) {
-        match self.get_post_thread().await {
+    async fn update(&self, preferences: &Preferences) {
+        match self
+            .status
+            .track("post thread", self.moderated_post_thread(preferences))
+            .await
+        {
             Ok(thread) => {
                 if let Err(e) = self.tx.send(thread.clone()) {
                     log::warn!("failed to send post thread: {e}");
@@ -111,6 +134,24 @@ impl Updater {
             }
         }
     }
+    /// The thread without the replies that the moderation hides from lists.
+    async fn moderated_post_thread(
+        &self,
+        preferences: &Preferences,
+    ) -> Result<Union<OutputThreadRefs>> {
+        let (moderator, thread) = tokio::join!(
+            self.moderator.get(&self.agent, preferences),
+            self.get_post_thread()
+        );
+        let moderator = moderator?;
+        let mut thread = thread?;
+        if let Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(thread_view_post)) =
+            &mut thread
+        {
+            filter_replies(thread_view_post, &moderator);
+        }
+        Ok(thread)
+    }
     async fn get_post_thread(&self) -> Result<Union<OutputThreadRefs>> {
         Ok(self
             .agent
@@ -131,3 +172,139 @@ impl Updater {
             .thread)
     }
 }
+
+/// Remove the replies filtered by the moderator, and the replies to them, at every depth.
+fn filter_replies(thread_view_post: &mut ThreadViewPost, moderator: &Moderator) {
+    let Some(replies) = thread_view_post.replies.as_mut() else {
+        return;
+    };
+    replies.retain_mut(|reply| match reply {
+        Union::Refs(ThreadViewPostRepliesItem::ThreadViewPost(reply)) => {
+            if moderator
+                .moderate_post(&reply.post)
+                .ui(DecisionContext::ContentList)
+                .filter()
+            {
+                return false;
+            }
+            filter_replies(reply, moderator);
+            true
+        }
+        _ => true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fake::{FakeServer, post_view, profile};
+    use super::*;
+    use serde_json::{Value, json};
+
+    const GET_POST_THREAD: &str = "app.bsky.feed.getPostThread";
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_post_thread() {
+        let server = FakeServer::default();
+        server.respond(
+            "app.bsky.actor.getPreferences",
+            json!({ "preferences": [] }),
+        );
+        let post = post_view(
+            profile("bob.test", None),
+            "3kpost",
+            "hello",
+            "2024-10-01T00:00:00.000Z",
+        );
+        server.respond(
+            GET_POST_THREAD,
+            json!({
+                "thread": {
+                    "$type": "app.bsky.feed.defs#threadViewPost",
+                    "post": post,
+                    "replies": [],
+                },
+            }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.post_thread(post.uri.clone());
+        let mut rx = watch.subscribe();
+        rx.changed().await.expect("channel closed");
+        assert!(matches!(
+            &*rx.borrow_and_update(),
+            Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(thread))
+                if thread.post.uri == post.uri
+        ));
+        let calls = server.calls(GET_POST_THREAD);
+        assert_eq!(calls[0].param("depth"), Some("10"));
+        let calls = calls.len();
+        // refreshed on every interval
+        time::sleep(Duration::from_secs(59)).await;
+        assert_eq!(server.calls(GET_POST_THREAD).len(), calls);
+        time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(server.calls(GET_POST_THREAD).len(), calls + 1);
+        watch.unsubscribe();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn moderate_replies() {
+        let server = FakeServer::default();
+        server.respond(
+            "app.bsky.actor.getPreferences",
+            json!({ "preferences": [] }),
+        );
+        server.respond("app.bsky.labeler.getServices", json!({ "views": [] }));
+        let post = |handle: &str, rkey: &str, muted: bool| {
+            let mut post = serde_json::to_value(post_view(
+                profile(handle, None),
+                rkey,
+                rkey,
+                "2024-10-01T00:00:00.000Z",
+            ))
+            .expect("failed to serialize post");
+            post["author"]["viewer"] = json!({ "muted": muted });
+            post
+        };
+        let thread = |post, replies: Vec<Value>| {
+            json!({
+                "$type": "app.bsky.feed.defs#threadViewPost",
+                "post": post,
+                "replies": replies,
+            })
+        };
+        server.respond(
+            GET_POST_THREAD,
+            json!({
+                "thread": thread(
+                    post("bob.test", "3kpost", false),
+                    vec![
+                        thread(post("carol.test", "3kmuted", true), vec![]),
+                        thread(
+                            post("dave.test", "3kreply", false),
+                            vec![thread(post("carol.test", "3knested", true), vec![])],
+                        ),
+                    ],
+                ),
+            }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.post_thread(String::from(
+            "at://did:fake:bob.test/app.bsky.feed.post/3kpost",
+        ));
+        let mut rx = watch.subscribe();
+        rx.changed().await.expect("channel closed");
+        let Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(thread)) =
+            &*rx.borrow_and_update()
+        else {
+            panic!("not a thread");
+        };
+        // the replies of the muted account are hidden, at any depth
+        let replies = thread.replies.as_deref().unwrap_or_default();
+        assert_eq!(replies.len(), 1);
+        let Union::Refs(ThreadViewPostRepliesItem::ThreadViewPost(reply)) = &replies[0] else {
+            panic!("not a reply");
+        };
+        assert!(reply.post.uri.ends_with("/3kreply"));
+        assert_eq!(reply.replies.as_ref().map(Vec::len), Some(0));
+        watch.unsubscribe();
+    }
+}
@@ -1,16 +1,18 @@
-use super::super::{Watch, Watcher};
-use bsky_sdk::{preference::Preferences, BskyAgent};
-use std::{sync::Arc, time::Duration};
+use super::super::client::Agent;
+use super::super::{Status, Watch, Watcher};
+use bsky_sdk::preference::Preferences;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::{broadcast, watch};
 use tokio::time;
 
 impl Watcher {
+    /// A handle of the preferences shared by all watches of the watcher.
     pub fn preferences(&self) -> impl Watch<Output = Preferences> + use<> {
-        let (tx, _) = broadcast::channel(1);
         PreferencesWatcher {
-            agent: self.agent.clone(),
-            period: Duration::from_secs(self.config.intervals.preferences),
-            tx,
+            shared: self.preferences.clone(),
+            subscriptions: AtomicUsize::new(0),
         }
     }
 }
@@ -21,30 +23,89 @@ enum Command {
     Refresh,
 }
 
-struct PreferencesWatcher {
-    agent: Arc<BskyAgent>,
+/// Preferences fetched by one polling loop, running while any handle is subscribed to them.
+pub struct SharedPreferences {
+    agent: Arc<Agent>,
+    status: Arc<Status>,
     period: Duration,
-    tx: broadcast::Sender<Command>,
+    state: Mutex<Option<Running>>,
 }
 
-impl Watch for PreferencesWatcher {
-    type Output = Preferences;
+struct Running {
+    subscribers: usize,
+    /// Never marked as seen, so that its clones for new subscribers see the preferences already fetched.
+    rx: watch::Receiver<Preferences>,
+    command: broadcast::Sender<Command>,
+}
 
-    fn subscribe(&self) -> watch::Receiver<Self::Output> {
-        let agent = self.agent.clone();
-        let mut command = self.tx.subscribe();
-        let mut interval = time::interval(self.period);
+impl SharedPreferences {
+    pub fn new(agent: Arc<Agent>, status: Arc<Status>, period: Duration) -> Self {
+        Self {
+            agent,
+            status,
+            period,
+            state: Mutex::new(None),
+        }
+    }
+    fn subscribe(&self) -> watch::Receiver<Preferences> {
+        let Ok(mut state) = self.state.lock() else {
+            // closed at once, as if the loop had finished
+            log::error!("failed to lock preferences");
+            return watch::channel(Preferences::default()).1;
+        };
+        if let Some(running) = state.as_mut() {
+            running.subscribers += 1;
+            return running.rx.clone();
+        }
         let (tx, rx) = watch::channel(Preferences::default());
+        let (command, _) = broadcast::channel(1);
+        self.spawn(tx, command.subscribe());
+        state.replace(Running {
+            subscribers: 1,
+            rx: rx.clone(),
+            command,
+        });
+        rx
+    }
+    fn unsubscribe(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(running) = state.as_mut() {
+                running.subscribers -= 1;
+                if running.subscribers == 0 {
+                    if let Some(running) = state.take() {
+                        if let Err(e) = running.command.send(Command::Quit) {
+                            log::error!("failed to send quit command: {e}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+    fn refresh(&self) {
+        if let Ok(state) = self.state.lock() {
+            if let Some(running) = state.as_ref() {
+                if let Err(e) = running.command.send(Command::Refresh) {
+                    log::error!("failed to send refresh command: {e}");
+                }
+            }
+        }
+    }
+    fn spawn(&self, tx: watch::Sender<Preferences>, mut command: broadcast::Receiver<Command>) {
+        let (agent, status) = (self.agent.clone(), self.status.clone());
+        let watching = status.watch("preferences");
+        let mut interval = time::interval(self.period);
         tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
             loop {
                 let tick = interval.tick();
-                let (agent, tx) = (agent.clone(), tx.clone());
+                let (agent, status, tx) = (agent.clone(), status.clone(), tx.clone());
                 tokio::select! {
                     Ok(command) = command.recv() => {
                         match command {
                             Command::Refresh => {
                                 tokio::spawn(async move {
-                                    update(&agent, &tx).await;
+                                    update(&agent, &status, &tx).await;
                                 });
                             }
                             Command::Quit => {
@@ -54,29 +115,129 @@ impl Watch for PreferencesWatcher {
                     }
                     _ = tick => {
                         tokio::spawn(async move {
-                            update(&agent, &tx).await;
+                            update(&agent, &status, &tx).await;
                         });
                     }
                 }
             }
         });
-        rx
+    }
+}
+
+struct PreferencesWatcher {
+    shared: Arc<SharedPreferences>,
+    subscriptions: AtomicUsize,
+}
+
+impl Watch for PreferencesWatcher {
+    type Output = Preferences;
+
+    fn subscribe(&self) -> watch::Receiver<Self::Output> {
+        self.subscriptions.fetch_add(1, Ordering::Relaxed);
+        self.shared.subscribe()
     }
     fn unsubscribe(&self) {
-        if let Err(e) = self.tx.send(Command::Quit) {
-            log::error!("failed to send quit command: {e}");
+        // release only the subscriptions of this handle
+        if self
+            .subscriptions
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            self.shared.unsubscribe();
         }
     }
     fn refresh(&self) {
-        if let Err(e) = self.tx.send(Command::Refresh) {
-            log::error!("failed to send refresh command: {e}");
-        }
+        self.shared.refresh();
     }
 }
 
-async fn update(agent: &BskyAgent, tx: &watch::Sender<Preferences>) {
-    if let Ok(preferences) = agent.get_preferences(true).await {
+async fn update(agent: &Agent, status: &Status, tx: &watch::Sender<Preferences>) {
+    if let Ok(preferences) = status
+        .track("preferences", agent.get_preferences(true))
+        .await
+    {
         agent.configure_labelers_from_preferences(&preferences);
         tx.send(preferences).ok();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fake::FakeServer;
+    use super::super::super::types::FeedSourceInfo;
+    use super::*;
+    use serde_json::json;
+    use tokio::time::Instant;
+
+    const GET_PREFERENCES: &str = "app.bsky.actor.getPreferences";
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_preferences() {
+        let server = FakeServer::default();
+        server.respond(GET_PREFERENCES, json!({ "preferences": [] }));
+        server.respond(
+            GET_PREFERENCES,
+            json!({
+                "preferences": [{
+                    "$type": "app.bsky.actor.defs#savedFeedsPrefV2",
+                    "items": [{
+                        "id": "3l6ovcmm2vd2j",
+                        "pinned": true,
+                        "type": "timeline",
+                        "value": "following",
+                    }],
+                }],
+            }),
+        );
+        let watcher = server.watcher().await;
+        let watch = watcher.preferences();
+        let mut rx = watch.subscribe();
+        let start = Instant::now();
+        rx.changed().await.expect("channel closed");
+        assert!(rx.borrow_and_update().saved_feeds.is_empty());
+        // refreshed without waiting for the interval
+        watch.refresh();
+        rx.changed().await.expect("channel closed");
+        assert_eq!(rx.borrow_and_update().saved_feeds.len(), 1);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        time::sleep(Duration::from_secs(599)).await;
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 2);
+        time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 3);
+        watch.unsubscribe();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn share_preferences() {
+        let server = FakeServer::default();
+        server.respond(GET_PREFERENCES, json!({ "preferences": [] }));
+        server.respond("app.bsky.labeler.getServices", json!({ "views": [] }));
+        server.respond("app.bsky.feed.getTimeline", json!({ "feed": [] }));
+        let watcher = server.watcher().await;
+        let (first, second) = (watcher.preferences(), watcher.preferences());
+        let mut rx1 = first.subscribe();
+        rx1.changed().await.expect("channel closed");
+        // a later subscriber gets the preferences already fetched
+        let mut rx2 = second.subscribe();
+        assert!(rx2.has_changed().expect("channel closed"));
+        rx2.borrow_and_update();
+        let feed = watcher.feed(FeedSourceInfo::Timeline(String::from("following")));
+        let mut feed_rx = feed.subscribe();
+        feed_rx.changed().await.expect("channel closed");
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 1);
+        // a refresh from any of them updates all
+        feed.refresh();
+        rx1.changed().await.expect("channel closed");
+        rx2.changed().await.expect("channel closed");
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 2);
+        // polled until the last one unsubscribes
+        first.unsubscribe();
+        first.unsubscribe();
+        feed.unsubscribe();
+        time::sleep(Duration::from_secs(601)).await;
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 3);
+        second.unsubscribe();
+        time::sleep(Duration::from_secs(1200)).await;
+        assert_eq!(server.calls(GET_PREFERENCES).len(), 3);
+    }
+}
@@ -0,0 +1,212 @@
+use super::super::client::Agent;
+use super::super::{Status, Watch, Watcher};
+use bsky_sdk::Result;
+use bsky_sdk::api::app::bsky::notification::get_unread_count;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio::time;
+
+impl Watcher {
+    /// Number of the unread notifications, also kept in the status.
+    ///
+    /// Polled once for all handles of the watcher, however many columns show it.
+    pub fn unread_count(&self) -> impl Watch<Output = usize> + use<> {
+        UnreadCountWatcher {
+            shared: self.unread_count.clone(),
+            subscriptions: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// Unread count fetched by one polling loop, running while any handle is subscribed to it.
+pub struct SharedUnreadCount {
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    period: Duration,
+    state: Mutex<Option<Running>>,
+}
+
+struct Running {
+    subscribers: usize,
+    rx: watch::Receiver<usize>,
+    quit: broadcast::Sender<()>,
+}
+
+impl SharedUnreadCount {
+    pub fn new(agent: Arc<Agent>, status: Arc<Status>, period: Duration) -> Self {
+        Self {
+            agent,
+            status,
+            period,
+            state: Mutex::new(None),
+        }
+    }
+    fn subscribe(&self) -> watch::Receiver<usize> {
+        let Ok(mut state) = self.state.lock() else {
+            log::error!("failed to lock unread count");
+            return watch::channel(Default::default()).1;
+        };
+        if let Some(running) = state.as_mut() {
+            running.subscribers += 1;
+            return running.rx.clone();
+        }
+        let (tx, rx) = watch::channel(Default::default());
+        let (quit, _) = broadcast::channel(1);
+        let updater = Updater {
+            agent: self.agent.clone(),
+            status: self.status.clone(),
+            tx,
+        };
+        let mut quit_rx = quit.subscribe();
+        let watching = self.status.watch("unread count");
+        let mut interval = time::interval(self.period);
+        tokio::spawn(async move {
+            // the refresh time is shown while the loop runs
+            let _watching = watching;
+            loop {
+                let tick = interval.tick();
+                tokio::select! {
+                    _ = tick => {
+                        let updater = updater.clone();
+                        tokio::spawn(async move {
+                            updater.update().await;
+                        });
+                    }
+                    // quit when the last handle is unsubscribed
+                    _ = quit_rx.recv() => {
+                        break;
+                    }
+                }
+            }
+        });
+        state.replace(Running {
+            subscribers: 1,
+            rx: rx.clone(),
+            quit,
+        });
+        rx
+    }
+    #[cfg(test)]
+    pub fn subscribers(&self) -> usize {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.as_ref().map(|running| running.subscribers))
+            .unwrap_or_default()
+    }
+    fn unsubscribe(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(running) = state.as_mut() {
+                running.subscribers -= 1;
+                if running.subscribers == 0 {
+                    if let Some(running) = state.take() {
+                        if let Err(e) = running.quit.send(()) {
+                            log::error!("failed to send quit: {e}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct UnreadCountWatcher {
+    shared: Arc<SharedUnreadCount>,
+    subscriptions: AtomicUsize,
+}
+
+impl Watch for UnreadCountWatcher {
+    type Output = usize;
+
+    fn subscribe(&self) -> watch::Receiver<Self::Output> {
+        self.subscriptions.fetch_add(1, Ordering::Relaxed);
+        self.shared.subscribe()
+    }
+    fn unsubscribe(&self) {
+        // release only the subscriptions of this handle
+        if self
+            .subscriptions
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            self.shared.unsubscribe();
+        }
+    }
+    // polled with the interval of the notifications only
+    fn refresh(&self) {}
+}
+
+#[derive(Clone)]
+struct Updater {
+    agent: Arc<Agent>,
+    status: Arc<Status>,
+    tx: watch::Sender<usize>,
+}
+
+impl Updater {
+    async fn update(&self) {
+        match self
+            .status
+            .track("unread count", self.get_unread_count())
+            .await
+        {
+            Ok(count) => {
+                self.status.set_unread(count);
+                self.tx.send(count).ok();
+            }
+            Err(e) => {
+                log::warn!("failed to get unread count: {e}");
+            }
+        }
+    }
+    async fn get_unread_count(&self) -> Result<usize> {
+        let output = self
+            .agent
+            .api
+            .app
+            .bsky
+            .notification
+            .get_unread_count(
+                get_unread_count::ParametersData {
+                    priority: None,
+                    seen_at: None,
+                }
+                .into(),
+            )
+            .await?;
+        Ok(usize::try_from(output.count).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fake::FakeServer;
+    use super::*;
+    use serde_json::json;
+
+    const GET_UNREAD_COUNT: &str = "app.bsky.notification.getUnreadCount";
+
+    #[tokio::test(start_paused = true)]
+    async fn share_unread_count() {
+        let server = FakeServer::default();
+        server.respond(GET_UNREAD_COUNT, json!({ "count": 3 }));
+        let watcher = server.watcher().await;
+        let (first, second) = (watcher.unread_count(), watcher.unread_count());
+        let mut rx = first.subscribe();
+        rx.changed().await.expect("channel closed");
+        drop(second.subscribe());
+        assert_eq!(watcher.status.unread(), Some(3));
+        time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(server.calls(GET_UNREAD_COUNT).len(), 1);
+        // polled until the last one unsubscribes
+        first.unsubscribe();
+        first.unsubscribe();
+        time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(server.calls(GET_UNREAD_COUNT).len(), 2);
+        second.unsubscribe();
+        time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(server.calls(GET_UNREAD_COUNT).len(), 2);
+    }
+}
@@ -1,9 +1,12 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use serde::Serialize;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::{env, fs};
 use tuisky::app::App;
-use tuisky::config::Config;
+use tuisky::config::{Config, ConfigFile, ParseError};
 use tuisky::utils::{get_config_dir, initialize_panic_handler};
 
 #[derive(Parser, Debug)]
@@ -11,12 +14,31 @@ use tuisky::utils::{get_config_dir, initialize_panic_handler};
 /// TUI Client for Bluesky.
 struct Args {
     /// Path to the configuration file.
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     config: Option<PathBuf>,
     /// Maximum number of columns to display.
     /// The number of columns will be determined by the terminal width.
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     num_columns: Option<usize>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspect the configuration file.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Validate the configuration file and warn about conflicting key bindings.
+    Check,
+    /// Print the default configuration.
+    PrintDefault,
+    /// Print the configuration in effect, with the defaults filled in.
+    PrintEffective,
 }
 
 impl Args {
@@ -42,22 +64,73 @@ fn init_logger() {
     builder.init();
 }
 
+fn run_config_command(command: &ConfigCommand, mut config_file: ConfigFile) -> Result<ExitCode> {
+    match command {
+        ConfigCommand::Check => {
+            let path = config_file.path().display().to_string();
+            if !config_file.path().exists() {
+                eprintln!("{path}: error: file not found");
+                return Ok(ExitCode::FAILURE);
+            }
+            let config = match config_file.load() {
+                Ok(config) => config,
+                Err(e) => {
+                    match e.downcast_ref::<ParseError>() {
+                        Some(ParseError {
+                            location: Some((line, column)),
+                            message,
+                        }) => eprintln!("{path}:{line}:{column}: error: {message}"),
+                        _ => eprintln!("{path}: error: {e}"),
+                    }
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            let conflicts = config.keybindings.conflicts();
+            for conflict in &conflicts {
+                eprintln!("{path}: warning: {conflict}");
+            }
+            match conflicts.len() {
+                0 => println!("{path}: ok"),
+                1 => println!("{path}: ok with 1 warning"),
+                len => println!("{path}: ok with {len} warnings"),
+            }
+        }
+        ConfigCommand::PrintDefault => {
+            let mut config = Config::default();
+            config.set_default_keybindings();
+            print!("{}", to_toml(&config)?);
+        }
+        ConfigCommand::PrintEffective => {
+            print!("{}", to_toml(&config_file.load()?)?);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Serialize with the keys sorted, since the keybindings are stored in `HashMap`s.
+fn to_toml(value: &impl Serialize) -> Result<String> {
+    Ok(toml::to_string_pretty(&toml::Value::try_from(value)?)?)
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
-    let mut config = if args.config_path()?.exists() {
-        toml::from_str(&fs::read_to_string(args.config_path()?)?)?
-    } else {
-        Config::default()
-    };
-    config.set_default_keybindings();
-    if let Some(num_columns) = args.num_columns {
-        config.num_columns = Some(num_columns);
+    let mut config_file = ConfigFile::new(args.config_path()?, args.num_columns);
+    if let Some(Command::Config(command)) = &args.command {
+        return run_config_command(command, config_file);
     }
+    let config = config_file
+        .load()
+        .wrap_err_with(|| format!("failed to load {}", config_file.path().display()))?;
 
     init_logger();
 
+    for conflict in config.keybindings.conflicts() {
+        log::warn!("{conflict}");
+    }
+
     initialize_panic_handler()?;
 
-    App::new(config).run().await
+    App::new(config, config_file).run().await?;
+    Ok(ExitCode::SUCCESS)
 }
@@ -1,6 +1,11 @@
 pub mod column;
+#[cfg(test)]
+mod harness;
 pub mod main;
 pub mod modals;
+pub mod mouse;
+pub mod text;
+pub mod toasts;
 pub mod views;
 
 use crate::config::Config;
@@ -1,19 +1,25 @@
+use super::Component;
 use super::views::types::{Action as ViewAction, Transition, View};
 use super::views::{
-    FeedViewComponent, LoginComponent, MenuViewComponent, NewPostViewComponent, PostViewComponent,
-    RootComponent, ViewComponent,
+    Account, AccountsViewComponent, Command, FeedViewComponent, HelpViewComponent, LoginComponent,
+    MenuViewComponent, NewPostViewComponent, NotificationsViewComponent, PaletteViewComponent,
+    PostViewComponent, RootComponent, ViewComponent,
+};
+use crate::backend::client;
+use crate::backend::types::FeedSourceInfo;
+use crate::backend::{Watch, Watcher};
+use crate::config::{
+    ColumnConfig, Config, Key, KeyBuffer, KeybindingScope, Keybindings, Keymap, StartView,
 };
-use super::Component;
-use crate::backend::Watcher;
-use crate::config::Config;
 use crate::types::{Action, IdType};
 use bsky_sdk::agent::config::Config as AgentConfig;
 use bsky_sdk::api::agent::atp_agent::AtpSession;
-use bsky_sdk::BskyAgent;
-use color_eyre::{eyre, Result};
-use crossterm::event::KeyEvent;
-use ratatui::layout::{Rect, Size};
+use color_eyre::{Result, eyre};
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::Frame;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Rect, Size};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 use tokio::sync::mpsc::{self, UnboundedSender};
@@ -23,17 +29,30 @@ static COUNTER: AtomicU32 = AtomicU32::new(0);
 pub struct ColumnComponent {
     pub id: IdType,
     pub watcher: Option<Arc<Watcher>>,
+    /// Polls the unread count into the status of the watcher while signed in.
+    unread_count: Option<Box<dyn Watch<Output = usize>>>,
     pub views: Vec<Box<dyn ViewComponent>>,
+    /// Saved views to open on top of the root after signing in, instead of the start view.
+    restore: Vec<View>,
     menu: MenuViewComponent,
     pub is_menu_active: bool,
+    /// Hidden columns keep their session and views, but the current view is not subscribed.
+    hidden: bool,
+    help: Option<HelpViewComponent>,
+    palette: Option<PaletteViewComponent>,
     config: Config,
+    layout: ColumnConfig,
+    keymaps: HashMap<Option<KeybindingScope>, Keymap<ViewAction>>,
+    pending_keys: KeyBuffer,
     action_tx: UnboundedSender<Action>,
     view_tx: UnboundedSender<ViewAction>,
     session: Arc<RwLock<Option<AtpSession>>>,
+    /// Contents of the column as last drawn, reused until its state changes.
+    cache: Option<Buffer>,
 }
 
 impl ColumnComponent {
-    pub fn new(config: Config, action_tx: UnboundedSender<Action>) -> Self {
+    pub fn new(config: Config, layout: ColumnConfig, action_tx: UnboundedSender<Action>) -> Self {
         let id = COUNTER.fetch_add(1, Ordering::SeqCst);
         let (view_tx, mut view_rx) = mpsc::unbounded_channel();
         let tx = action_tx.clone();
@@ -45,6 +64,11 @@ impl ColumnComponent {
                             log::error!("failed to send login action: {e}");
                         }
                     }
+                    ViewAction::Toast(toast) => {
+                        if let Err(e) = tx.send(Action::Toast(toast)) {
+                            log::error!("failed to send toast action: {e}");
+                        }
+                    }
                     _ => {
                         if let Err(e) = tx.send(Action::View((id, action))) {
                             log::error!("failed to send view action: {e}");
@@ -56,20 +80,29 @@ impl ColumnComponent {
         Self {
             id,
             watcher: None,
+            unread_count: None,
             views: Vec::new(),
-            menu: MenuViewComponent::new(view_tx.clone(), &config.keybindings),
+            restore: Vec::new(),
+            menu: MenuViewComponent::new(view_tx.clone(), &config),
             is_menu_active: false,
+            hidden: false,
+            help: None,
+            palette: None,
+            keymaps: Self::keymaps(&config.keybindings),
+            pending_keys: config.keybindings.key_buffer(),
             config,
+            layout,
             action_tx,
             view_tx,
             session: Arc::new(RwLock::new(None)),
+            cache: None,
         }
     }
     pub fn init_with_config(&mut self, config: &AgentConfig) -> Result<()> {
         let config = config.clone();
         let (id, tx) = (self.id, self.action_tx.clone());
         tokio::spawn(async move {
-            let Ok(agent) = BskyAgent::builder().config(config).build().await else {
+            let Ok(agent) = client::builder().config(config).build().await else {
                 return log::error!("failed to build agent from config");
             };
             if let Err(e) = tx.send(Action::Login((id, Box::new(agent)))) {
@@ -78,6 +111,162 @@ impl ColumnComponent {
         });
         Ok(())
     }
+    fn keymaps(keybindings: &Keybindings) -> HashMap<Option<KeybindingScope>, Keymap<ViewAction>> {
+        [
+            None,
+            Some(KeybindingScope::Feed),
+            Some(KeybindingScope::Post),
+            Some(KeybindingScope::Composer),
+            Some(KeybindingScope::Modal),
+        ]
+        .into_iter()
+        .map(|scope| (scope, keybindings.view_keymap(scope)))
+        .collect()
+    }
+    pub fn set_hidden(&mut self, hidden: bool) -> Result<()> {
+        if hidden == self.hidden {
+            return Ok(());
+        }
+        if let Some(view) = self.views.last_mut() {
+            if hidden {
+                view.deactivate()?;
+            } else {
+                view.activate()?;
+            }
+        }
+        self.is_menu_active = false;
+        self.hidden = hidden;
+        self.invalidate();
+        Ok(())
+    }
+    /// Unsubscribe the watches of the column before it is closed.
+    pub fn close(&mut self) -> Result<()> {
+        self.set_hidden(true)?;
+        if let Some(unread_count) = self.unread_count.take() {
+            unread_count.unsubscribe();
+        }
+        Ok(())
+    }
+    /// Lay out the column again at the next draw, even if its state is not changed.
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+    /// Start with a choice of the accounts signed in to other columns, or the login form if there are none.
+    pub fn init_with_accounts(&mut self, accounts: Vec<Account>) -> Result<()> {
+        if accounts.is_empty() {
+            return self.init(Size::default());
+        }
+        let mut component = Box::new(AccountsViewComponent::new(self.view_tx.clone(), accounts));
+        component.register_config_handler(self.config.clone())?;
+        self.views = vec![component];
+        self.invalidate();
+        Ok(())
+    }
+    /// A new column with the same account, settings and stack of views.
+    pub fn duplicate(&self) -> Result<Self> {
+        let mut column = Self::new(
+            self.config.clone(),
+            self.layout.clone(),
+            self.action_tx.clone(),
+        );
+        column.watcher.clone_from(&self.watcher);
+        column.watch_unread_count();
+        if let (Ok(mut session), Ok(current)) = (column.session.write(), self.session.read()) {
+            session.clone_from(&current);
+        }
+        column.views = self
+            .views
+            .iter()
+            .map(|view| column.view(&view.view()))
+            .collect::<Result<_>>()?;
+        if let Some(view) = column.views.last_mut() {
+            view.activate()?;
+        }
+        Ok(column)
+    }
+    /// Open the saved views again after signing in, instead of the start view.
+    pub fn restore(&mut self, views: Vec<View>) {
+        self.restore = views;
+    }
+    /// Sign in with the watcher, which may be shared with the other columns of the account,
+    /// and open the saved views or the start view.
+    pub fn share(&mut self, watcher: Arc<Watcher>) -> Result<Option<Action>> {
+        {
+            let agent = watcher.agent.clone();
+            let session = self.session.clone();
+            tokio::spawn(async move {
+                if let Some(output) = agent.get_session().await {
+                    if let Ok(mut session) = session.write() {
+                        session.replace(output);
+                    }
+                }
+            });
+        }
+        self.watcher = Some(watcher);
+        self.watch_unread_count();
+        self.transition(&Transition::Replace(Box::new(View::Root)))?;
+        let restore = std::mem::take(&mut self.restore);
+        if restore.is_empty() {
+            return self.start_view();
+        }
+        for view in restore {
+            self.transition(&Transition::Push(Box::new(view)))?;
+        }
+        Ok(Some(Action::Render))
+    }
+    /// Use the watcher reconfigured from the current one, in the column and its views.
+    pub fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        for view in self.views.iter_mut() {
+            view.replace_watcher(watcher.clone())?;
+        }
+        self.watcher = Some(watcher);
+        self.watch_unread_count();
+        Ok(())
+    }
+    /// Views above the root to save, without the composer as its text is not saved.
+    pub fn saved_views(&self) -> Vec<View> {
+        self.views
+            .iter()
+            .map(|view| view.view())
+            .filter(|view| {
+                !matches!(
+                    view,
+                    View::Login | View::Accounts | View::Root | View::NewPost(_)
+                )
+            })
+            .collect()
+    }
+    /// The signed-in account, to share with a new column.
+    pub fn account(&self) -> Option<Account> {
+        let watcher = self.watcher.as_ref()?;
+        let session = self.session.read().ok()?;
+        let session = session.as_ref()?;
+        Some(Account {
+            handle: session.handle.as_str().to_string(),
+            did: session.did.as_str().to_string(),
+            watcher: watcher.clone(),
+        })
+    }
+    fn watch_unread_count(&mut self) {
+        if let Some(unread_count) = self.unread_count.take() {
+            unread_count.unsubscribe();
+        }
+        if let Some(watcher) = &self.watcher {
+            let unread_count = watcher.unread_count();
+            // the count is read from the status
+            drop(unread_count.subscribe());
+            self.unread_count = Some(Box::new(unread_count));
+        }
+    }
+    pub fn layout(&self) -> &ColumnConfig {
+        &self.layout
+    }
+    pub fn set_layout(&mut self, layout: ColumnConfig) {
+        self.layout = layout;
+    }
+    pub fn pending_keys(&self) -> &[Key] {
+        self.pending_keys.keys()
+    }
     pub fn title(&self) -> String {
         if let Some(session) = self.session.read().ok().as_ref().and_then(|s| s.as_ref()) {
             format!(" {} ", session.handle.as_str())
@@ -88,54 +277,75 @@ impl ColumnComponent {
     pub(crate) fn transition(&mut self, transition: &Transition) -> Result<Option<Action>> {
         match transition {
             Transition::Push(view) => {
-                if let Some(current) = self.views.last_mut() {
+                if let Some(current) = self.views.last_mut().filter(|_| !self.hidden) {
                     current.deactivate()?;
                 }
                 let mut next = self.view(view)?;
-                next.as_mut().activate()?;
+                if !self.hidden {
+                    next.as_mut().activate()?;
+                }
                 self.views.push(next);
             }
             Transition::Pop => {
-                if let Some(mut view) = self.views.pop() {
+                if let Some(mut view) = self.views.pop().filter(|_| !self.hidden) {
                     view.deactivate()?;
                 }
-                if let Some(current) = self.views.last_mut() {
+                if let Some(current) = self.views.last_mut().filter(|_| !self.hidden) {
                     current.activate()?;
                 }
             }
             Transition::Replace(view) => {
-                if let Some(mut current) = self.views.pop() {
+                if let Some(mut current) = self.views.pop().filter(|_| !self.hidden) {
                     current.deactivate()?;
                 }
                 let mut next = self.view(view)?;
-                next.as_mut().activate()?;
+                if !self.hidden {
+                    next.as_mut().activate()?;
+                }
                 self.views.push(next);
             }
         }
         Ok(Some(Action::Render))
     }
+    fn login_view(&self) -> Result<Box<dyn ViewComponent>> {
+        let mut component = LoginComponent::new(self.view_tx.clone());
+        if let Some(account) = self.layout.account() {
+            component = component.with_identifier(account);
+        }
+        let mut component = Box::new(component);
+        component.register_config_handler(self.config.clone())?;
+        Ok(component)
+    }
     fn view(&self, view: &View) -> Result<Box<dyn ViewComponent>> {
-        let watcher = self
-            .watcher
-            .as_ref()
-            .ok_or_else(|| eyre::eyre!("watcher not initialized"))?;
-        Ok(match view {
-            View::Login => Box::new(LoginComponent::new(self.view_tx.clone())),
-            View::Root => Box::new(RootComponent::new(self.view_tx.clone(), watcher.clone())),
-            View::NewPost => Box::new(NewPostViewComponent::new(
+        let watcher = || {
+            self.watcher
+                .as_ref()
+                .ok_or_else(|| eyre::eyre!("watcher not initialized"))
+        };
+        let mut component: Box<dyn ViewComponent> = match view {
+            // the accounts to choose from are not kept, so sign in again instead
+            View::Login | View::Accounts => return self.login_view(),
+            View::Root => Box::new(RootComponent::new(self.view_tx.clone(), watcher()?.clone())),
+            View::NewPost(reply) => Box::new(NewPostViewComponent::new(
                 self.view_tx.clone(),
-                watcher.agent.clone(),
+                watcher()?.agent.clone(),
+                reply.as_deref().cloned(),
             )),
             View::Feed(info) => Box::new(FeedViewComponent::new(
                 self.view_tx.clone(),
-                watcher.clone(),
+                watcher()?.clone(),
                 info.as_ref().clone(),
+                self.config.download.clone(),
+            )),
+            View::Notifications => Box::new(NotificationsViewComponent::new(
+                self.view_tx.clone(),
+                watcher()?.clone(),
             )),
             View::Post(boxed) => {
                 let (post_view, reply) = boxed.as_ref();
                 Box::new(PostViewComponent::new(
                     self.view_tx.clone(),
-                    watcher.clone(),
+                    watcher()?.clone(),
                     post_view.clone(),
                     reply.clone(),
                     self.session
@@ -144,52 +354,210 @@ impl ColumnComponent {
                         .as_ref()
                         .and_then(|s| s.as_ref())
                         .cloned(),
+                    self.config.download.clone(),
                 ))
             }
-        })
+        };
+        component.register_config_handler(self.config.clone())?;
+        Ok(component)
+    }
+    /// Open the view of `view` in the column settings on top of the root.
+    fn start_view(&mut self) -> Result<Option<Action>> {
+        match self.layout.view.clone() {
+            None => Ok(Some(Action::Render)),
+            Some(StartView::Timeline) => self.transition(&Transition::Push(Box::new(View::Feed(
+                Box::new(FeedSourceInfo::Timeline(String::from("following"))),
+            )))),
+            Some(StartView::Notifications) => {
+                self.transition(&Transition::Push(Box::new(View::Notifications)))
+            }
+            Some(StartView::Search(query)) => self.transition(&Transition::Push(Box::new(
+                View::Feed(Box::new(FeedSourceInfo::Search(query))),
+            ))),
+            Some(StartView::Feed(uri) | StartView::List(uri)) => {
+                self.run_command(Command::Feed(uri))
+            }
+            Some(StartView::Author(actor)) => self.run_command(Command::Profile(actor)),
+        }
+    }
+    fn run_command(&self, command: Command) -> Result<Option<Action>> {
+        match command {
+            Command::Global(action) => Ok(Some((&action).into())),
+            Command::View(action) => Ok(Some(Action::View((self.id, action)))),
+            _ => {
+                let Some(watcher) = &self.watcher else {
+                    return Ok(Some(Action::Error(String::from(
+                        "sign in to open posts, feeds and profiles",
+                    ))));
+                };
+                let (agent, view_tx, action_tx) = (
+                    watcher.agent.clone(),
+                    self.view_tx.clone(),
+                    self.action_tx.clone(),
+                );
+                tokio::spawn(async move {
+                    match command.view(&agent).await {
+                        Ok(view) => {
+                            view_tx
+                                .send(ViewAction::Transition(Transition::Push(Box::new(view))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to run {command:?}: {e}");
+                            action_tx
+                                .send(Action::Error(format!("failed to open: {e}")))
+                                .ok();
+                        }
+                    }
+                });
+                Ok(Some(Action::Render))
+            }
+        }
     }
 }
 
 impl Component for ColumnComponent {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.keymaps = Self::keymaps(&config.keybindings);
+        self.pending_keys = config.keybindings.key_buffer();
+        self.menu = MenuViewComponent::new(self.view_tx.clone(), &config);
+        if self.help.is_some() {
+            let scope = self.views.last().and_then(|view| view.keybinding_scope());
+            self.help = Some(HelpViewComponent::new(&config, scope));
+        }
+        for view in self.views.iter_mut() {
+            view.register_config_handler(config.clone())?;
+        }
+        self.config = config;
+        self.invalidate();
+        Ok(())
+    }
     fn init(&mut self, _size: Size) -> Result<()> {
-        self.views = vec![Box::new(LoginComponent::new(self.view_tx.clone()))];
+        self.views = vec![self.login_view()?];
+        self.invalidate();
         Ok(())
     }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        if !self.is_menu_active {
+        self.invalidate();
+        if let Some(help) = self.help.as_mut() {
+            return Ok(match help.handle_key_events(key)? {
+                Some(ViewAction::Back) => {
+                    self.help = None;
+                    Some(Action::Render)
+                }
+                Some(_) => Some(Action::Render),
+                None => None,
+            });
+        }
+        if let Some(palette) = self.palette.as_mut() {
+            return match palette.handle_key_events(key)? {
+                Some(ViewAction::Back) => {
+                    self.palette = None;
+                    Ok(Some(Action::Render))
+                }
+                Some(ViewAction::Enter) => {
+                    let command = palette.command();
+                    self.palette = None;
+                    match command {
+                        Some(command) => self.run_command(command),
+                        None => Ok(Some(Action::Render)),
+                    }
+                }
+                Some(_) => Ok(Some(Action::Render)),
+                None => Ok(None),
+            };
+        }
+        // keys continuing a pending key sequence are not passed to the view
+        let pending = !self.pending_keys.keys().is_empty() && !self.pending_keys.is_expired();
+        if !self.is_menu_active && !pending {
             if let Some(view) = self.views.last_mut() {
                 if let Some(action) = view.handle_key_events(key)? {
                     return Ok(Some(Action::View((self.id, action))));
                 }
             }
         }
-        if let Some(action) = self.config.keybindings.column.get(&key.into()) {
-            Ok(Some(Action::View((self.id, action.into()))))
+        let scope = if self.is_menu_active {
+            None
+        } else {
+            self.views.last().and_then(|view| view.keybinding_scope())
+        };
+        if let Some(action) = self.keymaps[&scope].feed(&mut self.pending_keys, key.into()) {
+            Ok(Some(Action::View((self.id, action.clone()))))
+        } else if pending || !self.pending_keys.keys().is_empty() {
+            Ok(Some(Action::Render))
         } else {
             Ok(None)
         }
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        self.invalidate();
+        if let Some(help) = self.help.as_mut() {
+            return Ok(help.handle_mouse_events(mouse)?.map(|_| Action::Render));
+        }
+        if let Some(palette) = self.palette.as_mut() {
+            return Ok(palette.handle_mouse_events(mouse)?.map(|_| Action::Render));
+        }
+        if self.is_menu_active {
+            return Ok(match self.menu.handle_mouse_events(mouse) {
+                Some(action) => Some(Action::View((self.id, action))),
+                // a click outside closes the menu
+                None if matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left)) => {
+                    self.is_menu_active = false;
+                    Some(Action::Render)
+                }
+                None => None,
+            });
+        }
+        if let Some(view) = self.views.last_mut() {
+            if let Some(action) = view.handle_mouse_events(mouse)? {
+                return Ok(Some(Action::View((self.id, action))));
+            }
+        }
+        Ok(None)
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        // any action for this column may change what is drawn
+        if matches!(&action, Action::View((id, _)) | Action::Login((id, _)) if *id == self.id) {
+            self.invalidate();
+        }
         match action {
             Action::View((id, view_action)) if id == self.id => {
                 match view_action {
                     ViewAction::Render => {
                         return Ok(Some(Action::Render));
                     }
-                    ViewAction::NewPost if self.watcher.is_some() => {
-                        if !self
-                            .views
-                            .last()
-                            .map(|view| view.view() == View::NewPost)
-                            .unwrap_or_default()
-                        {
-                            return self.transition(&Transition::Push(Box::new(View::NewPost)));
-                        }
+                    ViewAction::NewPost
+                        if self.watcher.is_some()
+                            && !self
+                                .views
+                                .last()
+                                .map(|view| matches!(view.view(), View::NewPost(_)))
+                                .unwrap_or_default() =>
+                    {
+                        return self.transition(&Transition::Push(Box::new(View::NewPost(None))));
+                    }
+                    ViewAction::Share(watcher) => {
+                        return self.share(watcher);
                     }
                     ViewAction::Menu if self.watcher.is_some() => {
                         self.is_menu_active = !self.is_menu_active;
                         return Ok(Some(Action::Render));
                     }
+                    ViewAction::Help => {
+                        let scope = self.views.last().and_then(|view| view.keybinding_scope());
+                        self.help = Some(HelpViewComponent::new(&self.config, scope));
+                        self.is_menu_active = false;
+                        return Ok(Some(Action::Render));
+                    }
+                    ViewAction::CommandPalette => {
+                        let scope = self.views.last().and_then(|view| view.keybinding_scope());
+                        self.palette = Some(PaletteViewComponent::new(&self.config, scope));
+                        self.is_menu_active = false;
+                        return Ok(Some(Action::Render));
+                    }
+                    ViewAction::Transition(transition) => {
+                        return self.transition(&transition);
+                    }
                     _ => {}
                 }
                 if self.is_menu_active {
@@ -205,7 +573,8 @@ impl Component for ColumnComponent {
                                 session.take();
                             }
                             self.watcher.take();
-                            self.views = vec![Box::new(LoginComponent::new(self.view_tx.clone()))];
+                            self.watch_unread_count();
+                            self.views = vec![self.login_view()?];
                             return Ok(Some(Action::Render));
                         }
                         Ok(Some(ViewAction::Transition(transition))) => {
@@ -218,35 +587,45 @@ impl Component for ColumnComponent {
                     Ok(None)
                 };
             }
+            Action::Tick(_) if self.pending_keys.expire() => {
+                return Ok(Some(Action::Render));
+            }
             Action::Login((id, agent)) if id == self.id => {
-                {
-                    let agent = agent.clone();
-                    let session = self.session.clone();
-                    tokio::spawn(async move {
-                        if let Some(output) = agent.get_session().await {
-                            if let Ok(mut session) = session.write() {
-                                session.replace(output);
-                            }
-                        }
-                    });
-                }
-                self.watcher = Some(Arc::new(Watcher::new(
+                return self.share(Arc::new(Watcher::new(
                     Arc::new(*agent),
                     self.config.watcher.clone(),
                 )));
-                return self.transition(&Transition::Replace(Box::new(View::Root)));
             }
             _ => {}
         }
         Ok(None)
     }
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if let Some(cache) = self.cache.as_ref().filter(|cache| cache.area == area) {
+            let buffer = f.buffer_mut();
+            for position in area.positions() {
+                buffer[position].clone_from(&cache[position]);
+            }
+            return Ok(());
+        }
         if let Some(view) = self.views.last_mut() {
             view.draw(f, area)?;
         }
         if self.is_menu_active {
             self.menu.draw(f, area)?;
         }
+        if let Some(help) = self.help.as_mut() {
+            help.draw(f, area)?;
+        }
+        if let Some(palette) = self.palette.as_mut() {
+            palette.draw(f, area)?;
+        }
+        let mut cache = Buffer::empty(area);
+        let buffer = f.buffer_mut();
+        for position in area.positions() {
+            cache[position].clone_from(&buffer[position]);
+        }
+        self.cache = Some(cache);
         Ok(())
     }
 }
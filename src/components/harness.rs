@@ -0,0 +1,311 @@
+//! Runs the main component headless against ratatui's `TestBackend`, with keys and watcher data
+//! given by the tests, to compare the drawn screens with the snapshots in `snapshots/`.
+//!
+//! The agent of a signed-in column is served by a `FakeServer` without any outputs,
+//! so the views are filled only with the data sent by the tests.
+//! Run `cargo insta review` (or set `INSTA_UPDATE=always`) to accept changed snapshots.
+
+use super::Component;
+use super::main::MainComponent;
+use super::views::types::{Action as ViewAction, Data, Transition, View};
+use crate::backend::fake::{FakeServer, HANDLE, feed_view_post, post_view, profile};
+use crate::backend::types::{FeedSourceInfo, PinnedFeed};
+use crate::config::{Config, Key, TimestampConfig, TimestampFormat};
+use crate::types::Action;
+use bsky_sdk::api::app::bsky::actor::defs::SavedFeedData;
+use bsky_sdk::api::app::bsky::feed::defs::FeedViewPost;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Size;
+use std::env;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+pub struct Harness {
+    terminal: Terminal<TestBackend>,
+    main: MainComponent,
+    action_rx: UnboundedReceiver<Action>,
+    server: FakeServer,
+}
+
+impl Harness {
+    /// One column with the default keybindings, and times shown in UTC.
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut config = Config {
+            num_columns: Some(1),
+            timestamp: TimestampConfig {
+                feed: Self::timestamp(),
+                post: Self::timestamp(),
+            },
+            ..Default::default()
+        };
+        config.set_default_keybindings();
+        let (action_tx, action_rx) = mpsc::unbounded_channel();
+        // no columns are restored from the saved data of the user
+        let mut main = MainComponent::new(config.clone(), action_tx.clone())
+            .with_data_dir(env::temp_dir().join("tuisky-harness"));
+        main.register_action_handler(action_tx)
+            .expect("failed to register action handler");
+        main.register_config_handler(config)
+            .expect("failed to register config handler");
+        main.init(Size::new(width, height))
+            .expect("failed to init main component");
+        Self {
+            terminal: Terminal::new(TestBackend::new(width, height))
+                .expect("failed to create terminal"),
+            main,
+            action_rx,
+            server: FakeServer::default(),
+        }
+    }
+    fn timestamp() -> TimestampFormat {
+        TimestampFormat {
+            relative: false,
+            timezone: "+00:00".parse().expect("failed to parse timezone"),
+            format: String::from("%Y-%m-%d %H:%M"),
+        }
+    }
+    /// Sign in to the focused column, and wait for its root view.
+    pub async fn login(&mut self) {
+        let agent = self.server.agent().await;
+        let id = self.main.selected_id().expect("no column");
+        self.dispatch(Action::Login((id, Box::new(agent)))).await;
+    }
+    /// Press the keys separated by spaces, such as `"Down Down Enter"`.
+    pub async fn keys(&mut self, keys: &str) {
+        for key in keys.split_whitespace() {
+            let Key(code, modifiers) = key.parse().expect("invalid key");
+            self.key(KeyEvent::new(code, modifiers)).await;
+        }
+    }
+    /// Type the characters of the text.
+    pub async fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .await;
+        }
+    }
+    async fn key(&mut self, key: KeyEvent) {
+        if let Some(action) = self
+            .main
+            .handle_key_events(key)
+            .expect("failed to handle key")
+        {
+            self.dispatch(action).await;
+        }
+        self.settle().await;
+    }
+    /// Send the action to the focused column as if it came from its view.
+    pub async fn send(&mut self, action: ViewAction) {
+        let id = self.main.selected_id().expect("no column");
+        self.dispatch(Action::View((id, action))).await;
+    }
+    /// Update the current view of the focused column as if the data came from its watcher.
+    pub async fn update(&mut self, data: Data) {
+        self.send(ViewAction::Update(Box::new(data))).await;
+    }
+    pub async fn push(&mut self, view: View) {
+        self.send(ViewAction::Transition(Transition::Push(Box::new(view))))
+            .await;
+    }
+    async fn dispatch(&mut self, action: Action) {
+        self.handle(action);
+        self.settle().await;
+    }
+    fn handle(&mut self, action: Action) {
+        match action {
+            Action::Render | Action::Error(_) | Action::Tick(_) => {}
+            action => {
+                if let Some(action) = self.main.update(action).expect("failed to update") {
+                    self.handle(action);
+                }
+            }
+        }
+    }
+    /// Let the spawned tasks run, and handle the actions sent from them until there are no more.
+    async fn settle(&mut self) {
+        loop {
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            let Ok(action) = self.action_rx.try_recv() else {
+                break;
+            };
+            self.handle(action);
+        }
+    }
+    /// Draw the screen, with the status line cleared as it shows the requests of the watchers,
+    /// and the id of the column masked as it is counted across the tests running in parallel.
+    pub fn draw(&mut self) -> String {
+        let main = &mut self.main;
+        self.terminal
+            .draw(|f| {
+                let area = f.area();
+                main.draw(f, area).expect("failed to draw");
+                let buffer = f.buffer_mut();
+                for x in area.left()..area.right() {
+                    buffer[(x, area.bottom() - 1)].reset();
+                }
+            })
+            .expect("failed to draw");
+        let screen = self.terminal.backend().to_string();
+        match screen.split_once(" id: ") {
+            Some((head, tail)) => {
+                let digits =
+                    tail.len() - tail.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                format!("{head} id: {}{}", "#".repeat(digits), &tail[digits..])
+            }
+            None => screen,
+        }
+    }
+}
+
+fn timeline() -> PinnedFeed {
+    PinnedFeed {
+        saved_feed: SavedFeedData {
+            id: String::from("3l6ovcmm2vd2j"),
+            pinned: true,
+            r#type: String::from("timeline"),
+            value: String::from("following"),
+        }
+        .into(),
+        info: FeedSourceInfo::Timeline(String::from("following")),
+    }
+}
+
+fn posts() -> Vec<FeedViewPost> {
+    vec![
+        feed_view_post(post_view(
+            profile("bob.test", Some("Bob")),
+            "3kbob",
+            "Hello, world!",
+            "2024-10-01T12:34:56.000Z",
+        )),
+        feed_view_post(post_view(
+            profile("carol.test", None),
+            "3kcarol",
+            "日本語のテキストと絵文字👨‍👩‍👧も、カラムの幅で折り返されます。",
+            "2024-10-01T12:00:00.000Z",
+        )),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    #[tokio::test]
+    async fn login() {
+        let mut harness = Harness::new(60, 20);
+        assert_snapshot!(harness.draw());
+        harness.type_text(HANDLE).await;
+        harness.keys("Down").await;
+        harness.type_text("password").await;
+        assert_snapshot!("login_filled", harness.draw());
+    }
+
+    #[tokio::test]
+    async fn root() {
+        let mut harness = Harness::new(60, 20);
+        harness.login().await;
+        harness.update(Data::SavedFeeds(vec![timeline()])).await;
+        assert_snapshot!(harness.draw());
+        harness.keys("Ctrl-x").await;
+        assert_snapshot!("root_menu", harness.draw());
+    }
+
+    #[tokio::test]
+    async fn feed() {
+        let mut harness = Harness::new(60, 20);
+        harness.login().await;
+        harness
+            .push(View::Feed(Box::new(FeedSourceInfo::Timeline(
+                String::from("following"),
+            ))))
+            .await;
+        harness.update(Data::Feed(posts())).await;
+        assert_snapshot!(harness.draw());
+    }
+
+    #[tokio::test]
+    async fn feed_full_lines() {
+        let mut harness = Harness::new(60, 12);
+        harness.login().await;
+        harness
+            .push(View::Feed(Box::new(FeedSourceInfo::Timeline(
+                String::from("following"),
+            ))))
+            .await;
+        // lines of the text fill the list up to its last column, without being clipped
+        harness
+            .update(Data::Feed(vec![feed_view_post(post_view(
+                profile("bob.test", None),
+                "3kbob",
+                &format!("{} {}", "0123456789".repeat(6), "word ".repeat(12)),
+                "2024-10-01T12:34:56.000Z",
+            ))]))
+            .await;
+        assert_snapshot!(harness.draw());
+    }
+
+    #[tokio::test]
+    async fn post() {
+        let mut harness = Harness::new(60, 30);
+        harness.login().await;
+        harness
+            .push(View::Feed(Box::new(FeedSourceInfo::Timeline(
+                String::from("following"),
+            ))))
+            .await;
+        harness.update(Data::Feed(posts())).await;
+        harness.keys("Down Down Enter").await;
+        assert_snapshot!(harness.draw());
+    }
+
+    #[tokio::test]
+    async fn composer() {
+        let mut harness = Harness::new(60, 24);
+        harness.login().await;
+        harness.push(View::NewPost(None)).await;
+        assert_snapshot!(harness.draw());
+        harness.type_text("Hello, 世界! 👋").await;
+        assert_snapshot!("composer_text", harness.draw());
+    }
+
+    #[tokio::test]
+    async fn reply() {
+        let mut harness = Harness::new(60, 24);
+        harness.login().await;
+        harness
+            .push(View::Feed(Box::new(FeedSourceInfo::Timeline(
+                String::from("following"),
+            ))))
+            .await;
+        harness.update(Data::Feed(posts())).await;
+        harness.keys("Down").await;
+        harness.send(ViewAction::Reply).await;
+        assert_snapshot!(harness.draw());
+        harness.type_text("Hi!").await;
+        harness.send(ViewAction::Submit).await;
+        let writes = harness.server.writes();
+        let record = &writes[0].input.as_ref().expect("no input")["record"];
+        let uri = "at://did:fake:bob.test/app.bsky.feed.post/3kbob";
+        assert_eq!(record["text"], "Hi!");
+        assert_eq!(record["reply"]["parent"]["uri"], uri);
+        assert_eq!(record["reply"]["root"]["uri"], uri);
+    }
+
+    #[tokio::test]
+    async fn embed_modals() {
+        let mut harness = Harness::new(60, 30);
+        harness.login().await;
+        harness.push(View::NewPost(None)).await;
+        harness.send(ViewAction::Embed).await;
+        assert_snapshot!("embed", harness.draw());
+        harness.keys("Down Enter").await;
+        assert_snapshot!("embed_images", harness.draw());
+        harness.keys("Esc Down Down Enter").await;
+        assert_snapshot!("embed_record", harness.draw());
+    }
+}
@@ -1,28 +1,47 @@
 use super::column::ColumnComponent;
+use super::mouse::position;
+use super::text;
+use super::toasts::Toasts;
+use super::views::types::View;
+use super::views::Account;
 use super::Component;
-use crate::config::Config;
-use crate::types::Action;
+use crate::backend::{Status, Watcher};
+use crate::config::{column_widths, ColumnConfig, Config, Key, Theme};
+use crate::types::{Action, IdType, Toast};
 use crate::utils::get_data_dir;
 use bsky_sdk::agent::config::Config as AgentConfig;
+use chrono::{DateTime, Local};
 use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect, Size};
 use ratatui::style::{Style, Stylize};
-use ratatui::widgets::{Block, BorderType};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Paragraph};
 use ratatui::Frame;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{create_dir_all, File};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct AppData {
     views: Vec<ViewData>,
+    #[serde(default)]
+    selected: Option<usize>,
+    #[serde(default)]
+    zoom: bool,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct ViewData {
     agent: Option<AgentConfig>,
+    #[serde(default)]
+    layout: Option<ColumnConfig>,
+    /// Stack of the views above the root.
+    #[serde(default)]
+    views: Vec<View>,
 }
 
 #[derive(Default)]
@@ -34,104 +53,603 @@ pub struct MainComponent {
     config: Config,
     action_tx: UnboundedSender<Action>,
     columns: Vec<ColumnComponent>,
+    /// Number of the columns shown at once, and the rest are hidden.
+    visible: usize,
+    /// Index of the leftmost column shown.
+    offset: usize,
+    /// Show only the focused column with the full width.
+    zoom: bool,
+    /// Areas of the columns as last drawn.
+    areas: Vec<Rect>,
+    /// Keys of an incomplete global key sequence, shown in the status line.
+    global_keys: Vec<Key>,
+    /// The latest error reported with `Action::Error`.
+    error: Option<(DateTime<Local>, String)>,
+    /// Time of the latest error of the watchers shown as a toast.
+    watcher_error: Option<DateTime<Local>>,
+    toasts: Toasts,
+    /// Sum of the versions of the statuses as last drawn, or `None` to redraw.
+    status_version: Option<u64>,
     state: State,
+    theme: Theme,
+    size: Size,
+    /// Directory of the saved columns, or the data directory of the user if not set.
+    data_dir: Option<PathBuf>,
+    /// Restored columns to share the watcher of another column of their account,
+    /// keyed by the id of that column which is signing in.
+    waiting: HashMap<IdType, Vec<IdType>>,
 }
 
 impl MainComponent {
     pub fn new(config: Config, action_tx: UnboundedSender<Action>) -> Self {
         Self {
+            theme: Theme::from(&config.theme),
+            toasts: Toasts::new(&config.keybindings),
             config,
             action_tx,
             columns: Vec::new(),
+            visible: 0,
+            offset: 0,
+            zoom: false,
+            areas: Vec::new(),
+            global_keys: Vec::new(),
+            error: None,
+            watcher_error: None,
+            status_version: None,
             state: State { selected: None },
+            size: Size::default(),
+            data_dir: None,
+            waiting: HashMap::new(),
         }
     }
+    #[cfg(test)]
+    pub fn with_data_dir(mut self, data_dir: PathBuf) -> Self {
+        self.data_dir = Some(data_dir);
+        self
+    }
+    #[cfg(test)]
+    pub fn selected_id(&self) -> Option<IdType> {
+        self.state
+            .selected
+            .map(|selected| self.columns[selected].id)
+    }
     pub async fn save(&self) -> Result<()> {
         let mut appdata = AppData {
             views: Vec::with_capacity(self.columns.len()),
+            selected: self.state.selected,
+            zoom: self.zoom,
         };
-        for view in &self.columns {
-            let config = if let Some(w) = &view.watcher {
+        for column in &self.columns {
+            let config = if let Some(w) = &column.watcher {
                 Some(w.agent.to_config().await)
             } else {
                 None
             };
-            appdata.views.push(ViewData { agent: config });
+            appdata.views.push(ViewData {
+                agent: config,
+                layout: Some(column.layout().clone()),
+                views: column.saved_views(),
+            });
         }
-        let path = Self::appdata_path()?;
+        let path = self.appdata_path()?;
         serde_json::to_writer_pretty(File::create(&path)?, &appdata)?;
         log::info!("saved appdata to: {path:?}");
         Ok(())
     }
-    fn load() -> Result<AppData> {
-        let path = Self::appdata_path()?;
+    pub fn pending_keys(&self) -> &[Key] {
+        self.state
+            .selected
+            .map(|selected| self.columns[selected].pending_keys())
+            .unwrap_or_default()
+    }
+    /// Lay out all the columns again at the next draw, such as for relative timestamps.
+    pub fn invalidate(&mut self) {
+        for column in self.columns.iter_mut() {
+            column.invalidate();
+        }
+    }
+    pub fn set_global_keys(&mut self, keys: &[Key]) {
+        self.global_keys = keys.to_vec();
+    }
+    fn is_account(config: &AgentConfig, account: &str) -> bool {
+        config.session.as_ref().is_some_and(|session| {
+            session.handle.as_str() == account || session.did.as_str() == account
+        })
+    }
+    fn load(&self) -> Result<AppData> {
+        let path = self.appdata_path()?;
         let appdata = serde_json::from_reader::<_, AppData>(File::open(&path)?)?;
         log::info!("loaded appdata from {path:?}");
         Ok(appdata)
     }
-    fn appdata_path() -> Result<PathBuf> {
-        let data_dir = get_data_dir()?;
+    fn appdata_path(&self) -> Result<PathBuf> {
+        let data_dir = match &self.data_dir {
+            Some(data_dir) => data_dir.clone(),
+            None => get_data_dir()?,
+        };
         create_dir_all(&data_dir)?;
         Ok(data_dir.join("appdata.json"))
     }
-}
+    /// Add, show or hide columns to match `num_columns` or `[[columns]]` and the width of the terminal.
+    fn fit_columns(&mut self) -> Result<()> {
+        let restore = self.columns.is_empty();
+        let max_num = self
+            .config
+            .num_columns
+            .unwrap_or(if self.config.columns.is_empty() {
+                usize::MAX
+            } else {
+                self.config.columns.len()
+            });
+        // as many columns as fit with their minimum widths, but at least one
+        let mut num_columns = 0;
+        let mut total = 0;
+        while num_columns < max_num {
+            let min_width = self
+                .columns
+                .get(num_columns)
+                .map_or_else(|| self.config.column(num_columns), |c| c.layout().clone())
+                .min_width();
+            total += usize::from(min_width);
+            if total > usize::from(self.size.width) && num_columns > 0 {
+                break;
+            }
+            num_columns += 1;
+        }
 
-impl Component for MainComponent {
-    fn init(&mut self, size: Size) -> Result<()> {
-        let appdata = if let Ok(appdata) = Self::load() {
-            appdata
+        // on startup, all the saved columns are restored even if some of them are hidden
+        if self.columns.len() < num_columns || restore {
+            let appdata = if let Ok(appdata) = self.load() {
+                appdata
+            } else {
+                log::warn!("failed to load appdata, using default");
+                AppData::default()
+            };
+            let len = if restore {
+                num_columns.max(appdata.views.len())
+            } else {
+                num_columns
+            };
+            if restore {
+                self.state.selected = appdata.selected;
+                self.zoom = appdata.zoom;
+            }
+            // the first column of each account signs in, and the others share its watcher
+            let mut signed_in = self
+                .columns
+                .iter()
+                .filter_map(|column| column.account().map(|account| (account.did, column.id)))
+                .collect::<HashMap<_, _>>();
+            for i in self.columns.len()..len {
+                // the settings in `[[columns]]` take precedence over the saved ones
+                let layout = self
+                    .config
+                    .columns
+                    .get(i)
+                    .cloned()
+                    .or_else(|| appdata.views.get(i).and_then(|view| view.layout.clone()))
+                    .unwrap_or_default();
+                let mut column = ColumnComponent::new(
+                    self.config.clone(),
+                    layout.clone(),
+                    self.action_tx.clone(),
+                );
+                // prefer the saved session of the account set for the column
+                let saved = layout
+                    .account()
+                    .and_then(|account| {
+                        appdata.views.iter().find(|view| {
+                            view.agent
+                                .as_ref()
+                                .is_some_and(|config| Self::is_account(config, account))
+                        })
+                    })
+                    .or_else(|| appdata.views.get(i));
+                match saved {
+                    Some(ViewData {
+                        agent: Some(config),
+                        views,
+                        ..
+                    }) => {
+                        column.restore(views.clone());
+                        let did = config.session.as_ref().map(|s| s.did.as_str().to_string());
+                        match did.as_ref().and_then(|did| signed_in.get(did)) {
+                            // share the watcher of the column with the same account
+                            Some(id) => match self.columns.iter().find(|c| c.id == *id) {
+                                Some(ColumnComponent {
+                                    watcher: Some(watcher),
+                                    ..
+                                }) => {
+                                    column.share(watcher.clone())?;
+                                }
+                                _ => self.waiting.entry(*id).or_default().push(column.id),
+                            },
+                            None => {
+                                if let Some(did) = did {
+                                    signed_in.insert(did, column.id);
+                                }
+                                column.init_with_config(config)?;
+                            }
+                        }
+                    }
+                    _ => column.init(self.size)?,
+                }
+                self.columns.push(column);
+            }
+        }
+        self.visible = num_columns;
+        self.update_visibility()
+    }
+    /// Scroll the shown columns to the selected one, and hide the others.
+    ///
+    /// Hidden columns keep their session and views, and are shown again as they were.
+    fn update_visibility(&mut self) -> Result<()> {
+        let len = self.columns.len();
+        self.state.selected = match len {
+            0 => None,
+            len => Some(self.state.selected.unwrap_or_default().min(len - 1)),
+        };
+        let visible = self.shown();
+        if let Some(selected) = self.state.selected {
+            if selected < self.offset {
+                self.offset = selected;
+            } else if selected >= self.offset + visible {
+                self.offset = selected + 1 - visible;
+            }
+        }
+        self.offset = self.offset.min(len - visible);
+        let range = self.offset..self.offset + visible;
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.set_hidden(!range.contains(&i))?;
+        }
+        Ok(())
+    }
+    /// Number of the columns shown now.
+    fn shown(&self) -> usize {
+        if self.zoom {
+            self.visible.min(1)
         } else {
-            log::warn!("failed to load appdata, using default");
-            AppData::default()
+            self.visible
+        }
+        .min(self.columns.len())
+    }
+    fn focus(&mut self, index: usize) -> Result<Option<Action>> {
+        if let Some(selected) = self.state.selected {
+            if let Some(column) = self.columns.get_mut(selected) {
+                column.is_menu_active = false;
+            }
+        }
+        self.state.selected = Some(index);
+        self.update_visibility()?;
+        Ok(Some(Action::Render))
+    }
+    /// Statuses of the watchers of the columns, without duplicates of the shared ones.
+    fn statuses(&self) -> Vec<&Arc<Status>> {
+        let mut statuses = Vec::<&Arc<Status>>::new();
+        for status in self
+            .columns
+            .iter()
+            .filter_map(|column| column.watcher.as_ref().map(|watcher| &watcher.status))
+        {
+            if !statuses.iter().any(|s| Arc::ptr_eq(s, status)) {
+                statuses.push(status);
+            }
+        }
+        statuses
+    }
+    fn status_version(&self) -> u64 {
+        self.statuses().iter().map(|status| status.version()).sum()
+    }
+    /// Account and status of the focused column, requests in flight of all columns, pending keys and the latest error.
+    fn draw_status(&self, f: &mut Frame<'_>, area: Rect) {
+        let separator = || Span::from(" | ").style(self.theme.muted);
+        let column = self.state.selected.and_then(|i| self.columns.get(i));
+        let status = column
+            .and_then(|column| column.watcher.as_ref())
+            .map(|watcher| &watcher.status);
+
+        let mut left = vec![Span::from(" ")];
+        match column.and_then(|column| column.account()) {
+            Some(account) => {
+                left.push(Span::from(format!("@{}", account.handle)).style(self.theme.handle))
+            }
+            None => left.push(Span::from("not signed in").style(self.theme.muted)),
+        }
+        if let Some(unread) = status.and_then(|status| status.unread()) {
+            left.push(separator());
+            let span = Span::from(format!("{unread} unread"));
+            left.push(if unread > 0 { span.bold() } else { span });
+        }
+        let in_flight = self
+            .statuses()
+            .iter()
+            .map(|status| status.in_flight())
+            .sum::<usize>();
+        if in_flight > 0 {
+            left.push(separator());
+            left.push(Span::from(format!("{in_flight} loading")));
+        }
+        let refreshed = status.map(|status| status.refreshed()).unwrap_or_default();
+        if !refreshed.is_empty() {
+            left.push(separator());
+            left.push(
+                Span::from(
+                    refreshed
+                        .iter()
+                        .map(|(name, time)| format!("{name} {}", time.format("%H:%M:%S")))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+                .style(self.theme.muted),
+            );
+        }
+
+        let mut right = Vec::new();
+        let column_keys = self.pending_keys();
+        let keys = if column_keys.len() >= self.global_keys.len() {
+            column_keys
+        } else {
+            &self.global_keys
         };
+        if !keys.is_empty() {
+            right.push(
+                Span::from(format!(
+                    " {} ",
+                    keys.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ))
+                .reversed(),
+            );
+        }
+        let error = self
+            .statuses()
+            .iter()
+            .filter_map(|status| status.error())
+            .chain(self.error.clone())
+            .max_by_key(|(time, _)| *time);
+        if let Some((time, message)) = error {
+            right.push(
+                Span::from(format!(" {} {message} ", time.format("%H:%M:%S")))
+                    .style(self.theme.danger),
+            );
+        }
 
-        let auto_num = usize::from(size.width) / 75;
-        let num_columns = self
-            .config
-            .num_columns
-            .map_or(auto_num, |n| n.min(auto_num));
+        let right = Line::from(right);
+        let width = (right.width() as u16).min(area.width / 2);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(width)])
+            .split(area);
+        f.render_widget(Paragraph::new(Line::from(left)), layout[0]);
+        f.render_widget(Paragraph::new(right.alignment(Alignment::Right)), layout[1]);
+    }
+    /// The accounts signed in to the columns, without duplicates.
+    fn accounts(&self) -> Vec<Account> {
+        let mut accounts = Vec::<Account>::new();
+        for account in self.columns.iter().filter_map(ColumnComponent::account) {
+            if !accounts.iter().any(|a| a.did == account.did) {
+                accounts.push(account);
+            }
+        }
+        accounts
+    }
+}
 
-        for i in 0..num_columns {
-            let mut column = ColumnComponent::new(self.config.clone(), self.action_tx.clone());
-            if let Some(config) = appdata.views.get(i).and_then(|view| view.agent.as_ref()) {
-                column.init_with_config(config)?;
-            } else {
-                column.init(size)?;
+impl Component for MainComponent {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        for column in self.columns.iter_mut() {
+            column.register_config_handler(config.clone())?;
+        }
+        self.theme = Theme::from(&config.theme);
+        self.toasts.set_keybindings(&config.keybindings);
+        if config.watcher != self.config.watcher {
+            // reconfigured once for each account, to stay shared by its columns
+            let mut reconfigured = Vec::<(Arc<Watcher>, Arc<Watcher>)>::new();
+            for column in self.columns.iter_mut() {
+                let Some(current) = column.watcher.clone() else {
+                    continue;
+                };
+                let watcher = match reconfigured
+                    .iter()
+                    .find(|(old, _)| Arc::ptr_eq(old, &current))
+                {
+                    Some((_, new)) => new.clone(),
+                    None => {
+                        let new = Arc::new(current.reconfigure(config.watcher.clone()));
+                        reconfigured.push((current, new.clone()));
+                        new
+                    }
+                };
+                column.replace_watcher(watcher)?;
+            }
+        }
+        if config.columns != self.config.columns {
+            for (i, column) in self.columns.iter_mut().enumerate() {
+                column.set_layout(config.column(i));
             }
-            self.columns.push(column);
         }
-        if !self.columns.is_empty() {
-            self.state.selected = Some(0);
+        let fit =
+            config.num_columns != self.config.num_columns || config.columns != self.config.columns;
+        self.config = config;
+        if fit {
+            self.fit_columns()?;
         }
         Ok(())
     }
+    fn init(&mut self, size: Size) -> Result<()> {
+        self.size = size;
+        self.fit_columns()
+    }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.toasts.is_history_open() {
+            return Ok(self.toasts.handle_key_events(key).then_some(Action::Render));
+        }
         if let Some(selected) = self.state.selected {
             self.columns[selected].handle_key_events(key)
         } else {
             Ok(None)
         }
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if self.toasts.is_history_open() {
+            return Ok(self
+                .toasts
+                .handle_mouse_events(mouse)
+                .then_some(Action::Render));
+        }
+        let Some(index) = self
+            .areas
+            .iter()
+            .position(|area| area.contains(position(&mouse)))
+            .map(|index| self.offset + index)
+            .filter(|index| *index < self.columns.len())
+        else {
+            return Ok(None);
+        };
+        // a click on another column focuses it, and is also passed to the column
+        let focus = matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
+            && self.state.selected != Some(index);
+        if focus {
+            if let Some(selected) = self.state.selected {
+                self.columns[selected].is_menu_active = false;
+            }
+            self.state.selected = Some(index);
+        }
+        let action = self.columns[index].handle_mouse_events(mouse)?;
+        Ok(action.or(focus.then_some(Action::Render)))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::NextFocus => {
-                if let Some(selected) = self.state.selected {
-                    self.columns[selected].is_menu_active = false;
+            Action::NextFocus if !self.columns.is_empty() => {
+                let len = self.columns.len();
+                return self.focus(self.state.selected.map_or(0, |s| s + 1) % len);
+            }
+            Action::PrevFocus if !self.columns.is_empty() => {
+                let len = self.columns.len();
+                return self.focus(self.state.selected.map_or(0, |s| s + len - 1) % len);
+            }
+            Action::NewColumn => {
+                let mut column = ColumnComponent::new(
+                    self.config.clone(),
+                    ColumnConfig::default(),
+                    self.action_tx.clone(),
+                );
+                column.init_with_accounts(self.accounts())?;
+                let index = self.state.selected.map_or(self.columns.len(), |s| s + 1);
+                self.columns.insert(index, column);
+                return self.focus(index);
+            }
+            Action::CloseColumn => {
+                let Some(selected) = self.state.selected else {
+                    return Ok(None);
+                };
+                if self.columns.len() == 1 {
+                    return Ok(Some(Action::Error(String::from(
+                        "cannot close the last column",
+                    ))));
                 }
-                self.state.selected =
-                    Some(self.state.selected.map_or(0, |s| s + 1) % self.columns.len());
+                // unsubscribe the current view and the unread count before dropping the column
+                let mut column = self.columns.remove(selected);
+                column.close()?;
+                // the next column gets the focus, or the previous one if it was the last
+                self.update_visibility()?;
                 return Ok(Some(Action::Render));
             }
-            Action::PrevFocus => {
+            Action::ToggleZoom => {
+                // the other columns are hidden while zoomed, and keep their views
+                self.zoom = !self.zoom;
+                self.update_visibility()?;
+                return Ok(Some(Action::Render));
+            }
+            Action::MoveColumnLeft => {
+                if let Some(selected) = self.state.selected.filter(|s| *s > 0) {
+                    self.columns.swap(selected, selected - 1);
+                    self.state.selected = Some(selected - 1);
+                    self.update_visibility()?;
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::MoveColumnRight => {
+                if let Some(selected) = self.state.selected.filter(|s| s + 1 < self.columns.len()) {
+                    self.columns.swap(selected, selected + 1);
+                    self.state.selected = Some(selected + 1);
+                    self.update_visibility()?;
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::DuplicateColumn => {
                 if let Some(selected) = self.state.selected {
-                    self.columns[selected].is_menu_active = false;
+                    let column = self.columns[selected].duplicate()?;
+                    self.columns.insert(selected + 1, column);
+                    return self.focus(selected + 1);
                 }
-                self.state.selected = Some(
-                    self.state
-                        .selected
-                        .map_or(0, |s| s + self.columns.len() - 1)
-                        % self.columns.len(),
-                );
+            }
+            Action::Login((id, agent)) => {
+                let Some(column) = self.columns.iter_mut().find(|column| column.id == id) else {
+                    return Ok(None);
+                };
+                let action = column.update(Action::Login((id, agent)))?;
+                if let Some(waiting) = self.waiting.remove(&id) {
+                    let watcher = self
+                        .columns
+                        .iter()
+                        .find(|column| column.id == id)
+                        .and_then(|column| column.watcher.clone());
+                    if let Some(watcher) = watcher {
+                        for column in self
+                            .columns
+                            .iter_mut()
+                            .filter(|column| waiting.contains(&column.id))
+                        {
+                            column.share(watcher.clone())?;
+                        }
+                    }
+                }
+                return Ok(action);
+            }
+            Action::Resize(width, height) => {
+                self.size = Size::new(width, height);
+                self.fit_columns()?;
+                return Ok(Some(Action::Render));
+            }
+            Action::Tick(_) => {
+                let version = self.status_version();
+                let mut render = self.status_version != Some(version);
+                self.status_version = Some(version);
+                // new errors of the watchers are also shown as toasts
+                let errors = self
+                    .statuses()
+                    .iter()
+                    .filter_map(|status| status.error())
+                    .filter(|(time, _)| Some(*time) > self.watcher_error)
+                    .collect::<Vec<_>>();
+                for (time, message) in errors {
+                    self.watcher_error = self.watcher_error.max(Some(time));
+                    self.toasts.push(Toast::error(message));
+                    render = true;
+                }
+                render |= self.toasts.expire();
+                for column in self.columns.iter_mut() {
+                    render |= column.update(action.clone())?.is_some();
+                }
+                if render {
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::Error(message) => {
+                // shown at the next tick, so that an error in drawing is not redrawn at once
+                self.toasts.push(Toast::error(message.clone()));
+                self.error = Some((Local::now(), message));
+                self.status_version = None;
+            }
+            Action::Toast(toast) => {
+                self.toasts.push(toast);
+                return Ok(Some(Action::Render));
+            }
+            Action::ToastHistory => {
+                self.toasts.toggle_history();
                 return Ok(Some(Action::Render));
             }
             _ => {
@@ -145,22 +663,359 @@ impl Component for MainComponent {
         Ok(None)
     }
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+        let area = layout[0];
+        self.draw_status(f, layout[1]);
+        let end = self.offset + self.shown();
+        let specs = self.columns[self.offset..end]
+            .iter()
+            .map(|column| column.layout().clone())
+            .collect::<Vec<_>>();
         let layout = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(self.columns.iter().map(|_| Constraint::Fill(1)))
+            .constraints(
+                column_widths(area.width, &specs)
+                    .into_iter()
+                    .map(Constraint::Length),
+            )
             .split(area);
-        for (i, (area, view)) in layout.iter().zip(self.columns.iter_mut()).enumerate() {
+        self.areas = layout.to_vec();
+        for (i, (area, view)) in layout
+            .iter()
+            .zip(self.columns[self.offset..end].iter_mut())
+            .enumerate()
+        {
             let mut block = Block::bordered()
-                .title(view.title())
+                .title(text::truncate(
+                    &view.title(),
+                    usize::from(area.width.saturating_sub(2)),
+                ))
                 .title_alignment(Alignment::Center);
-            if self.state.selected == Some(i) {
+            if self.state.selected == Some(self.offset + i) {
                 block = block
                     .border_type(BorderType::Double)
-                    .border_style(Style::default().reset().bold());
+                    .border_style(Style::default().reset().patch(self.theme.focused));
             }
             view.draw(f, block.inner(*area))?;
             f.render_widget(block, *area);
         }
+        self.toasts.draw(f, area, &self.theme);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeServer;
+    use crate::backend::types::FeedSourceInfo;
+    use crate::components::views::types::{Action as ViewAction, Transition};
+    use std::env;
+    use std::time::Duration;
+    use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+    /// Data directory of a test, removed when the test ends.
+    struct DataDir(PathBuf);
+
+    impl DataDir {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("tuisky-main-{name}-{}", std::process::id())))
+        }
+    }
+
+    impl Drop for DataDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn main_component(dir: &DataDir, width: u16) -> MainComponent {
+        main_component_with_rx(dir, width).0
+    }
+
+    fn main_component_with_rx(
+        dir: &DataDir,
+        width: u16,
+    ) -> (MainComponent, UnboundedReceiver<Action>) {
+        let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let mut main =
+            MainComponent::new(Config::default(), action_tx).with_data_dir(dir.0.clone());
+        main.init(Size::new(width, 24))
+            .expect("failed to init main component");
+        (main, action_rx)
+    }
+
+    /// Let the spawned tasks run, and handle the actions sent from them.
+    async fn settle(main: &mut MainComponent, action_rx: &mut UnboundedReceiver<Action>) {
+        loop {
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            let Ok(mut action) = action_rx.try_recv() else {
+                break;
+            };
+            while let Some(next) = main.update(action).expect("failed to update") {
+                action = next;
+            }
+        }
+    }
+
+    fn ids(main: &MainComponent) -> Vec<IdType> {
+        main.columns.iter().map(|column| column.id).collect()
+    }
+
+    fn update(main: &mut MainComponent, action: Action) {
+        main.update(action).expect("failed to update");
+    }
+
+    #[tokio::test]
+    async fn visibility() {
+        // two columns of the default minimum width fit
+        let dir = DataDir::new("visibility");
+        let mut main = main_component(&dir, 160);
+        assert_eq!((main.columns.len(), main.visible), (2, 2));
+        update(&mut main, Action::NextFocus);
+        update(&mut main, Action::NewColumn);
+        assert_eq!(main.columns.len(), 3);
+        // the new column is focused, and the shown ones are scrolled to it
+        assert_eq!((main.state.selected, main.offset), (Some(2), 1));
+        update(&mut main, Action::PrevFocus);
+        assert_eq!((main.state.selected, main.offset), (Some(1), 1));
+        update(&mut main, Action::PrevFocus);
+        assert_eq!((main.state.selected, main.offset), (Some(0), 0));
+        update(&mut main, Action::ToggleZoom);
+        assert_eq!(main.shown(), 1);
+        update(&mut main, Action::PrevFocus);
+        assert_eq!((main.state.selected, main.offset), (Some(2), 2));
+        update(&mut main, Action::ToggleZoom);
+        assert_eq!((main.shown(), main.offset), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn close_column() {
+        let server = FakeServer::default();
+        let dir = DataDir::new("close");
+        let mut main = main_component(&dir, 160);
+        let before = ids(&main);
+        update(
+            &mut main,
+            Action::Login((before[0], Box::new(server.agent().await))),
+        );
+        let watcher = main.columns[0].watcher.clone().expect("no watcher");
+        assert_eq!(watcher.unread_count_subscribers(), 1);
+        update(&mut main, Action::CloseColumn);
+        assert_eq!(ids(&main), before[1..]);
+        assert_eq!(main.state.selected, Some(0));
+        // the unread count of the closed column is no longer polled
+        assert_eq!(watcher.unread_count_subscribers(), 0);
+        // the last column is kept
+        assert!(matches!(
+            main.update(Action::CloseColumn),
+            Ok(Some(Action::Error(_)))
+        ));
+        assert_eq!(ids(&main), before[1..]);
+    }
+
+    #[tokio::test]
+    async fn move_column() {
+        let dir = DataDir::new("move");
+        let mut main = main_component(&dir, 240);
+        let before = ids(&main);
+        assert_eq!(before.len(), 3);
+        update(&mut main, Action::MoveColumnRight);
+        assert_eq!(ids(&main), [before[1], before[0], before[2]]);
+        assert_eq!(main.state.selected, Some(1));
+        update(&mut main, Action::MoveColumnRight);
+        update(&mut main, Action::MoveColumnRight);
+        assert_eq!(ids(&main), [before[1], before[2], before[0]]);
+        assert_eq!(main.state.selected, Some(2));
+        update(&mut main, Action::MoveColumnLeft);
+        assert_eq!(ids(&main), [before[1], before[0], before[2]]);
+        assert_eq!(main.state.selected, Some(1));
+    }
+
+    #[tokio::test]
+    async fn duplicate_column() {
+        let server = FakeServer::default();
+        let dir = DataDir::new("duplicate");
+        let mut main = main_component(&dir, 160);
+        let id = main.columns[0].id;
+        update(
+            &mut main,
+            Action::Login((id, Box::new(server.agent().await))),
+        );
+        let view = View::Feed(Box::new(FeedSourceInfo::Search(String::from("tuisky"))));
+        update(
+            &mut main,
+            Action::View((
+                id,
+                ViewAction::Transition(Transition::Push(Box::new(view.clone()))),
+            )),
+        );
+        update(&mut main, Action::DuplicateColumn);
+        assert_eq!(main.columns.len(), 3);
+        assert_eq!(main.state.selected, Some(1));
+        let (original, duplicate) = (&main.columns[0], &main.columns[1]);
+        assert_ne!(original.id, duplicate.id);
+        assert!(Arc::ptr_eq(
+            original.watcher.as_ref().expect("no watcher"),
+            duplicate.watcher.as_ref().expect("no watcher")
+        ));
+        assert_eq!(duplicate.saved_views(), vec![view]);
+    }
+
+    #[tokio::test]
+    async fn save_and_restore() {
+        let server = FakeServer::default();
+        let dir = DataDir::new("save");
+        let mut main = main_component(&dir, 80);
+        let id = main.columns[0].id;
+        main.columns[0].set_layout(ColumnConfig {
+            weight: Some(2),
+            ..Default::default()
+        });
+        update(
+            &mut main,
+            Action::Login((id, Box::new(server.agent().await))),
+        );
+        let view = View::Feed(Box::new(FeedSourceInfo::Search(String::from("tuisky"))));
+        update(
+            &mut main,
+            Action::View((
+                id,
+                ViewAction::Transition(Transition::Push(Box::new(view.clone()))),
+            )),
+        );
+        main.save().await.expect("failed to save");
+
+        let mut appdata = main.load().expect("failed to load");
+        assert_eq!(appdata.views.len(), 1);
+        assert_eq!(appdata.views[0].views, vec![view.clone()]);
+        assert_eq!(
+            appdata.views[0].layout.as_ref().and_then(|l| l.weight),
+            Some(2)
+        );
+        // signed in again without resuming the session from the network
+        if let Some(config) = appdata.views[0].agent.as_mut() {
+            config.session = None;
+        }
+        serde_json::to_writer(
+            File::create(main.appdata_path().expect("no path")).expect("failed to create"),
+            &appdata,
+        )
+        .expect("failed to write appdata");
+
+        let mut restored = main_component(&dir, 80);
+        let column = &restored.columns[0];
+        let id = column.id;
+        assert_eq!(column.layout().weight, Some(2));
+        update(
+            &mut restored,
+            Action::Login((id, Box::new(server.agent().await))),
+        );
+        let views = restored.columns[0]
+            .views
+            .iter()
+            .map(|view| view.view())
+            .collect::<Vec<_>>();
+        assert_eq!(views, vec![View::Root, view]);
+    }
+
+    fn same_watcher(a: &ColumnComponent, b: &ColumnComponent) -> bool {
+        match (&a.watcher, &b.watcher) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    #[tokio::test]
+    async fn share_account() {
+        let server = FakeServer::default();
+        let dir = DataDir::new("share");
+        let (mut main, mut action_rx) = main_component_with_rx(&dir, 160);
+        let id = main.columns[0].id;
+        update(
+            &mut main,
+            Action::Login((id, Box::new(server.agent().await))),
+        );
+        settle(&mut main, &mut action_rx).await;
+        // choose the account of the first column in the new one
+        update(&mut main, Action::NewColumn);
+        let id = main.columns[1].id;
+        update(&mut main, Action::View((id, ViewAction::Enter)));
+        settle(&mut main, &mut action_rx).await;
+        assert!(same_watcher(&main.columns[0], &main.columns[1]));
+        assert!(!same_watcher(&main.columns[0], &main.columns[2]));
+        // the unread count is polled once for both
+        const GET_UNREAD_COUNT: &str = "app.bsky.notification.getUnreadCount";
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while server.calls(GET_UNREAD_COUNT).is_empty() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("not polled");
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(server.calls(GET_UNREAD_COUNT).len(), 1);
+        // and the watcher is still shared after it is reconfigured
+        let before = main.columns[0].watcher.clone().expect("no watcher");
+        let mut config = Config::default();
+        config.watcher.intervals.preferences += 1;
+        main.register_config_handler(config)
+            .expect("failed to register config handler");
+        assert!(same_watcher(&main.columns[0], &main.columns[1]));
+        let after = main.columns[0].watcher.as_ref().expect("no watcher");
+        assert!(!Arc::ptr_eq(&before, after));
+        assert!(Arc::ptr_eq(&before.status, &after.status));
+    }
+
+    #[tokio::test]
+    async fn restore_shared() {
+        let server = FakeServer::default();
+        let mut config = server.agent().await.to_config().await;
+        // nothing listens, so that the agent is not signed in from the saved session
+        config.endpoint = String::from("http://127.0.0.1:9");
+        let view = View::Feed(Box::new(FeedSourceInfo::Search(String::from("tuisky"))));
+        let appdata = AppData {
+            views: vec![
+                ViewData {
+                    agent: Some(config.clone()),
+                    ..Default::default()
+                },
+                ViewData {
+                    agent: Some(config),
+                    layout: None,
+                    views: vec![view.clone()],
+                },
+            ],
+            ..Default::default()
+        };
+        let dir = DataDir::new("restore-shared");
+        let main = main_component(&dir, 0);
+        serde_json::to_writer(
+            File::create(main.appdata_path().expect("no path")).expect("failed to create"),
+            &appdata,
+        )
+        .expect("failed to write appdata");
+
+        let mut main = main_component(&dir, 160);
+        assert_eq!(main.columns.len(), 2);
+        let id = main.columns[0].id;
+        update(
+            &mut main,
+            Action::Login((id, Box::new(server.agent().await))),
+        );
+        assert!(same_watcher(&main.columns[0], &main.columns[1]));
+        let views = main.columns[1]
+            .views
+            .iter()
+            .map(|view| view.view())
+            .collect::<Vec<_>>();
+        assert_eq!(views, vec![View::Root, view]);
+    }
+}
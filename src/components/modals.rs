@@ -6,16 +6,26 @@ pub mod types;
 pub use self::embed::EmbedModalComponent;
 use self::types::Action;
 use super::views::types::Action as ViewsAction;
+use crate::config::Config;
 use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::layout::Rect;
 use ratatui::Frame;
 
 pub trait ModalComponent {
+    #[allow(unused_variables)]
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        Ok(())
+    }
     #[allow(unused_variables)]
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         Ok(None)
     }
+    /// Focus the clicked field, and return the action to pass to `update` such as `Enter` on a button.
+    #[allow(unused_variables)]
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<ViewsAction>> {
+        Ok(None)
+    }
     #[allow(unused_variables)]
     fn update(&mut self, action: ViewsAction) -> Result<Option<Action>> {
         Ok(None)
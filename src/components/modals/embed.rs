@@ -1,14 +1,17 @@
+use super::super::mouse::{ListAreas, position};
+use super::super::text;
 use super::super::views::types::Action as ViewsAction;
 use super::embed_images::EmbedImagesModalComponent;
 use super::embed_record::EmbedRecordModalComponent;
 use super::types::{Data, EmbedData, ImageData};
 use super::{Action, ModalComponent};
+use crate::config::{Config, Theme};
 use bsky_sdk::api::com::atproto::repo::strong_ref;
 use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{Block, BorderType, Clear, List, ListState, Padding};
 use tokio::sync::mpsc::UnboundedSender;
@@ -17,9 +20,13 @@ pub struct EmbedModalComponent {
     action_tx: UnboundedSender<ViewsAction>,
     embeds_state: ListState,
     actions_state: ListState,
+    embeds_areas: ListAreas,
+    actions_areas: ListAreas,
     record: Option<strong_ref::Main>,
     images: Vec<ImageData>,
     child: Option<Box<dyn ModalComponent>>,
+    config: Config,
+    theme: Theme,
 }
 
 impl EmbedModalComponent {
@@ -33,14 +40,26 @@ impl EmbedModalComponent {
             action_tx,
             embeds_state: Default::default(),
             actions_state: Default::default(),
+            embeds_areas: Default::default(),
+            actions_areas: Default::default(),
             record,
             images,
             child: None,
+            config: Config::default(),
+            theme: Theme::default(),
         }
     }
 }
 
 impl ModalComponent for EmbedModalComponent {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        if let Some(child) = self.child.as_mut() {
+            child.register_config_handler(config.clone())?;
+        }
+        self.theme = Theme::from(&config.theme);
+        self.config = config;
+        Ok(())
+    }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         if let Some(child) = self.child.as_mut() {
             child.handle_key_events(key)
@@ -48,6 +67,31 @@ impl ModalComponent for EmbedModalComponent {
             Ok(None)
         }
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<ViewsAction>> {
+        if let Some(child) = self.child.as_mut() {
+            return child.handle_mouse_events(mouse);
+        }
+        Ok(match mouse.kind {
+            MouseEventKind::ScrollDown => Some(ViewsAction::NextItem),
+            MouseEventKind::ScrollUp => Some(ViewsAction::PrevItem),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = position(&mouse);
+                // open the clicked embed or action at once
+                if let Some(i) = self.embeds_areas.index_at(position) {
+                    self.embeds_state.select(Some(i));
+                    self.actions_state.select(None);
+                    Some(ViewsAction::Enter)
+                } else if let Some(i) = self.actions_areas.index_at(position) {
+                    self.embeds_state.select(None);
+                    self.actions_state.select(Some(i));
+                    Some(ViewsAction::Enter)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
     fn update(&mut self, action: ViewsAction) -> Result<Option<Action>> {
         if let Some(child) = self.child.as_mut() {
             return Ok(match child.update(action)? {
@@ -168,6 +212,9 @@ impl ModalComponent for EmbedModalComponent {
                     }
                     _ => {}
                 }
+                if let Some(child) = self.child.as_mut() {
+                    child.register_config_handler(self.config.clone())?;
+                }
                 Some(Action::Render)
             }
             ViewsAction::Back => Some(Action::Cancel),
@@ -194,39 +241,47 @@ impl ModalComponent for EmbedModalComponent {
         ])
         .areas(inner);
 
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.embed);
+        // long paths and alt texts are cut to keep the items in one line each
+        let width = usize::from(block.inner(embeds).width);
         let mut embed_items = Vec::new();
         if let Some(record) = &self.record {
             embed_items.push(Text::from(vec![
-                Line::from(format!("record: {}", record.uri)),
+                Line::from(text::truncate(&format!("record: {}", record.uri), width)),
                 Line::from(format!("  {}", record.cid.as_ref())).dim(),
             ]));
         }
         for (i, image) in self.images.iter().enumerate() {
             embed_items.push(Text::from(vec![
-                Line::from(format!("image{}: {}", i + 1, image.path)),
-                Line::from(format!("  {}", image.alt)).dim(),
+                Line::from(text::truncate(
+                    &format!("image{}: {}", i + 1, image.path),
+                    width,
+                )),
+                Line::from(text::truncate(&format!("  {}", image.alt), width)).dim(),
             ]));
         }
+        let heights = embed_items.iter().map(Text::height).collect::<Vec<_>>();
+        self.embeds_areas.update(block.inner(embeds), 0, heights);
         f.render_stateful_widget(
             List::new(embed_items)
-                .block(
-                    Block::bordered()
-                        .border_type(BorderType::Rounded)
-                        .border_style(Color::Yellow),
-                )
-                .highlight_style(Style::reset().reversed()),
+                .block(block)
+                .highlight_style(Style::reset().patch(self.theme.selected)),
             embeds,
             &mut self.embeds_state,
         );
+        let block = Block::default().padding(Padding::horizontal(1));
+        self.actions_areas.update(block.inner(actions), 0, [1; 4]);
         f.render_stateful_widget(
             List::new([
                 Line::from("Add images"),
                 Line::from("Add external").dim(),
                 Line::from("Add record"),
-                Line::from("OK").centered().blue(),
+                Line::styled("OK", self.theme.button).centered(),
             ])
-            .block(Block::default().padding(Padding::horizontal(1)))
-            .highlight_style(Style::default().reversed()),
+            .block(block)
+            .highlight_style(Style::default().patch(self.theme.selected)),
             actions,
             &mut self.actions_state,
         );
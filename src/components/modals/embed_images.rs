@@ -1,8 +1,10 @@
+use super::super::mouse::is_click;
 use super::super::views::types::Action as ViewsAction;
 use super::types::{Action, Data, ImageData};
 use super::ModalComponent;
+use crate::config::{Config, Theme};
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use image::ImageReader;
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
 use ratatui::style::{Color, Style, Stylize};
@@ -17,6 +19,7 @@ pub struct Image {
     pub alt: TextArea<'static>,
 }
 
+#[derive(Clone, Copy)]
 enum Focus {
     Path,
     Alt,
@@ -53,8 +56,11 @@ enum State {
 pub struct EmbedImagesModalComponent {
     image: Image,
     focus: Focus,
+    /// Areas of the fields and the buttons as last drawn.
+    areas: Vec<(Focus, Rect)>,
     state: State,
     index: Option<usize>,
+    theme: Theme,
 }
 
 impl EmbedImagesModalComponent {
@@ -77,8 +83,10 @@ impl EmbedImagesModalComponent {
         let mut ret = Self {
             image,
             focus: Focus::Path,
+            areas: Vec::new(),
             state: State::None,
             index: init.map(|(i, _)| i),
+            theme: Theme::default(),
         };
         ret.check_path();
         ret
@@ -104,8 +112,8 @@ impl EmbedImagesModalComponent {
             };
             self.image.path.set_block(match self.state {
                 State::None => block.border_style(Color::Reset),
-                State::Ok => block.border_style(Color::Green),
-                State::Error => block.border_style(Color::Red),
+                State::Ok => block.border_style(self.theme.valid),
+                State::Error => block.border_style(self.theme.invalid),
             });
         }
     }
@@ -134,6 +142,11 @@ impl EmbedImagesModalComponent {
 }
 
 impl ModalComponent for EmbedImagesModalComponent {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        self.check_path();
+        Ok(())
+    }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         match self.focus {
             Focus::Path => {
@@ -167,6 +180,21 @@ impl ModalComponent for EmbedImagesModalComponent {
         }
         Ok(None)
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<ViewsAction>> {
+        let Some(focus) = self
+            .areas
+            .iter()
+            .find(|(_, area)| is_click(&mouse, *area))
+            .map(|(focus, _)| *focus)
+        else {
+            return Ok(None);
+        };
+        self.update_focus(focus);
+        Ok(Some(match focus {
+            Focus::Ok | Focus::Delete => ViewsAction::Enter,
+            _ => ViewsAction::Render,
+        }))
+    }
     fn update(&mut self, action: ViewsAction) -> Result<Option<Action>> {
         Ok(match action {
             ViewsAction::NextItem => {
@@ -221,26 +249,31 @@ impl ModalComponent for EmbedImagesModalComponent {
         let layout = Layout::vertical(constraints).split(inner);
         let mut line = Line::from("OK").centered();
         line = match self.state {
-            State::Ok => line.blue(),
+            State::Ok => line.patch_style(self.theme.button),
             _ => line.dim(),
         };
         if let Focus::Ok = self.focus {
-            line = line.reversed();
+            line = line.patch_style(self.theme.selected);
         }
         f.render_widget(&self.image.path, layout[0]);
         f.render_widget(&self.image.alt, layout[1]);
         f.render_widget(line, layout[2]);
+        self.areas = vec![
+            (Focus::Path, layout[0]),
+            (Focus::Alt, layout[1]),
+            (Focus::Ok, layout[2]),
+        ];
         if let Some(area) = layout.get(3) {
             f.render_widget(
-                Line::from("Delete")
+                Line::styled("Delete", self.theme.danger)
                     .centered()
-                    .red()
                     .patch_style(match self.focus {
-                        Focus::Delete => Style::default().reversed(),
+                        Focus::Delete => self.theme.selected,
                         _ => Style::default(),
                     }),
                 *area,
-            )
+            );
+            self.areas.push((Focus::Delete, *area));
         }
         Ok(())
     }
@@ -1,12 +1,14 @@
+use super::super::mouse::is_click;
 use super::super::views::types::Action as ViewsAction;
 use super::types::{Action, Data};
 use super::ModalComponent;
+use crate::backend::client;
+use crate::config::Theme;
 use bsky_sdk::agent::config::Config;
 use bsky_sdk::api::com::atproto::repo::strong_ref;
 use bsky_sdk::api::types::string::{AtIdentifier, Cid, Nsid, RecordKey};
-use bsky_sdk::BskyAgent;
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use ratatui::layout::{Constraint, Layout, Margin, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::Line;
@@ -28,6 +30,7 @@ static RE_AT_URI: LazyLock<Regex> = LazyLock::new(|| {
 
 const PUBLIC_API_ENDPOINT: &str = "https://public.api.bsky.app";
 
+#[derive(Clone, Copy)]
 enum Focus {
     None,
     Input,
@@ -67,7 +70,10 @@ pub struct EmbedRecordModalComponent {
     input: TextArea<'static>,
     record: Option<String>,
     focus: Focus,
+    /// Areas of the field and the buttons as last drawn.
+    areas: Vec<(Focus, Rect)>,
     state: Arc<Mutex<State>>,
+    theme: Theme,
 }
 
 impl EmbedRecordModalComponent {
@@ -84,7 +90,9 @@ impl EmbedRecordModalComponent {
             input,
             record: init,
             focus: Focus::Input,
+            areas: Vec::new(),
             state: Arc::new(Mutex::new(State::None)),
+            theme: Theme::default(),
         }
     }
     fn get_record(&self, uri: &str) -> Option<&str> {
@@ -134,7 +142,7 @@ impl EmbedRecordModalComponent {
         }
     }
     async fn try_get_record(collection: Nsid, repo: AtIdentifier, rkey: RecordKey) -> Result<Cid> {
-        let agent = BskyAgent::builder()
+        let agent = client::builder()
             .config(Config {
                 endpoint: PUBLIC_API_ENDPOINT.to_string(),
                 ..Default::default()
@@ -161,6 +169,10 @@ impl EmbedRecordModalComponent {
 }
 
 impl ModalComponent for EmbedRecordModalComponent {
+    fn register_config_handler(&mut self, config: crate::config::Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        Ok(())
+    }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         if matches!(self.focus, Focus::Input)
             && !matches!(
@@ -180,6 +192,21 @@ impl ModalComponent for EmbedRecordModalComponent {
         }
         Ok(None)
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<ViewsAction>> {
+        let Some(focus) = self
+            .areas
+            .iter()
+            .find(|(_, area)| is_click(&mouse, *area))
+            .map(|(focus, _)| *focus)
+        else {
+            return Ok(None);
+        };
+        self.update_focus(focus);
+        Ok(Some(match focus {
+            Focus::Ok | Focus::Delete => ViewsAction::Enter,
+            _ => ViewsAction::Render,
+        }))
+    }
     fn update(&mut self, action: ViewsAction) -> Result<Option<Action>> {
         Ok(match action {
             ViewsAction::NextItem => {
@@ -244,8 +271,8 @@ impl ModalComponent for EmbedRecordModalComponent {
             let block = block.clone();
             self.input.set_block(match &state {
                 State::None => block.border_style(Color::Reset),
-                State::Ok(_) => block.border_style(Color::Green),
-                State::Error(_) => block.border_style(Color::Red),
+                State::Ok(_) => block.border_style(self.theme.valid),
+                State::Error(_) => block.border_style(self.theme.invalid),
             });
         }
         f.render_widget(&self.input, layout[0]);
@@ -253,7 +280,7 @@ impl ModalComponent for EmbedRecordModalComponent {
             match &state {
                 State::None => Line::from(""),
                 State::Ok(cid) => Line::from(format!("CID: {}", cid.as_ref())).bold(),
-                State::Error(err) => Line::from(err.clone()).red(),
+                State::Error(err) => Line::styled(err.clone(), self.theme.invalid),
             },
             layout[1],
         );
@@ -263,25 +290,27 @@ impl ModalComponent for EmbedRecordModalComponent {
                 _ => "Get Record",
             })
             .centered()
-            .blue()
+            .patch_style(self.theme.button)
             .patch_style(if let Focus::Ok = self.focus {
-                Style::default().reversed()
+                self.theme.selected
             } else {
                 Style::default()
             }),
             layout[2],
         );
+        self.areas = vec![(Focus::Input, layout[0]), (Focus::Ok, layout[2])];
         if let Some(area) = layout.get(3) {
             f.render_widget(
-                Line::from("Delete").centered().red().patch_style(
-                    if let Focus::Delete = self.focus {
-                        Style::default().reversed()
+                Line::styled("Delete", self.theme.danger)
+                    .centered()
+                    .patch_style(if let Focus::Delete = self.focus {
+                        self.theme.selected
                     } else {
                         Style::default()
-                    },
-                ),
+                    }),
                 *area,
-            )
+            );
+            self.areas.push((Focus::Delete, *area));
         }
 
         Ok(())
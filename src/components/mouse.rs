@@ -0,0 +1,192 @@
+use super::views::types::Action;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use ratatui::widgets::ListState;
+
+/// Position of the mouse cursor in the terminal.
+pub fn position(mouse: &MouseEvent) -> Position {
+    Position::new(mouse.column, mouse.row)
+}
+
+/// Whether the event is a click of the left button in the area.
+pub fn is_click(mouse: &MouseEvent, area: Rect) -> bool {
+    matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left)) && area.contains(position(mouse))
+}
+
+/// Areas of the items of a list as it was last drawn, to find the item under the mouse cursor
+/// and to move the selection by pages.
+#[derive(Debug, Default)]
+pub struct ListAreas {
+    items: Vec<(usize, Rect)>,
+    heights: Vec<usize>,
+    height: u16,
+}
+
+impl ListAreas {
+    /// Record the items drawn in `area` from the `offset` of the list, given the heights of all items.
+    pub fn update(&mut self, area: Rect, offset: usize, heights: impl IntoIterator<Item = usize>) {
+        self.items.clear();
+        self.heights = heights.into_iter().collect();
+        self.height = area.height;
+        let mut y = area.y;
+        for (i, height) in self.heights.iter().copied().enumerate().skip(offset) {
+            if y >= area.bottom() {
+                break;
+            }
+            let height = u16::try_from(height)
+                .unwrap_or(u16::MAX)
+                .min(area.bottom() - y);
+            self.items
+                .push((i, Rect::new(area.x, y, area.width, height)));
+            y += height;
+        }
+    }
+    pub fn index_at(&self, position: Position) -> Option<usize> {
+        self.items
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            .map(|(i, _)| *i)
+    }
+    /// Move the selection by a page or half a page of the drawn height, or to the first or last item.
+    pub fn navigate(&self, action: &Action, state: &mut ListState) -> Option<Action> {
+        let last = self.heights.len().checked_sub(1)?;
+        let selected = state.selected().unwrap_or_default().min(last);
+        let index = match action {
+            Action::PageDown => self.forward(selected, self.height),
+            Action::PageUp => self.backward(selected, self.height),
+            Action::HalfPageDown => self.forward(selected, self.height / 2),
+            Action::HalfPageUp => self.backward(selected, self.height / 2),
+            // the newest items come first
+            Action::Top | Action::Newest => 0,
+            Action::Bottom => last,
+            _ => return None,
+        };
+        state.select(Some(index));
+        Some(Action::Render)
+    }
+    /// Index of the item `rows` rows below the selected one, moving at least one item.
+    fn forward(&self, selected: usize, rows: u16) -> usize {
+        let mut index = selected;
+        let mut total = 0;
+        while index + 1 < self.heights.len() {
+            total += self.heights[index];
+            if total > usize::from(rows) && index > selected {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+    /// Index of the item `rows` rows above the selected one, moving at least one item.
+    fn backward(&self, selected: usize, rows: u16) -> usize {
+        let mut index = selected;
+        let mut total = 0;
+        while index > 0 {
+            total += self.heights[index - 1];
+            if total > usize::from(rows) && index < selected {
+                break;
+            }
+            index -= 1;
+        }
+        index
+    }
+    /// Move the selection with the wheel, and select the clicked item or open it if it is already selected.
+    pub fn handle_mouse_events(&self, mouse: MouseEvent, state: &mut ListState) -> Option<Action> {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => Some(Action::NextItem),
+            MouseEventKind::ScrollUp => Some(Action::PrevItem),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let index = self.index_at(position(&mouse))?;
+                if state.selected() == Some(index) {
+                    Some(Action::Enter)
+                } else {
+                    state.select(Some(index));
+                    Some(Action::Render)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn event(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn index_at() {
+        let mut areas = ListAreas::default();
+        areas.update(Rect::new(2, 3, 10, 6), 1, [5, 2, 3, 4]);
+        assert_eq!(areas.index_at(Position::new(2, 2)), None);
+        assert_eq!(areas.index_at(Position::new(2, 3)), Some(1));
+        assert_eq!(areas.index_at(Position::new(11, 4)), Some(1));
+        assert_eq!(areas.index_at(Position::new(12, 4)), None);
+        assert_eq!(areas.index_at(Position::new(5, 5)), Some(2));
+        assert_eq!(areas.index_at(Position::new(5, 8)), Some(3));
+        assert_eq!(areas.index_at(Position::new(5, 9)), None);
+    }
+
+    #[test]
+    fn click() {
+        let mut areas = ListAreas::default();
+        areas.update(Rect::new(0, 0, 10, 10), 0, [2, 2]);
+        let mut state = ListState::default();
+        let click = event(MouseEventKind::Down(MouseButton::Left), 1, 2);
+        assert!(matches!(
+            areas.handle_mouse_events(click, &mut state),
+            Some(Action::Render)
+        ));
+        assert_eq!(state.selected(), Some(1));
+        assert!(matches!(
+            areas.handle_mouse_events(click, &mut state),
+            Some(Action::Enter)
+        ));
+        let outside = event(MouseEventKind::Down(MouseButton::Left), 1, 5);
+        assert!(areas.handle_mouse_events(outside, &mut state).is_none());
+        assert_eq!(state.selected(), Some(1));
+        let scroll = event(MouseEventKind::ScrollDown, 1, 5);
+        assert!(matches!(
+            areas.handle_mouse_events(scroll, &mut state),
+            Some(Action::NextItem)
+        ));
+    }
+
+    #[test]
+    fn navigate() {
+        let mut areas = ListAreas::default();
+        let mut state = ListState::default();
+        assert!(areas.navigate(&Action::PageDown, &mut state).is_none());
+
+        areas.update(Rect::new(0, 0, 10, 10), 0, [2, 2, 2, 2, 2, 2, 2, 2, 20, 2]);
+        let mut select = |action: Action| {
+            assert!(matches!(
+                areas.navigate(&action, &mut state),
+                Some(Action::Render)
+            ));
+            state.selected()
+        };
+        assert_eq!(select(Action::PageDown), Some(5));
+        assert_eq!(select(Action::HalfPageDown), Some(7));
+        assert_eq!(select(Action::PageDown), Some(8));
+        // an item taller than the page is passed at once
+        assert_eq!(select(Action::PageDown), Some(9));
+        assert_eq!(select(Action::PageDown), Some(9));
+        assert_eq!(select(Action::HalfPageUp), Some(8));
+        assert_eq!(select(Action::PageUp), Some(3));
+        assert_eq!(select(Action::PageUp), Some(0));
+        assert_eq!(select(Action::Bottom), Some(9));
+        assert_eq!(select(Action::Top), Some(0));
+        assert_eq!(select(Action::PageUp), Some(0));
+        assert!(areas.navigate(&Action::NextItem, &mut state).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+use std::ops::Range;
+use unicode_linebreak::{BreakOpportunity, linebreaks};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Maximum length of the text of a post in graphemes, as checked by the server.
+pub const MAX_GRAPHEMES: usize = 300;
+/// Maximum length of the text of a post in UTF-8 bytes, as checked by the server.
+pub const MAX_BYTES: usize = 3000;
+
+/// Number of the cells the text takes in the terminal.
+///
+/// Counted by grapheme clusters in the same way as ratatui draws them,
+/// so that emoji sequences and combining characters take the cells they are drawn in.
+pub fn width(text: &str) -> usize {
+    text.graphemes(true).map(UnicodeWidthStr::width).sum()
+}
+
+/// Number of the extended grapheme clusters, which the server counts for the limit of posts.
+pub fn grapheme_len(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Cut the text to fit in `max` cells, ending with `…` if it is cut.
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut total = 0;
+    for grapheme in text.graphemes(true) {
+        let w = grapheme.width();
+        if total + w + 1 > max {
+            break;
+        }
+        result.push_str(grapheme);
+        total += w;
+    }
+    if max > 0 {
+        result.push('…');
+    }
+    result
+}
+
+/// Wrap the text into lines of at most `max` cells.
+///
+/// Lines are broken where the Unicode line breaking algorithm allows, so also between CJK characters,
+/// and words longer than a line are broken between graphemes.
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    wrap_ranges(text, max)
+        .into_iter()
+        .map(|range| text[range].to_string())
+        .collect()
+}
+
+/// Same as [`wrap`], but returns the byte ranges of the lines in the text,
+/// to keep track of the styles given by byte offsets such as facets.
+pub fn wrap_ranges(text: &str, max: usize) -> Vec<Range<usize>> {
+    // trailing spaces may go beyond the end of the line
+    let trimmed = |range: Range<usize>| range.start..range.start + text[range].trim_end().len();
+    let max = max.max(1);
+    let mut lines = Vec::new();
+    let (mut line_start, mut line_width) = (0, 0);
+    let mut start = 0;
+    for (end, opportunity) in linebreaks(text) {
+        let mandatory = opportunity == BreakOpportunity::Mandatory;
+        let segment = if mandatory {
+            text[start..end].trim_end_matches([
+                '\n', '\r', '\u{0b}', '\u{0c}', '\u{85}', '\u{2028}', '\u{2029}',
+            ])
+        } else {
+            &text[start..end]
+        };
+        let word_width = width(segment.trim_end());
+        if line_width + word_width > max && start > line_start {
+            lines.push(trimmed(line_start..start));
+            (line_start, line_width) = (start, 0);
+        }
+        if word_width > max {
+            for (i, grapheme) in segment.grapheme_indices(true) {
+                let w = grapheme.width();
+                if line_width + w > max && start + i > line_start && !grapheme.trim().is_empty() {
+                    lines.push(line_start..start + i);
+                    (line_start, line_width) = (start + i, 0);
+                }
+                line_width += w;
+            }
+        } else {
+            line_width += width(segment);
+        }
+        if mandatory {
+            lines.push(trimmed(line_start..start + segment.len()));
+            (line_start, line_width) = (end, 0);
+        }
+        start = end;
+    }
+    if lines.is_empty() {
+        lines.push(0..0);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(width("👨\u{200d}👩\u{200d}👧"), 2);
+        assert_eq!(width("🇯🇵👍🏽"), 4);
+        assert_eq!(grapheme_len("e\u{301}👨\u{200d}👩\u{200d}👧🇯🇵"), 3);
+    }
+
+    #[test]
+    fn wrap_words() {
+        assert_eq!(wrap("", 10), vec![""]);
+        assert_eq!(wrap("hello world", 20), vec!["hello world"]);
+        assert_eq!(wrap("hello world", 8), vec!["hello", "world"]);
+        assert_eq!(wrap("hello\n\nworld\n", 20), vec!["hello", "", "world"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(
+            wrap_ranges("hello  world\nfoo", 8),
+            vec![0..5, 7..12, 13..16]
+        );
+    }
+
+    #[test]
+    fn wrap_wide() {
+        // CJK text is broken between characters, by the cells they take
+        assert_eq!(
+            wrap("日本語のテキストです。", 8),
+            vec!["日本語の", "テキスト", "です。"]
+        );
+        assert_eq!(wrap("한국어 텍스트", 8), vec!["한국어", "텍스트"]);
+        // graphemes are not split
+        let family = "👨\u{200d}👩\u{200d}👧";
+        assert_eq!(
+            wrap(&family.repeat(3), 4),
+            vec![family.repeat(2), family.to_string()]
+        );
+        assert_eq!(
+            wrap("cafe\u{301}cafe\u{301}", 5),
+            vec!["cafe\u{301}c", "afe\u{301}"]
+        );
+        for line in wrap("日本語とEnglishが混在した、とても長いテキスト", 9) {
+            assert!(width(&line) <= 9, "{line}");
+        }
+    }
+
+    #[test]
+    fn truncate_to_width() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 6), "hello…");
+        assert_eq!(truncate("日本語", 5), "日本…");
+        assert_eq!(truncate("日本語", 4), "日…");
+    }
+}
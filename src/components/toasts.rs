@@ -0,0 +1,296 @@
+use super::text;
+use super::views::types::Action as ViewAction;
+use crate::config::{KeyBuffer, KeybindingScope, Keybindings, Keymap, Theme};
+use crate::types::{Toast, ToastLevel};
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListState, Padding, Paragraph};
+use std::time::{Duration, Instant};
+
+/// Number of the toasts kept in the history.
+const MAX_HISTORY: usize = 100;
+/// Number of the toasts shown at once.
+const MAX_SHOWN: usize = 3;
+const WIDTH: u16 = 50;
+
+/// Toasts shown over the columns until they expire, and the history of all of them.
+pub struct Toasts {
+    /// Oldest first.
+    entries: Vec<Entry>,
+    /// State of the history list while it is open.
+    history: Option<ListState>,
+    /// The history is a modal, moved and closed with the modal keybindings.
+    keymap: Keymap<ViewAction>,
+    pending_keys: KeyBuffer,
+}
+
+struct Entry {
+    toast: Toast,
+    time: DateTime<Local>,
+    /// Number of the same toasts in a row.
+    count: usize,
+    expires: Option<Instant>,
+}
+
+impl Toasts {
+    pub fn new(keybindings: &Keybindings) -> Self {
+        Self {
+            entries: Vec::new(),
+            history: None,
+            keymap: keybindings.view_keymap(Some(KeybindingScope::Modal)),
+            pending_keys: keybindings.key_buffer(),
+        }
+    }
+    pub fn set_keybindings(&mut self, keybindings: &Keybindings) {
+        self.keymap = keybindings.view_keymap(Some(KeybindingScope::Modal));
+        self.pending_keys = keybindings.key_buffer();
+    }
+    /// Errors stay longer than the others.
+    fn duration(level: ToastLevel) -> Duration {
+        match level {
+            ToastLevel::Error => Duration::from_secs(10),
+            ToastLevel::Info | ToastLevel::Success => Duration::from_secs(5),
+        }
+    }
+    pub fn push(&mut self, toast: Toast) {
+        self.push_at(toast, Instant::now());
+    }
+    fn push_at(&mut self, toast: Toast, now: Instant) {
+        let expires = Some(now + Self::duration(toast.level));
+        // the same toast again is counted instead of being repeated
+        if let Some(last) = self.entries.last_mut().filter(|last| last.toast == toast) {
+            last.count += 1;
+            last.time = Local::now();
+            last.expires = expires;
+            return;
+        }
+        self.entries.push(Entry {
+            toast,
+            time: Local::now(),
+            count: 1,
+            expires,
+        });
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY);
+        }
+    }
+    /// Hide the expired toasts, and return whether any of them was shown.
+    pub fn expire(&mut self) -> bool {
+        self.expire_at(Instant::now())
+    }
+    fn expire_at(&mut self, now: Instant) -> bool {
+        let mut changed = false;
+        for entry in &mut self.entries {
+            if entry.expires.is_some_and(|expires| expires <= now) {
+                entry.expires = None;
+                changed = true;
+            }
+        }
+        changed
+    }
+    fn shown(&self) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.expires.is_some())
+            .take(MAX_SHOWN)
+    }
+    pub fn is_history_open(&self) -> bool {
+        self.history.is_some()
+    }
+    pub fn toggle_history(&mut self) {
+        self.history = match self.history {
+            Some(_) => None,
+            None => Some(ListState::default().with_selected(Some(0))),
+        };
+    }
+    /// Returns whether to redraw. The history takes all keys while it is open.
+    pub fn handle_key_events(&mut self, key: KeyEvent) -> bool {
+        let Some(state) = self.history.as_mut() else {
+            return false;
+        };
+        let Some(action) = self.keymap.feed(&mut self.pending_keys, key.into()) else {
+            return false;
+        };
+        match action {
+            ViewAction::Back => self.history = None,
+            ViewAction::NextItem => state.scroll_down_by(1),
+            ViewAction::PrevItem => state.scroll_up_by(1),
+            ViewAction::PageDown => state.scroll_down_by(10),
+            ViewAction::PageUp => state.scroll_up_by(10),
+            ViewAction::Top => state.select_first(),
+            ViewAction::Bottom => state.select_last(),
+            _ => return false,
+        }
+        true
+    }
+    /// Returns whether to redraw.
+    pub fn handle_mouse_events(&mut self, mouse: MouseEvent) -> bool {
+        let Some(state) = self.history.as_mut() else {
+            return false;
+        };
+        match mouse.kind {
+            MouseEventKind::ScrollDown => state.scroll_down_by(1),
+            MouseEventKind::ScrollUp => state.scroll_up_by(1),
+            _ => return false,
+        }
+        true
+    }
+    fn style(level: ToastLevel, theme: &Theme) -> Style {
+        match level {
+            ToastLevel::Info => Style::default(),
+            ToastLevel::Success => theme.valid,
+            ToastLevel::Error => theme.danger,
+        }
+    }
+    fn message(entry: &Entry) -> String {
+        if entry.count > 1 {
+            format!("{} (x{})", entry.toast.message, entry.count)
+        } else {
+            entry.toast.message.clone()
+        }
+    }
+    /// Draw the toasts stacked up from the bottom right of the area, and the history over them if it is open.
+    pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let width = WIDTH.min(area.width);
+        let mut bottom = area.bottom();
+        for entry in self.shown() {
+            let message = Self::message(entry);
+            let lines = text::wrap(&message, usize::from(width.saturating_sub(4)));
+            let height = (lines.len() as u16 + 2).min(bottom.saturating_sub(area.y));
+            if height < 3 {
+                break;
+            }
+            bottom -= height;
+            let toast_area = Rect::new(area.right() - width, bottom, width, height);
+            f.render_widget(Clear, toast_area);
+            f.render_widget(
+                Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()).block(
+                    Block::bordered()
+                        .border_style(Self::style(entry.toast.level, theme))
+                        .padding(Padding::horizontal(1)),
+                ),
+                toast_area,
+            );
+        }
+
+        let Some(state) = self.history.as_mut() else {
+            return;
+        };
+        let area = area.inner(Margin::new(2, 1));
+        let items = self
+            .entries
+            .iter()
+            .rev()
+            .map(|entry| {
+                Line::from(vec![
+                    Span::from(entry.time.format("%H:%M:%S").to_string()).style(theme.timestamp),
+                    Span::from(" "),
+                    Span::from(Self::message(entry)).style(Self::style(entry.toast.level, theme)),
+                ])
+            })
+            .collect::<Vec<_>>();
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().reset().patch(theme.selected))
+                .block(
+                    Block::bordered()
+                        .title("Message history")
+                        .padding(Padding::horizontal(1)),
+                ),
+            area,
+            state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Key, ModalAction};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn toasts() -> Toasts {
+        let mut config = Config::default();
+        config.set_default_keybindings();
+        Toasts::new(&config.keybindings)
+    }
+
+    #[test]
+    fn push_and_expire() {
+        let now = Instant::now();
+        let mut toasts = toasts();
+        toasts.push_at(Toast::success("posted"), now);
+        toasts.push_at(Toast::error("failed"), now);
+        toasts.push_at(Toast::error("failed"), now + Duration::from_secs(1));
+        assert_eq!(toasts.entries.len(), 2);
+        assert_eq!(toasts.entries[1].count, 2);
+        assert_eq!(toasts.shown().count(), 2);
+
+        assert!(!toasts.expire_at(now + Duration::from_secs(4)));
+        assert!(toasts.expire_at(now + Duration::from_secs(5)));
+        assert_eq!(
+            toasts
+                .shown()
+                .map(|entry| entry.toast.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["failed"]
+        );
+        // the repeated error expires later
+        assert!(!toasts.expire_at(now + Duration::from_secs(10)));
+        assert!(toasts.expire_at(now + Duration::from_secs(11)));
+        assert_eq!(toasts.shown().count(), 0);
+        assert_eq!(toasts.entries.len(), 2);
+    }
+
+    #[test]
+    fn history() {
+        let mut toasts = toasts();
+        for i in 0..MAX_HISTORY + 5 {
+            toasts.push(Toast::info(format!("{i}")));
+        }
+        assert_eq!(toasts.entries.len(), MAX_HISTORY);
+        assert_eq!(toasts.entries[0].toast.message, "5");
+        assert_eq!(toasts.shown().count(), MAX_SHOWN);
+    }
+
+    #[test]
+    fn history_keys() {
+        let mut config = Config::default();
+        config.keybindings.modal.insert(
+            Key(KeyCode::Char('q'), KeyModifiers::NONE).into(),
+            ModalAction::Cancel,
+        );
+        config.set_default_keybindings();
+        let mut toasts = Toasts::new(&config.keybindings);
+        for i in 0..5 {
+            toasts.push(Toast::info(format!("{i}")));
+        }
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(!toasts.handle_key_events(key(KeyCode::Down)));
+        toasts.toggle_history();
+        assert!(toasts.handle_key_events(key(KeyCode::Down)));
+        assert_eq!(
+            toasts.history.as_ref().and_then(ListState::selected),
+            Some(1)
+        );
+        assert!(toasts.handle_key_events(key(KeyCode::Up)));
+        assert_eq!(
+            toasts.history.as_ref().and_then(ListState::selected),
+            Some(0)
+        );
+        // not bound in the modal keymap
+        assert!(!toasts.handle_key_events(key(KeyCode::Char('x'))));
+        assert!(toasts.handle_key_events(key(KeyCode::Esc)));
+        assert!(!toasts.is_history_open());
+        // closed with the custom binding
+        toasts.toggle_history();
+        assert!(toasts.handle_key_events(key(KeyCode::Char('q'))));
+        assert!(!toasts.is_history_open());
+    }
+}
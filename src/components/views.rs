@@ -1,25 +1,49 @@
+mod accounts;
 mod feed;
+mod help;
 mod login;
 mod menu;
 mod new_post;
+mod notifications;
+mod palette;
 mod post;
 mod root;
 pub mod types;
 mod utils;
 
+pub use self::accounts::{Account, AccountsViewComponent};
 pub use self::feed::FeedViewComponent;
+pub use self::help::HelpViewComponent;
 pub use self::login::LoginComponent;
 pub use self::menu::MenuViewComponent;
 pub use self::new_post::NewPostViewComponent;
+pub use self::notifications::NotificationsViewComponent;
+pub use self::palette::{Command, PaletteViewComponent};
 pub use self::post::PostViewComponent;
 pub use self::root::RootComponent;
 use self::types::{Action, View};
+use crate::backend::Watcher;
+use crate::config::{Config, KeybindingScope};
 use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{layout::Rect, Frame};
+use std::sync::Arc;
 
 pub trait ViewComponent {
     fn view(&self) -> View;
+    #[allow(unused_variables)]
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        Ok(())
+    }
+    /// Rebuild the subscriptions of the view with `watcher`, e.g. after the intervals are changed.
+    #[allow(unused_variables)]
+    fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        Ok(())
+    }
+    /// Section of keybindings to use in addition to `column`.
+    fn keybinding_scope(&self) -> Option<KeybindingScope> {
+        None
+    }
     fn activate(&mut self) -> Result<()> {
         Ok(())
     }
@@ -30,6 +54,11 @@ pub trait ViewComponent {
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         Ok(None)
     }
+    /// Handle a mouse event at a position in the area where the view was last drawn.
+    #[allow(unused_variables)]
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(None)
+    }
     #[allow(unused_variables)]
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         Ok(None)
@@ -0,0 +1,133 @@
+use super::super::mouse::ListAreas;
+use super::ViewComponent;
+use super::types::{Action, Transition, View};
+use crate::backend::Watcher;
+use crate::config::{Config, Theme};
+use color_eyre::Result;
+use crossterm::event::MouseEvent;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListState, Padding, Paragraph};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// An account signed in to another column, whose watcher is shared with the new column.
+#[derive(Clone)]
+pub struct Account {
+    pub handle: String,
+    pub did: String,
+    pub watcher: Arc<Watcher>,
+}
+
+/// Choose one of the signed-in accounts for a new column, or sign in with another account.
+pub struct AccountsViewComponent {
+    accounts: Vec<Account>,
+    state: ListState,
+    list_areas: ListAreas,
+    action_tx: UnboundedSender<Action>,
+    theme: Theme,
+}
+
+impl AccountsViewComponent {
+    pub fn new(action_tx: UnboundedSender<Action>, accounts: Vec<Account>) -> Self {
+        Self {
+            accounts,
+            state: ListState::default().with_selected(Some(0)),
+            list_areas: ListAreas::default(),
+            action_tx,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl ViewComponent for AccountsViewComponent {
+    fn view(&self) -> View {
+        View::Accounts
+    }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        Ok(())
+    }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(self.list_areas.handle_mouse_events(mouse, &mut self.state))
+    }
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.state) {
+            return Ok(Some(action));
+        }
+        // the last item is to sign in with another account
+        let len = self.accounts.len() + 1;
+        match action {
+            Action::NextItem => {
+                self.state.select(Some(
+                    self.state.selected().map_or(0, |s| (s + 1).min(len - 1)),
+                ));
+                return Ok(Some(Action::Render));
+            }
+            Action::PrevItem => {
+                self.state
+                    .select(Some(self.state.selected().map_or(0, |s| s.max(1) - 1)));
+                return Ok(Some(Action::Render));
+            }
+            Action::Enter => {
+                let Some(index) = self.state.selected() else {
+                    return Ok(None);
+                };
+                if let Some(account) = self.accounts.get(index) {
+                    if let Err(e) = self.action_tx.send(Action::Share(account.watcher.clone())) {
+                        log::error!("failed to send share action: {e}");
+                    }
+                } else {
+                    return Ok(Some(Action::Transition(Transition::Replace(Box::new(
+                        View::Login,
+                    )))));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let header = Paragraph::new(Line::from("Choose an account").bold()).block(
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .border_style(self.theme.border)
+                .padding(Padding::horizontal(1)),
+        );
+        let mut items = self
+            .accounts
+            .iter()
+            .map(|account| {
+                Text::from(vec![
+                    Line::from(Span::styled(
+                        format!("@{}", account.handle),
+                        self.theme.handle,
+                    )),
+                    Line::from(format!("  {}", account.did)).style(self.theme.muted),
+                ])
+            })
+            .collect::<Vec<_>>();
+        items.push(Text::styled(
+            "Sign in with another account",
+            self.theme.button,
+        ));
+        let heights = items.iter().map(Text::height).collect::<Vec<_>>();
+
+        let layout =
+            Layout::vertical([Constraint::Length(2), Constraint::Percentage(100)]).split(area);
+        f.render_widget(header, layout[0]);
+        let block = Block::default().padding(Padding::uniform(1));
+        let inner = block.inner(layout[1]);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().reset().patch(self.theme.selected))
+                .block(block),
+            layout[1],
+            &mut self.state,
+        );
+        self.list_areas.update(inner, self.state.offset(), heights);
+        Ok(())
+    }
+}
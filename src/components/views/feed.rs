@@ -1,32 +1,42 @@
+use super::super::mouse::ListAreas;
 use super::ViewComponent;
 use super::types::{Action, Data, Transition, View};
-use super::utils::{counts, profile_name, profile_name_as_str};
+use super::utils::{
+    counts, download, like, links, profile_name, profile_name_as_str, reply_ref, repost, text_lines,
+};
+use crate::backend::client::Agent;
 use crate::backend::types::FeedSourceInfo;
 use crate::backend::{Watch, Watcher};
+use crate::config::{Config, DownloadConfig, KeybindingScope, Theme, TimestampFormat};
+use crate::types::Toast;
 use bsky_sdk::api::app::bsky::feed::defs::{
     FeedViewPost, FeedViewPostReasonRefs, PostViewEmbedRefs, ReplyRefParentRefs,
 };
 use bsky_sdk::api::app::bsky::feed::post;
 use bsky_sdk::api::types::{TryFromUnknown, Union};
-use chrono::Local;
 use color_eyre::Result;
+use crossterm::event::MouseEvent;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, List, ListState, Padding, Paragraph};
 use std::sync::Arc;
-use textwrap::Options;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
 pub struct FeedViewComponent {
     items: Vec<FeedViewPost>,
     state: ListState,
+    list_areas: ListAreas,
     action_tx: UnboundedSender<Action>,
+    agent: Arc<Agent>,
     feed_info: FeedSourceInfo,
     watcher: Box<dyn Watch<Output = Vec<FeedViewPost>>>,
     quit: Option<oneshot::Sender<()>>,
+    download: DownloadConfig,
+    theme: Theme,
+    timestamp: TimestampFormat,
 }
 
 impl FeedViewComponent {
@@ -34,18 +44,33 @@ impl FeedViewComponent {
         action_tx: UnboundedSender<Action>,
         watcher: Arc<Watcher>,
         feed_info: FeedSourceInfo,
+        download: DownloadConfig,
     ) -> Self {
+        let agent = watcher.agent.clone();
         let watcher = Box::new(watcher.feed(feed_info.clone()));
         Self {
             items: Vec::new(),
             state: ListState::default(),
+            list_areas: ListAreas::default(),
             action_tx,
+            agent,
             feed_info,
             watcher,
             quit: None,
+            download,
+            theme: Theme::default(),
+            timestamp: TimestampFormat::default(),
         }
     }
-    fn lines(feed_view_post: &FeedViewPost, area: Rect) -> Option<Vec<Line<'_>>> {
+    fn selected(&self) -> Option<&FeedViewPost> {
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+    fn lines<'a>(
+        feed_view_post: &'a FeedViewPost,
+        area: Rect,
+        theme: &Theme,
+        timestamp: &TimestampFormat,
+    ) -> Option<Vec<Line<'a>>> {
         let Ok(record) = post::Record::try_from_unknown(feed_view_post.post.record.clone()) else {
             return None;
         };
@@ -53,19 +78,11 @@ impl FeedViewComponent {
         {
             let mut spans = [
                 vec![
-                    Span::from(
-                        feed_view_post
-                            .post
-                            .indexed_at
-                            .as_ref()
-                            .with_timezone(&Local)
-                            .format("%Y-%m-%d %H:%M:%S %z")
-                            .to_string(),
-                    )
-                    .green(),
+                    Span::from(timestamp.format(feed_view_post.post.indexed_at.as_ref()))
+                        .style(theme.timestamp),
                     Span::from(": "),
                 ],
-                profile_name(&feed_view_post.post.author),
+                profile_name(&feed_view_post.post.author, theme),
             ]
             .concat();
             if let Some(labels) = feed_view_post
@@ -76,7 +93,10 @@ impl FeedViewComponent {
                 .filter(|v| !v.is_empty())
             {
                 spans.push(Span::from(" "));
-                spans.push(format!("[{} labels]", labels.len()).magenta());
+                spans.push(Span::styled(
+                    format!("[{} labels]", labels.len()),
+                    theme.label,
+                ));
             }
             lines.push(Line::from(spans));
         }
@@ -84,30 +104,22 @@ impl FeedViewComponent {
             &feed_view_post.reason
         {
             lines.push(
-                Line::from(format!("  Reposted by {}", profile_name_as_str(&repost.by))).blue(),
+                Line::from(format!("  Reposted by {}", profile_name_as_str(&repost.by)))
+                    .style(theme.repost),
             );
         }
         if let Some(reply) = &feed_view_post.reply {
             if let Union::Refs(ReplyRefParentRefs::PostView(post_view)) = &reply.parent {
                 lines.push(Line::from(
                     [
-                        vec![Span::from("  Reply to ").blue()],
-                        profile_name(&post_view.author),
+                        vec![Span::styled("  Reply to ", theme.reply)],
+                        profile_name(&post_view.author, theme),
                     ]
                     .concat(),
                 ));
             }
         }
-        lines.extend(
-            textwrap::wrap(
-                &record.text,
-                Options::new(usize::from(area.width) - 2)
-                    .initial_indent("  ")
-                    .subsequent_indent("  "),
-            )
-            .iter()
-            .map(|s| Line::from(s.to_string())),
-        );
+        lines.extend(text_lines(&record, usize::from(area.width), "  ", theme));
         if let Some(embed) = &feed_view_post.post.embed {
             let content = match embed {
                 Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(images)) => {
@@ -122,10 +134,14 @@ impl FeedViewComponent {
                 }
                 _ => String::from("unknown"),
             };
-            lines.push(Line::from(format!("  Embedded {content}")).yellow());
+            lines.push(Line::from(format!("  Embedded {content}")).style(theme.embed));
         }
         lines.push(Line::from(
-            [vec![Span::from("  ")], counts(&feed_view_post.post, 5)].concat(),
+            [
+                vec![Span::from("  ")],
+                counts(&feed_view_post.post, 5, theme),
+            ]
+            .concat(),
         ));
         Some(lines)
     }
@@ -135,6 +151,25 @@ impl ViewComponent for FeedViewComponent {
     fn view(&self) -> View {
         View::Feed(Box::new(self.feed_info.clone()))
     }
+    fn keybinding_scope(&self) -> Option<KeybindingScope> {
+        Some(KeybindingScope::Feed)
+    }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        self.timestamp = config.timestamp.feed;
+        Ok(())
+    }
+    fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        let active = self.quit.is_some();
+        if active {
+            self.deactivate()?;
+        }
+        self.watcher = Box::new(watcher.feed(self.feed_info.clone()));
+        if active {
+            self.activate()?;
+        }
+        Ok(())
+    }
     fn activate(&mut self) -> Result<()> {
         let (tx, mut rx) = (self.action_tx.clone(), self.watcher.subscribe());
         let (quit_tx, mut quit_rx) = oneshot::channel();
@@ -175,7 +210,16 @@ impl ViewComponent for FeedViewComponent {
         self.watcher.unsubscribe();
         Ok(())
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(self.list_areas.handle_mouse_events(mouse, &mut self.state))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if matches!(action, Action::Newest) {
+            self.watcher.refresh();
+        }
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.state) {
+            return Ok(Some(action));
+        }
         match action {
             Action::NextItem if !self.items.is_empty() => {
                 self.state.select(Some(
@@ -196,8 +240,7 @@ impl ViewComponent for FeedViewComponent {
                 return Ok(Some(Action::Render));
             }
             Action::Enter => {
-                if let Some(feed_view_post) = self.state.selected().and_then(|i| self.items.get(i))
-                {
+                if let Some(feed_view_post) = self.selected() {
                     return Ok(Some(Action::Transition(Transition::Push(Box::new(
                         View::Post(Box::new((
                             feed_view_post.post.clone(),
@@ -214,6 +257,95 @@ impl ViewComponent for FeedViewComponent {
                     )))));
                 }
             }
+            Action::Like => {
+                if let Some(feed_view_post) = self.selected() {
+                    let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                    let post_view = feed_view_post.post.clone();
+                    tokio::spawn(async move {
+                        let result = match post_view.viewer.as_ref().and_then(|v| v.like.clone()) {
+                            Some(uri) => agent
+                                .delete_record(uri)
+                                .await
+                                .map(|_| ())
+                                .map_err(Into::into),
+                            None => like(&agent, &post_view).await.map(|_| ()),
+                        };
+                        match result {
+                            Ok(()) => {
+                                tx.send(Action::Refresh).ok();
+                            }
+                            Err(e) => {
+                                log::error!("failed to update like record: {e}");
+                                tx.send(Action::Toast(Toast::error(format!(
+                                    "failed to like or unlike: {e}"
+                                ))))
+                                .ok();
+                            }
+                        }
+                    });
+                }
+            }
+            Action::Repost => {
+                if let Some(feed_view_post) = self.selected() {
+                    let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                    let post_view = feed_view_post.post.clone();
+                    tokio::spawn(async move {
+                        let result = match post_view.viewer.as_ref().and_then(|v| v.repost.clone())
+                        {
+                            Some(uri) => agent
+                                .delete_record(uri)
+                                .await
+                                .map(|_| ())
+                                .map_err(Into::into),
+                            None => repost(&agent, &post_view).await.map(|_| ()),
+                        };
+                        match result {
+                            Ok(()) => {
+                                tx.send(Action::Refresh).ok();
+                            }
+                            Err(e) => {
+                                log::error!("failed to update repost record: {e}");
+                                tx.send(Action::Toast(Toast::error(format!(
+                                    "failed to repost or undo: {e}"
+                                ))))
+                                .ok();
+                            }
+                        }
+                    });
+                }
+            }
+            Action::Reply => {
+                if let Some(feed_view_post) = self.selected() {
+                    return Ok(Some(Action::Transition(Transition::Push(Box::new(
+                        View::NewPost(Some(Box::new(reply_ref(&feed_view_post.post)))),
+                    )))));
+                }
+            }
+            Action::Download => {
+                if let Some(feed_view_post) = self.selected() {
+                    download(
+                        self.agent.clone(),
+                        feed_view_post.post.clone(),
+                        &self.download,
+                        self.action_tx.clone(),
+                    );
+                }
+            }
+            Action::OpenLink => {
+                if let Some(uri) = self
+                    .selected()
+                    .and_then(|feed_view_post| links(&feed_view_post.post).into_iter().next())
+                {
+                    if let Err(e) = open::that(&uri) {
+                        log::error!("failed to open: {e}");
+                        self.action_tx
+                            .send(Action::Toast(Toast::error(format!(
+                                "failed to open {uri}: {e}"
+                            ))))
+                            .ok();
+                    }
+                }
+            }
             Action::Back => return Ok(Some(Action::Transition(Transition::Pop))),
             Action::Refresh => {
                 self.watcher.refresh();
@@ -252,39 +384,51 @@ impl ViewComponent for FeedViewComponent {
                     "by {}",
                     profile_name_as_str(&generator_view.creator)
                 ))
-                .gray(),
+                .style(self.theme.muted),
             ]),
             FeedSourceInfo::List(list_view) => Line::from(vec![
                 Span::from(list_view.name.clone()).bold(),
                 Span::from(" "),
-                Span::from(format!("by {}", profile_name_as_str(&list_view.creator))).gray(),
+                Span::from(format!("by {}", profile_name_as_str(&list_view.creator)))
+                    .style(self.theme.muted),
             ]),
             FeedSourceInfo::Timeline(_) => Line::from("Following").bold(),
+            FeedSourceInfo::Author(profile_view) => {
+                Line::from(profile_name(profile_view.as_ref(), &self.theme))
+            }
+            FeedSourceInfo::Search(query) => Line::from(vec![
+                Span::from("Search: ").style(self.theme.muted),
+                Span::from(query.clone()).bold(),
+            ]),
         })
         .bold()
         .block(
             Block::default()
                 .borders(Borders::BOTTOM)
-                .border_style(Color::Gray)
+                .border_style(self.theme.border)
                 .padding(Padding::horizontal(1)),
         );
+        let layout =
+            Layout::vertical([Constraint::Length(2), Constraint::Percentage(100)]).split(area);
+        let block = Block::default().padding(Padding::horizontal(1));
+        let inner = block.inner(layout[1]);
         let mut items = Vec::new();
         for feed_view_post in &self.items {
-            if let Some(lines) = Self::lines(feed_view_post, area) {
+            if let Some(lines) = Self::lines(feed_view_post, inner, &self.theme, &self.timestamp) {
                 items.push(Text::from(lines));
             }
         }
+        let heights = items.iter().map(Text::height).collect::<Vec<_>>();
 
-        let layout =
-            Layout::vertical([Constraint::Length(2), Constraint::Percentage(100)]).split(area);
         f.render_widget(header, layout[0]);
         f.render_stateful_widget(
             List::new(items)
-                .highlight_style(Style::default().reset().reversed())
-                .block(Block::default().padding(Padding::horizontal(1))),
+                .highlight_style(Style::default().reset().patch(self.theme.selected))
+                .block(block),
             layout[1],
             &mut self.state,
         );
+        self.list_areas.update(inner, self.state.offset(), heights);
         Ok(())
     }
 }
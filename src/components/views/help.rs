@@ -0,0 +1,285 @@
+use super::super::text;
+use super::types::Action;
+use crate::config::{Binding, Config, KeybindingScope, Keymap, Lookup, Theme};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Padding};
+use tui_textarea::TextArea;
+
+/// A row of the list, of which only the bindings can be selected.
+#[derive(Debug, PartialEq, Eq)]
+enum Row<'a> {
+    Blank,
+    Header(&'static str),
+    Binding(&'a Binding),
+}
+
+/// A searchable list of the key bindings in effect, grouped by section.
+pub struct HelpViewComponent {
+    bindings: Vec<Binding>,
+    /// Section of the view that the column currently shows.
+    current: Option<&'static str>,
+    query: TextArea<'static>,
+    /// Index of the selected binding among the matches.
+    selected: usize,
+    state: ListState,
+    keymap: Keymap<Action>,
+    theme: Theme,
+}
+
+impl HelpViewComponent {
+    pub fn new(config: &Config, scope: Option<KeybindingScope>) -> Self {
+        let mut query = TextArea::default();
+        query.set_block(Block::bordered().title("Search"));
+        query.set_cursor_line_style(Style::default());
+        Self {
+            bindings: config.keybindings.bindings(),
+            current: scope.map(|scope| scope.section()),
+            query,
+            selected: 0,
+            state: ListState::default(),
+            keymap: config.keybindings.modal_keymap(),
+            theme: Theme::from(&config.theme),
+        }
+    }
+    fn matches(&self) -> Vec<&Binding> {
+        let query = self.query.lines().join("").to_lowercase();
+        self.bindings
+            .iter()
+            .filter(|binding| {
+                binding.sequence.to_string().to_lowercase().contains(&query)
+                    || binding.action.to_lowercase().contains(&query)
+                    || binding.section.contains(&query)
+            })
+            .collect()
+    }
+    /// The matches grouped by section, with a header before each section.
+    fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows = Vec::new();
+        let mut section = None;
+        for binding in self.matches() {
+            if section != Some(binding.section) {
+                section = Some(binding.section);
+                if !rows.is_empty() {
+                    rows.push(Row::Blank);
+                }
+                rows.push(Row::Header(binding.section));
+            }
+            rows.push(Row::Binding(binding));
+        }
+        rows
+    }
+    /// Index of the row of the selected binding.
+    fn selected_row(&self, rows: &[Row<'_>]) -> Option<usize> {
+        rows.iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, Row::Binding(_)))
+            .nth(self.selected)
+            .map(|(i, _)| i)
+    }
+    fn select_by(&mut self, delta: isize) {
+        let len = self.matches().len();
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+    /// Returns [`Action::Back`] when the help should be closed.
+    pub fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        // only single keys, as the others are typed into the query
+        if let Lookup::Matched(action) = self.keymap.get(&[key.into()]) {
+            match action {
+                Action::Back => return Ok(Some(Action::Back)),
+                Action::NextItem => self.select_by(1),
+                Action::PrevItem => self.select_by(-1),
+                _ => return Ok(None),
+            }
+            return Ok(Some(Action::Render));
+        }
+        match (key.code, key.modifiers) {
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => self.select_by(1),
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => self.select_by(-1),
+            (KeyCode::PageDown, _) => self.select_by(10),
+            (KeyCode::PageUp, _) => self.select_by(-10),
+            (KeyCode::Enter, _) | (KeyCode::Char('m'), KeyModifiers::CONTROL) => {}
+            _ => {
+                if !self.query.input(key) {
+                    return Ok(None);
+                }
+                self.selected = 0;
+            }
+        }
+        Ok(Some(Action::Render))
+    }
+    pub fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.select_by(1),
+            MouseEventKind::ScrollUp => self.select_by(-1),
+            _ => return Ok(None),
+        }
+        Ok(Some(Action::Render))
+    }
+    pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let area = area.inner(Margin::new(1, 1));
+        let block = Block::bordered()
+            .title("Keybindings")
+            .padding(Padding::horizontal(1));
+        let [query, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(block.inner(area));
+
+        let rows = self.rows();
+        let width = rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Binding(binding) => Some(text::width(&binding.sequence.to_string())),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+        let selected = self.selected_row(&rows);
+        let mut items = Vec::new();
+        for row in &rows {
+            match row {
+                Row::Blank => items.push(ListItem::from("")),
+                Row::Header(section) => {
+                    let mut header = vec![Span::from(*section).bold()];
+                    if self.current == Some(*section) {
+                        header.push(Span::styled(" (current view)", self.theme.muted));
+                    }
+                    items.push(ListItem::from(Line::from(header)));
+                }
+                Row::Binding(binding) => {
+                    let sequence = binding.sequence.to_string();
+                    let padding = " ".repeat(width.saturating_sub(text::width(&sequence)));
+                    items.push(ListItem::from(Line::from(vec![
+                        Span::styled(format!("  {sequence}{padding}"), self.theme.facet),
+                        Span::from("  "),
+                        Span::from(binding.action.clone()),
+                    ])));
+                }
+            }
+        }
+        if items.is_empty() {
+            items.push(ListItem::from(Line::styled(
+                "No bindings",
+                self.theme.muted,
+            )));
+        }
+
+        self.state.select(selected);
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(&self.query, query);
+        f.render_stateful_widget(
+            List::new(items).highlight_style(Style::default().patch(self.theme.selected)),
+            list,
+            &mut self.state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FeedAction, ModalAction};
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config
+            .keybindings
+            .feed
+            .insert("g r".parse().expect("invalid sequence"), FeedAction::Repost);
+        config.set_default_keybindings();
+        config
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn sections(help: &HelpViewComponent) -> Vec<(&'static str, String)> {
+        help.rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                Row::Binding(binding) => Some((binding.section, binding.sequence.to_string())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn custom_binding() {
+        let help = HelpViewComponent::new(&config(), Some(KeybindingScope::Feed));
+        assert!(sections(&help).contains(&("feed", String::from("g r"))));
+        // listed under the header of its section
+        let rows = help.rows();
+        let index = rows
+            .iter()
+            .position(|row| matches!(row, Row::Binding(binding) if binding.action == "Repost"))
+            .expect("not listed");
+        let header = rows[..index].iter().rev().find_map(|row| match row {
+            Row::Header(section) => Some(*section),
+            _ => None,
+        });
+        assert_eq!(header, Some("feed"));
+    }
+
+    #[test]
+    fn search() {
+        let mut help = HelpViewComponent::new(&config(), None);
+        for c in "repost".chars() {
+            help.handle_key_events(key(KeyCode::Char(c)))
+                .expect("failed to handle key");
+        }
+        assert_eq!(sections(&help), vec![("feed", String::from("g r"))]);
+        assert_eq!(help.rows().first(), Some(&Row::Header("feed")));
+        for _ in 0..6 {
+            help.handle_key_events(key(KeyCode::Backspace))
+                .expect("failed to handle key");
+        }
+        assert!(sections(&help).len() > 1);
+    }
+
+    #[test]
+    fn select_bindings_only() {
+        let mut help = HelpViewComponent::new(&config(), None);
+        let len = help.matches().len();
+        for _ in 0..len + 5 {
+            help.handle_key_events(key(KeyCode::Down))
+                .expect("failed to handle key");
+            let rows = help.rows();
+            let row = help.selected_row(&rows).expect("nothing selected");
+            assert!(matches!(rows[row], Row::Binding(_)), "{:?}", rows[row]);
+        }
+        assert_eq!(help.selected, len - 1);
+        help.handle_key_events(key(KeyCode::PageUp))
+            .expect("failed to handle key");
+        assert_eq!(help.selected, len - 11);
+    }
+
+    #[test]
+    fn close_with_modal_keybinding() {
+        let mut config = config();
+        config.keybindings.modal.clear();
+        config.keybindings.modal.insert(
+            "Ctrl-g".parse().expect("invalid sequence"),
+            ModalAction::Cancel,
+        );
+        let mut help = HelpViewComponent::new(&config, None);
+        assert!(matches!(
+            help.handle_key_events(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL)),
+            Ok(Some(Action::Back))
+        ));
+        // Esc is no longer bound
+        assert!(!matches!(
+            help.handle_key_events(key(KeyCode::Esc)),
+            Ok(Some(Action::Back))
+        ));
+    }
+}
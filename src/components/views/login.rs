@@ -1,9 +1,11 @@
+use super::super::mouse::is_click;
 use super::types::{Action, View};
 use super::ViewComponent;
+use crate::backend::client;
+use crate::config::Theme;
 use bsky_sdk::agent::config::Config;
-use bsky_sdk::BskyAgent;
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Style, Stylize};
 use ratatui::text::Line;
@@ -45,8 +47,11 @@ pub struct LoginComponent {
     identifier: TextArea<'static>,
     password: TextArea<'static>,
     focus: Focus,
+    /// Areas of the fields and the button as last drawn.
+    areas: Vec<(Focus, Rect)>,
     error_message: Arc<RwLock<Option<String>>>,
     action_tx: UnboundedSender<Action>,
+    theme: Theme,
 }
 
 impl LoginComponent {
@@ -80,10 +85,18 @@ impl LoginComponent {
             identifier,
             password,
             focus: Focus::Identifier,
+            areas: Vec::new(),
             error_message: Arc::new(RwLock::new(None)),
             action_tx,
+            theme: Theme::default(),
         }
     }
+    /// Prefill the identifier and move the focus to the password.
+    pub fn with_identifier(mut self, identifier: &str) -> Self {
+        self.identifier.insert_str(identifier);
+        self.update_focus(Focus::Password);
+        self
+    }
     fn current_textarea(&mut self) -> Option<&mut TextArea<'static>> {
         match self.focus {
             Focus::Service => Some(&mut self.service),
@@ -114,7 +127,7 @@ impl LoginComponent {
         let error_message = Arc::clone(&self.error_message);
         let action_tx = self.action_tx.clone();
         tokio::spawn(async move {
-            let Ok(agent) = BskyAgent::builder()
+            let Ok(agent) = client::builder()
                 .config(Config {
                     endpoint: service,
                     ..Default::default()
@@ -168,6 +181,10 @@ impl ViewComponent for LoginComponent {
     fn view(&self) -> View {
         View::Login
     }
+    fn register_config_handler(&mut self, config: crate::config::Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        Ok(())
+    }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         if let Some(textarea) = self.current_textarea() {
             Ok(match (key.code, key.modifiers) {
@@ -187,6 +204,22 @@ impl ViewComponent for LoginComponent {
             Ok(None)
         }
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        let Some(focus) = self
+            .areas
+            .iter()
+            .find(|(_, area)| is_click(&mouse, *area))
+            .map(|(focus, _)| *focus)
+        else {
+            return Ok(None);
+        };
+        self.update_focus(focus);
+        Ok(Some(if focus == Focus::Submit {
+            Action::Enter
+        } else {
+            Action::Render
+        }))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
             Action::NextItem => {
@@ -223,19 +256,25 @@ impl ViewComponent for LoginComponent {
         ])
         .split(block.inner(area));
 
-        let mut submit = Line::from("Submit").blue().centered();
+        let mut submit = Line::styled("Submit", self.theme.button).centered();
         if self.focus == Focus::Submit {
-            submit = submit.reversed();
+            submit = submit.patch_style(self.theme.selected);
         }
         f.render_widget(&self.service, layout[0]);
         f.render_widget(&self.identifier, layout[1]);
         f.render_widget(&self.password, layout[2]);
         f.render_widget(submit, layout[3]);
+        self.areas = vec![
+            (Focus::Service, layout[0]),
+            (Focus::Identifier, layout[1]),
+            (Focus::Password, layout[2]),
+            (Focus::Submit, layout[3]),
+        ];
         if let Ok(message) = self.error_message.read() {
             if let Some(s) = message.as_ref() {
                 f.render_widget(
                     Paragraph::new(s.as_str())
-                        .style(Style::default().red())
+                        .style(self.theme.invalid)
                         .wrap(Wrap::default()),
                     layout[5],
                 );
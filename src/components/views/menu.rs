@@ -1,6 +1,8 @@
+use super::super::mouse::{position, ListAreas};
 use super::types::Action;
-use crate::config::{ColumnAction, Key, Keybindings};
+use crate::config::{ColumnAction, Config, KeySequence, Theme};
 use color_eyre::Result;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::Rect;
 use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
@@ -12,6 +14,7 @@ enum MenuAction {
     NewPost(Vec<String>),
     Refresh(Vec<String>),
     Back(Vec<String>),
+    Help(Vec<String>),
 }
 
 impl<'a> From<&'a MenuAction> for ListItem<'a> {
@@ -32,6 +35,11 @@ impl<'a> From<&'a MenuAction> for ListItem<'a> {
                 Span::from(format!("({})", v.join(", "))).dim(),
             ])),
             MenuAction::Back(_) => Self::from("Back".reset()),
+            MenuAction::Help(v) if !v.is_empty() => Self::from(Line::from(vec![
+                Span::from("Keybindings ").reset(),
+                Span::from(format!("({})", v.join(", "))).dim(),
+            ])),
+            MenuAction::Help(_) => Self::from("Keybindings".reset()),
         }
     }
 }
@@ -40,37 +48,50 @@ pub struct MenuViewComponent {
     action_tx: UnboundedSender<Action>,
     items: Vec<MenuAction>,
     state: ListState,
+    list_areas: ListAreas,
+    theme: Theme,
 }
 
 impl MenuViewComponent {
-    pub fn new(action_tx: UnboundedSender<Action>, keybindings: &Keybindings) -> Self {
-        let mut keys = vec![Vec::new(); 3];
-        for (k, v) in &keybindings.column {
+    pub fn new(action_tx: UnboundedSender<Action>, config: &Config) -> Self {
+        let mut keys = vec![Vec::new(); 4];
+        for (k, v) in &config.keybindings.column {
             match v {
                 ColumnAction::NewPost => keys[0].push(k),
                 ColumnAction::Refresh => keys[1].push(k),
                 ColumnAction::Back => keys[2].push(k),
+                ColumnAction::Help => keys[3].push(k),
                 _ => {}
             }
         }
         keys.iter_mut().for_each(|v| v.sort());
-        let to_string = |v: &[&Key]| {
-            v.iter()
-                .filter_map(|k| serde_json::to_string(k).ok())
-                .map(|s| s.trim_matches('"').to_string())
-                .collect::<Vec<_>>()
-        };
+        let to_string = |v: &[&KeySequence]| v.iter().map(ToString::to_string).collect::<Vec<_>>();
         Self {
             action_tx,
             items: vec![
                 MenuAction::NewPost(to_string(&keys[0])),
                 MenuAction::Refresh(to_string(&keys[1])),
                 MenuAction::Back(to_string(&keys[2])),
+                MenuAction::Help(to_string(&keys[3])),
             ],
             state: ListState::default().with_selected(Some(0)),
+            list_areas: ListAreas::default(),
+            theme: Theme::from(&config.theme),
         }
     }
+    /// Returns `None` if the event is not on the menu.
+    pub fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+            // a click runs the item at once
+            let index = self.list_areas.index_at(position(&mouse))?;
+            self.state.select(Some(index));
+        }
+        self.list_areas.handle_mouse_events(mouse, &mut self.state)
+    }
     pub fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.state) {
+            return Ok(Some(action));
+        }
         match action {
             Action::NextItem => {
                 if let Some(selected) = self.state.selected() {
@@ -91,6 +112,7 @@ impl MenuViewComponent {
                         MenuAction::NewPost(_) => Action::NewPost,
                         MenuAction::Refresh(_) => Action::Refresh,
                         MenuAction::Back(_) => Action::Back,
+                        MenuAction::Help(_) => Action::Help,
                     };
                     self.action_tx.send(action).ok();
                     return Ok(Some(Action::Menu));
@@ -105,11 +127,14 @@ impl MenuViewComponent {
     }
     pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let area = Rect::new(area.x, area.y, area.width, self.items.len() as u16 + 2);
+        let block = Block::bordered().title("Menu").dim();
+        self.list_areas
+            .update(block.inner(area), 0, vec![1; self.items.len()]);
         f.render_widget(Clear, area);
         f.render_stateful_widget(
             List::new(&self.items)
-                .block(Block::bordered().title("Menu").dim())
-                .highlight_style(Style::default().reversed()),
+                .block(block)
+                .highlight_style(Style::default().patch(self.theme.selected)),
             area,
             &mut self.state,
         );
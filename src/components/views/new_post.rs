@@ -1,16 +1,20 @@
 use super::super::modals::types::{Action as ModalAction, Data, EmbedData};
 use super::super::modals::{EmbedModalComponent, ModalComponent};
+use super::super::mouse::is_click;
+use super::super::text;
 use super::ViewComponent;
 use super::types::{Action, Transition, View};
-use bsky_sdk::BskyAgent;
+use crate::backend::client::Agent;
+use crate::config::{Config, KeybindingScope, Theme};
+use crate::types::Toast;
 use bsky_sdk::api::app::bsky::embed::{self, record_with_media};
-use bsky_sdk::api::app::bsky::feed::post::{RecordData, RecordEmbedRefs};
+use bsky_sdk::api::app::bsky::feed::post::{RecordData, RecordEmbedRefs, ReplyRef};
 use bsky_sdk::api::com::atproto::repo::{create_record, strong_ref};
 use bsky_sdk::api::types::Union;
 use bsky_sdk::api::types::string::{Datetime, Language};
 use bsky_sdk::rich_text::RichText;
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use futures_util::future;
 use image::{ImageFormat, ImageReader};
 use ratatui::layout::{Constraint, Layout};
@@ -57,17 +61,28 @@ impl Focus {
 
 pub struct NewPostViewComponent {
     action_tx: UnboundedSender<Action>,
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
+    reply: Option<ReplyRef>,
     text: TextArea<'static>,
     embed: Option<EmbedData>,
     langs: TextArea<'static>,
     focus: Focus,
+    /// Areas of the fields and the button as last drawn.
+    areas: Vec<(Focus, Rect)>,
+    /// Length of the text in graphemes and in bytes, both limited by the server.
     text_len: usize,
+    text_bytes: usize,
     modals: Option<Box<dyn ModalComponent>>,
+    config: Config,
+    theme: Theme,
 }
 
 impl NewPostViewComponent {
-    pub fn new(action_tx: UnboundedSender<Action>, agent: Arc<BskyAgent>) -> Self {
+    pub fn new(
+        action_tx: UnboundedSender<Action>,
+        agent: Arc<Agent>,
+        reply: Option<ReplyRef>,
+    ) -> Self {
         let mut text = TextArea::default();
         text.set_block(Block::bordered().title("Text"));
         text.set_cursor_line_style(Style::default());
@@ -78,12 +93,17 @@ impl NewPostViewComponent {
         Self {
             action_tx,
             agent,
+            reply,
             text,
             embed: None,
             langs,
             focus: Focus::Text,
+            areas: Vec::new(),
             text_len: 0,
+            text_bytes: 0,
             modals: None,
+            config: Config::default(),
+            theme: Theme::default(),
         }
     }
     fn current_textarea(&mut self) -> Option<&mut TextArea<'static>> {
@@ -113,6 +133,7 @@ impl NewPostViewComponent {
         let agent = self.agent.clone();
         let text = self.text.lines().join("\n");
         let embed_data = self.embed.clone();
+        let reply = self.reply.clone();
         let langs = Some(
             self.langs
                 .lines()
@@ -124,24 +145,30 @@ impl NewPostViewComponent {
         )
         .filter(|v| !v.is_empty());
         tokio::spawn(async move {
-            match Self::try_create_post_record(&agent, embed_data, langs, text).await {
+            match Self::try_create_post_record(&agent, embed_data, reply, langs, text).await {
                 Ok(output) => {
                     log::info!("Post created: {output:?}");
+                    tx.send(Action::Toast(Toast::success("Posted"))).ok();
                     if let Err(e) = tx.send(Action::Transition(Transition::Pop)) {
                         log::error!("failed to send event: {e}");
                     }
                 }
                 Err(e) => {
-                    // TODO: show error message
+                    // the composer stays open, so the post can be sent again after checking the error
                     log::error!("failed to create post: {e}");
+                    tx.send(Action::Toast(Toast::error(format!(
+                        "failed to create the post: {e}"
+                    ))))
+                    .ok();
                 }
             }
         });
         Ok(())
     }
     async fn try_create_post_record(
-        agent: &BskyAgent,
+        agent: &Agent,
         embed_data: Option<EmbedData>,
+        reply: Option<ReplyRef>,
         langs: Option<Vec<Language>>,
         text: String,
     ) -> Result<create_record::Output> {
@@ -229,7 +256,7 @@ impl NewPostViewComponent {
                 facets: rich_text.facets,
                 labels: None,
                 langs,
-                reply: None,
+                reply,
                 tags: None,
                 text: rich_text.text,
             })
@@ -239,7 +266,22 @@ impl NewPostViewComponent {
 
 impl ViewComponent for NewPostViewComponent {
     fn view(&self) -> View {
-        View::NewPost
+        View::NewPost(self.reply.clone().map(Box::new))
+    }
+    fn keybinding_scope(&self) -> Option<KeybindingScope> {
+        Some(if self.modals.is_some() {
+            KeybindingScope::Modal
+        } else {
+            KeybindingScope::Composer
+        })
+    }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        if let Some(modal) = self.modals.as_mut() {
+            modal.register_config_handler(config.clone())?;
+        }
+        self.theme = Theme::from(&config.theme);
+        self.config = config;
+        Ok(())
     }
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         if let Some(modal) = self.modals.as_mut() {
@@ -253,13 +295,17 @@ impl ViewComponent for NewPostViewComponent {
             if focus == Focus::Text {
                 let cursor = textarea.cursor();
                 let result = textarea.input(key) || textarea.cursor() != cursor;
-                self.text_len = RichText::new(self.text.lines().join("\n"), None).grapheme_len();
+                let content = self.text.lines().join("\n");
+                self.text_len = text::grapheme_len(&content);
+                self.text_bytes = content.len();
                 if let Some(block) = self.text.block() {
                     let mut block = block.clone();
                     block = match self.text_len {
                         0 => block.border_style(Color::Reset),
-                        1..=300 => block.border_style(Color::Green),
-                        _ => block.border_style(Color::Red),
+                        1..=text::MAX_GRAPHEMES if self.text_bytes <= text::MAX_BYTES => {
+                            block.border_style(self.theme.valid)
+                        }
+                        _ => block.border_style(self.theme.invalid),
                     };
                     self.text.set_block(block);
                 }
@@ -283,9 +329,9 @@ impl ViewComponent for NewPostViewComponent {
                                 .map(str::trim)
                                 .all(|s| s.parse::<Language>().is_ok())
                             {
-                                block = block.border_style(Color::Green);
+                                block = block.border_style(self.theme.valid);
                             } else {
-                                block = block.border_style(Color::Red);
+                                block = block.border_style(self.theme.invalid);
                             }
                             self.langs.set_block(block);
                         }
@@ -300,6 +346,28 @@ impl ViewComponent for NewPostViewComponent {
         }
         Ok(None)
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if let Some(modal) = self.modals.as_mut() {
+            return modal.handle_mouse_events(mouse);
+        }
+        // the fields are not focusable while the post is being submitted
+        if self.focus == Focus::None {
+            return Ok(None);
+        }
+        let Some(focus) = self
+            .areas
+            .iter()
+            .find(|(_, area)| is_click(&mouse, *area))
+            .map(|(focus, _)| *focus)
+        else {
+            return Ok(None);
+        };
+        self.update_focus(focus);
+        Ok(Some(match focus {
+            Focus::Embed | Focus::Submit => Action::Enter,
+            _ => Action::Render,
+        }))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         if let Some(modal) = self.modals.as_mut() {
             return Ok(match modal.update(action)? {
@@ -333,15 +401,17 @@ impl ViewComponent for NewPostViewComponent {
                 self.update_focus(self.focus.prev());
                 Ok(Some(Action::Render))
             }
-            Action::Enter if self.focus == Focus::Embed => {
-                self.modals = Some(Box::new(EmbedModalComponent::new(
-                    self.action_tx.clone(),
-                    self.embed.clone(),
-                )));
+            Action::Enter if self.focus == Focus::Embed => self.update(Action::Embed),
+            Action::Embed => {
+                let mut modal =
+                    EmbedModalComponent::new(self.action_tx.clone(), self.embed.clone());
+                modal.register_config_handler(self.config.clone())?;
+                self.modals = Some(Box::new(modal));
                 Ok(Some(Action::Render))
             }
-            Action::Enter if self.focus == Focus::Submit => {
-                self.focus = Focus::None;
+            Action::Enter if self.focus == Focus::Submit => self.update(Action::Submit),
+            Action::Submit if self.focus != Focus::None => {
+                self.update_focus(Focus::None);
                 self.create_post_record()?;
                 Ok(Some(Action::Render))
             }
@@ -372,37 +442,58 @@ impl ViewComponent for NewPostViewComponent {
                 (false, len) => format!("  {len} images"),
             });
             if self.focus != Focus::Embed {
-                line = line.yellow();
+                line = line.patch_style(self.theme.embed);
             }
             embed_lines.push(line);
         }
         let mut embed_text = Text::from(embed_lines);
         if self.focus == Focus::Embed {
-            embed_text = embed_text.reversed();
+            embed_text = embed_text.patch_style(self.theme.selected);
         }
-        let mut submit_line = Line::from("Post").centered().blue();
+        let mut submit_line = Line::styled("Post", self.theme.button).centered();
         if self.focus == Focus::Submit {
-            submit_line = submit_line.reversed();
+            submit_line = submit_line.patch_style(self.theme.selected);
         }
         f.render_widget(
-            Paragraph::new("New post").bold().block(
+            Paragraph::new(if self.reply.is_some() {
+                "Reply"
+            } else {
+                "New post"
+            })
+            .bold()
+            .block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .border_style(Color::Gray)
+                    .border_style(self.theme.border)
                     .padding(Padding::horizontal(1)),
             ),
             paragraph,
         );
         f.render_widget(
-            Line::from(format!("{} ", 300 - self.text_len as isize))
-                .right_aligned()
-                .gray(),
+            Line::from(format!(
+                "{} ",
+                text::MAX_GRAPHEMES as isize - self.text_len as isize
+            ))
+            .right_aligned()
+            .style(
+                if self.text_len > text::MAX_GRAPHEMES || self.text_bytes > text::MAX_BYTES {
+                    self.theme.invalid
+                } else {
+                    self.theme.muted
+                },
+            ),
             text_len,
         );
         f.render_widget(&self.text, text);
         f.render_widget(embed_text, embed);
         f.render_widget(&self.langs, langs);
         f.render_widget(submit_line, submit);
+        self.areas = vec![
+            (Focus::Text, text),
+            (Focus::Embed, embed),
+            (Focus::Langs, langs),
+            (Focus::Submit, submit),
+        ];
 
         for modal in self.modals.iter_mut() {
             modal.draw(f, area)?;
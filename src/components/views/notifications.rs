@@ -0,0 +1,282 @@
+use super::super::mouse::ListAreas;
+use super::ViewComponent;
+use super::palette::Command;
+use super::types::{Action, Data, Transition, View};
+use super::utils::{profile_name, text_lines};
+use crate::backend::client::Agent;
+use crate::backend::{Watch, Watcher};
+use crate::config::{Config, Theme, TimestampFormat};
+use crate::types::Toast;
+use bsky_sdk::api::app::bsky::feed::post;
+use bsky_sdk::api::app::bsky::notification::list_notifications::Notification;
+use bsky_sdk::api::types::TryFromUnknown;
+use color_eyre::Result;
+use crossterm::event::MouseEvent;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListState, Padding, Paragraph};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+
+pub struct NotificationsViewComponent {
+    items: Vec<Notification>,
+    state: ListState,
+    list_areas: ListAreas,
+    action_tx: UnboundedSender<Action>,
+    agent: Arc<Agent>,
+    watcher: Box<dyn Watch<Output = Vec<Notification>>>,
+    quit: Option<oneshot::Sender<()>>,
+    theme: Theme,
+    timestamp: TimestampFormat,
+}
+
+impl NotificationsViewComponent {
+    pub fn new(action_tx: UnboundedSender<Action>, watcher: Arc<Watcher>) -> Self {
+        let agent = watcher.agent.clone();
+        let watcher = Box::new(watcher.notifications());
+        Self {
+            items: Vec::new(),
+            state: ListState::default(),
+            list_areas: ListAreas::default(),
+            action_tx,
+            agent,
+            watcher,
+            quit: None,
+            theme: Theme::default(),
+            timestamp: TimestampFormat::default(),
+        }
+    }
+    /// What to open for the notification: the post it is about, or the account that followed.
+    fn command(notification: &Notification) -> Command {
+        match notification.reason.as_str() {
+            "reply" | "mention" | "quote" => Command::Open(notification.uri.clone()),
+            "follow" | "starterpack-joined" => {
+                Command::Profile(notification.author.did.as_str().to_string())
+            }
+            _ => Command::Open(
+                notification
+                    .reason_subject
+                    .clone()
+                    .unwrap_or_else(|| notification.uri.clone()),
+            ),
+        }
+    }
+    fn lines<'a>(
+        notification: &'a Notification,
+        area: Rect,
+        theme: &Theme,
+        timestamp: &TimestampFormat,
+    ) -> Vec<Line<'a>> {
+        let reason = match notification.reason.as_str() {
+            "like" => "liked your post",
+            "repost" => "reposted your post",
+            "follow" => "followed you",
+            "mention" => "mentioned you",
+            "reply" => "replied to you",
+            "quote" => "quoted your post",
+            "starterpack-joined" => "joined via your starter pack",
+            "like-via-repost" => "liked your repost",
+            "repost-via-repost" => "reposted your repost",
+            reason => reason,
+        };
+        let mut spans = [
+            vec![
+                Span::from(timestamp.format(notification.indexed_at.as_ref()))
+                    .style(theme.timestamp),
+                Span::from(": "),
+            ],
+            profile_name(&notification.author, theme),
+            vec![Span::from(" "), Span::from(reason)],
+        ]
+        .concat();
+        if !notification.is_read {
+            spans = spans.into_iter().map(|span| span.bold()).collect();
+        }
+        let mut lines = vec![Line::from(spans)];
+        if matches!(notification.reason.as_str(), "reply" | "mention" | "quote") {
+            if let Ok(record) = post::Record::try_from_unknown(notification.record.clone()) {
+                lines.extend(text_lines(&record, usize::from(area.width), "  ", theme));
+            }
+        }
+        lines
+    }
+}
+
+impl ViewComponent for NotificationsViewComponent {
+    fn view(&self) -> View {
+        View::Notifications
+    }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        self.timestamp = config.timestamp.feed;
+        Ok(())
+    }
+    fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        let active = self.quit.is_some();
+        if active {
+            self.deactivate()?;
+        }
+        self.watcher = Box::new(watcher.notifications());
+        if active {
+            self.activate()?;
+        }
+        Ok(())
+    }
+    fn activate(&mut self) -> Result<()> {
+        let (tx, mut rx) = (self.action_tx.clone(), self.watcher.subscribe());
+        let (quit_tx, mut quit_rx) = oneshot::channel();
+        self.quit = Some(quit_tx);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    changed = rx.changed() => {
+                        match changed {
+                            Ok(()) => {
+                                if let Err(e) = tx.send(Action::Update(Box::new(Data::Notifications(
+                                    rx.borrow_and_update().clone(),
+                                )))) {
+                                    log::error!("failed to send update action: {e}");
+                                }
+                            }
+                            Err(e) => {
+                                log::warn!("changed channel error: {e}");
+                                break;
+                            }
+                        }
+                    }
+                    _ = &mut quit_rx => {
+                        break;
+                    }
+                }
+            }
+            log::debug!("subscription finished");
+        });
+        Ok(())
+    }
+    fn deactivate(&mut self) -> Result<()> {
+        if let Some(tx) = self.quit.take() {
+            if tx.send(()).is_err() {
+                log::error!("failed to send quit signal");
+            }
+        }
+        self.watcher.unsubscribe();
+        Ok(())
+    }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(self.list_areas.handle_mouse_events(mouse, &mut self.state))
+    }
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if matches!(action, Action::Newest) {
+            self.watcher.refresh();
+        }
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.state) {
+            return Ok(Some(action));
+        }
+        match action {
+            Action::NextItem if !self.items.is_empty() => {
+                self.state.select(Some(
+                    self.state
+                        .selected()
+                        .map(|s| (s + 1).min(self.items.len() - 1))
+                        .unwrap_or_default(),
+                ));
+                return Ok(Some(Action::Render));
+            }
+            Action::PrevItem if !self.items.is_empty() => {
+                self.state.select(Some(
+                    self.state
+                        .selected()
+                        .map(|s| s.max(1) - 1)
+                        .unwrap_or_default(),
+                ));
+                return Ok(Some(Action::Render));
+            }
+            Action::Enter => {
+                if let Some(notification) = self.state.selected().and_then(|i| self.items.get(i)) {
+                    let command = Self::command(notification);
+                    let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                    tokio::spawn(async move {
+                        match command.view(&agent).await {
+                            Ok(view) => {
+                                tx.send(Action::Transition(Transition::Push(Box::new(view))))
+                                    .ok();
+                            }
+                            Err(e) => {
+                                log::error!("failed to open {command:?}: {e}");
+                                tx.send(Action::Toast(Toast::error(format!(
+                                    "failed to open: {e}"
+                                ))))
+                                .ok();
+                            }
+                        }
+                    });
+                }
+            }
+            Action::Back => return Ok(Some(Action::Transition(Transition::Pop))),
+            Action::Refresh => {
+                self.watcher.refresh();
+            }
+            Action::Update(data) => {
+                let Data::Notifications(notifications) = data.as_ref() else {
+                    return Ok(None);
+                };
+                let select = if let Some(cid) = self
+                    .state
+                    .selected()
+                    .and_then(|i| self.items.get(i))
+                    .map(|notification| notification.cid.as_ref())
+                {
+                    notifications
+                        .iter()
+                        .position(|notification| notification.cid.as_ref() == cid)
+                } else {
+                    None
+                };
+                self.items.clone_from(notifications);
+                self.state.select(select);
+                return Ok(Some(Action::Render));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let header = Paragraph::new(Line::from("Notifications").bold()).block(
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .border_style(self.theme.border)
+                .padding(Padding::horizontal(1)),
+        );
+        let layout =
+            Layout::vertical([Constraint::Length(2), Constraint::Percentage(100)]).split(area);
+        let block = Block::default().padding(Padding::horizontal(1));
+        let inner = block.inner(layout[1]);
+        let items = self
+            .items
+            .iter()
+            .map(|notification| {
+                Text::from(Self::lines(
+                    notification,
+                    inner,
+                    &self.theme,
+                    &self.timestamp,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let heights = items.iter().map(Text::height).collect::<Vec<_>>();
+
+        f.render_widget(header, layout[0]);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().reset().patch(self.theme.selected))
+                .block(block),
+            layout[1],
+            &mut self.state,
+        );
+        self.list_areas.update(inner, self.state.offset(), heights);
+        Ok(())
+    }
+}
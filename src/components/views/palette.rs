@@ -0,0 +1,522 @@
+use super::types::{Action, View};
+use crate::backend::client::Agent;
+use crate::backend::types::FeedSourceInfo;
+use crate::config::{
+    Binding, ColumnAction, ComposerAction, Config, FeedAction, GlobalAction, KeybindingScope,
+    ModalAction, PostAction, Theme,
+};
+use bsky_sdk::api::types::string::{AtIdentifier, Did};
+use color_eyre::{Result, eyre};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Padding};
+use std::fmt::Debug;
+use tui_textarea::TextArea;
+
+/// Commands that take an argument, with the description of the argument.
+const ARGUMENT_COMMANDS: [(&str, &str); 3] = [
+    ("open", "<at-uri or bsky.app URL>"),
+    ("feed", "<feed or list URI>"),
+    ("profile", "<handle or DID>"),
+];
+
+/// A command chosen from the command palette.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Global(GlobalAction),
+    View(Action),
+    /// Open a post, a feed, a list or a profile.
+    Open(String),
+    /// Open a feed generator or a list.
+    Feed(String),
+    /// Open the posts of an account.
+    Profile(String),
+}
+
+impl Command {
+    /// Fetch what the command refers to and return the view to show it.
+    pub async fn view(&self, agent: &Agent) -> Result<View> {
+        let (arg, target) = match self {
+            Self::Open(arg) => (arg, parse_target(arg)),
+            Self::Feed(arg) => (
+                arg,
+                parse_target(arg).filter(|t| matches!(t, Target::Feed(..) | Target::List(..))),
+            ),
+            Self::Profile(arg) => (
+                arg,
+                parse_target(arg).filter(|t| matches!(t, Target::Profile(_))),
+            ),
+            Self::Global(_) | Self::View(_) => return Err(eyre::eyre!("not a view: {self:?}")),
+        };
+        match target.ok_or_else(|| eyre::eyre!("invalid argument: {arg}"))? {
+            Target::Post(actor, rkey) => {
+                let uri = format!(
+                    "at://{}/app.bsky.feed.post/{rkey}",
+                    resolve(agent, &actor).await?.as_str()
+                );
+                let post_view = agent
+                    .api
+                    .app
+                    .bsky
+                    .feed
+                    .get_posts(
+                        bsky_sdk::api::app::bsky::feed::get_posts::ParametersData {
+                            uris: vec![uri.clone()],
+                        }
+                        .into(),
+                    )
+                    .await?
+                    .data
+                    .posts
+                    .pop()
+                    .ok_or_else(|| eyre::eyre!("post not found: {uri}"))?;
+                Ok(View::Post(Box::new((post_view, None))))
+            }
+            Target::Feed(actor, rkey) => {
+                let feed = format!(
+                    "at://{}/app.bsky.feed.generator/{rkey}",
+                    resolve(agent, &actor).await?.as_str()
+                );
+                let output = agent
+                    .api
+                    .app
+                    .bsky
+                    .feed
+                    .get_feed_generator(
+                        bsky_sdk::api::app::bsky::feed::get_feed_generator::ParametersData { feed }
+                            .into(),
+                    )
+                    .await?;
+                Ok(View::Feed(Box::new(FeedSourceInfo::Feed(Box::new(
+                    output.data.view,
+                )))))
+            }
+            Target::List(actor, rkey) => {
+                let list = format!(
+                    "at://{}/app.bsky.graph.list/{rkey}",
+                    resolve(agent, &actor).await?.as_str()
+                );
+                let output = agent
+                    .api
+                    .app
+                    .bsky
+                    .graph
+                    .get_list(
+                        bsky_sdk::api::app::bsky::graph::get_list::ParametersData {
+                            cursor: None,
+                            limit: 1.try_into().ok(),
+                            list,
+                        }
+                        .into(),
+                    )
+                    .await?;
+                Ok(View::Feed(Box::new(FeedSourceInfo::List(Box::new(
+                    output.data.list,
+                )))))
+            }
+            Target::Profile(actor) => {
+                let profile_view = agent
+                    .api
+                    .app
+                    .bsky
+                    .actor
+                    .get_profile(
+                        bsky_sdk::api::app::bsky::actor::get_profile::ParametersData {
+                            actor: actor
+                                .parse::<AtIdentifier>()
+                                .map_err(|e| eyre::eyre!("invalid actor {actor}: {e}"))?,
+                        }
+                        .into(),
+                    )
+                    .await?;
+                Ok(View::Feed(Box::new(FeedSourceInfo::Author(Box::new(
+                    profile_view,
+                )))))
+            }
+        }
+    }
+}
+
+/// What the argument of a command refers to, with the handle or DID of its owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Post(String, String),
+    Feed(String, String),
+    List(String, String),
+    Profile(String),
+}
+
+/// Parse an AT URI, a bsky.app URL or a bare handle or DID.
+fn parse_target(s: &str) -> Option<Target> {
+    let s = s.trim();
+    // the web URLs name the collections differently
+    let (path, [post, feed, list]) = if let Some(path) = s.strip_prefix("at://") {
+        (
+            path,
+            [
+                "app.bsky.feed.post",
+                "app.bsky.feed.generator",
+                "app.bsky.graph.list",
+            ],
+        )
+    } else if let Some(path) = s.strip_prefix("https://bsky.app/profile/") {
+        (path, ["post", "feed", "lists"])
+    } else {
+        let actor = s.strip_prefix('@').unwrap_or(s);
+        return (!actor.is_empty() && !actor.contains(['/', ' ']))
+            .then(|| Target::Profile(actor.to_string()));
+    };
+    match path
+        .trim_end_matches('/')
+        .split('/')
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [actor] => Some(Target::Profile(actor.to_string())),
+        [actor, collection, rkey] if *collection == post => {
+            Some(Target::Post(actor.to_string(), rkey.to_string()))
+        }
+        [actor, collection, rkey] if *collection == feed => {
+            Some(Target::Feed(actor.to_string(), rkey.to_string()))
+        }
+        [actor, collection, rkey] if *collection == list => {
+            Some(Target::List(actor.to_string(), rkey.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Resolve a handle to its DID, as records are addressed by DIDs.
+async fn resolve(agent: &Agent, actor: &str) -> Result<Did> {
+    if let Ok(did) = actor.parse() {
+        return Ok(did);
+    }
+    let handle = actor
+        .parse()
+        .map_err(|e| eyre::eyre!("invalid handle {actor}: {e}"))?;
+    Ok(agent
+        .api
+        .com
+        .atproto
+        .identity
+        .resolve_handle(
+            bsky_sdk::api::com::atproto::identity::resolve_handle::ParametersData { handle }.into(),
+        )
+        .await?
+        .data
+        .did)
+}
+
+/// Score how well `pattern` matches `text`, if all of its characters appear in order.
+///
+/// Matches at the start of words and runs of consecutive characters score higher.
+fn fuzzy_score(pattern: &str, text: &str) -> Option<usize> {
+    let mut pattern = pattern.chars().filter(|c| !c.is_whitespace()).peekable();
+    let (mut score, mut prev, mut consecutive) = (0, None::<char>, false);
+    for c in text.chars() {
+        let Some(p) = pattern.peek() else {
+            break;
+        };
+        let boundary = prev.is_none_or(|prev| {
+            !prev.is_alphanumeric() || (prev.is_lowercase() && c.is_uppercase())
+        });
+        consecutive = if c.to_lowercase().eq(p.to_lowercase()) {
+            score += 1;
+            if boundary {
+                score += 4;
+            }
+            if consecutive {
+                score += 2;
+            }
+            pattern.next();
+            true
+        } else {
+            false
+        };
+        prev = Some(c);
+    }
+    pattern.peek().is_none().then_some(score)
+}
+
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    section: &'static str,
+    keys: Vec<String>,
+    /// `None` for a command that still needs its argument.
+    command: Option<Command>,
+}
+
+/// A prompt to run any action or command by name.
+pub struct PaletteViewComponent {
+    entries: Vec<Entry>,
+    input: TextArea<'static>,
+    state: ListState,
+    theme: Theme,
+}
+
+impl PaletteViewComponent {
+    pub fn new(config: &Config, scope: Option<KeybindingScope>) -> Self {
+        let bindings = config.keybindings.bindings();
+        let mut entries = Vec::new();
+        entries.extend(GlobalAction::ALL.iter().map(|action| {
+            Entry::action(&bindings, "global", action, Command::Global(action.clone()))
+        }));
+        entries.extend(
+            ColumnAction::ALL
+                .iter()
+                .filter(|action| **action != ColumnAction::CommandPalette)
+                .map(|action| {
+                    Entry::action(&bindings, "column", action, Command::View(action.into()))
+                }),
+        );
+        if let Some(scope) = scope {
+            let section = scope.section();
+            match scope {
+                KeybindingScope::Feed => entries.extend(FeedAction::ALL.iter().map(|action| {
+                    Entry::action(&bindings, section, action, Command::View(action.into()))
+                })),
+                KeybindingScope::Post => entries.extend(PostAction::ALL.iter().map(|action| {
+                    Entry::action(&bindings, section, action, Command::View(action.into()))
+                })),
+                KeybindingScope::Composer => {
+                    entries.extend(ComposerAction::ALL.iter().map(|action| {
+                        Entry::action(&bindings, section, action, Command::View(action.into()))
+                    }))
+                }
+                KeybindingScope::Modal => entries.extend(ModalAction::ALL.iter().map(|action| {
+                    Entry::action(&bindings, section, action, Command::View(action.into()))
+                })),
+            }
+        }
+        entries.extend(ARGUMENT_COMMANDS.iter().map(|(name, arg)| Entry {
+            name: format!("{name} {arg}"),
+            section: "command",
+            keys: Vec::new(),
+            command: None,
+        }));
+
+        let mut input = TextArea::default();
+        input.set_block(Block::bordered().title(":"));
+        input.set_cursor_line_style(Style::default());
+        Self {
+            entries,
+            input,
+            state: ListState::default().with_selected(Some(0)),
+            theme: Theme::from(&config.theme),
+        }
+    }
+    /// Entries matching the input, best matches first.
+    ///
+    /// An input starting with the name of a command with an argument becomes that command.
+    fn candidates(&self) -> Vec<Entry> {
+        let input = self.input.lines().join("");
+        if let Some((name, arg)) = input.trim_start().split_once(' ') {
+            let arg = arg.trim();
+            let command = match name {
+                "open" => Some(Command::Open(arg.to_string())),
+                "feed" => Some(Command::Feed(arg.to_string())),
+                "profile" => Some(Command::Profile(arg.to_string())),
+                _ => None,
+            };
+            if let Some(command) = command.filter(|_| !arg.is_empty()) {
+                return vec![Entry {
+                    name: format!("{name} {arg}"),
+                    section: "command",
+                    keys: Vec::new(),
+                    command: Some(command),
+                }];
+            }
+        }
+        let mut scored = self
+            .entries
+            .iter()
+            .filter_map(|entry| fuzzy_score(&input, &entry.name).map(|score| (score, entry)))
+            .collect::<Vec<_>>();
+        // the sort is stable, so entries with the same score keep the order of sections
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, entry)| entry.clone()).collect()
+    }
+    /// The command to run for the selected candidate.
+    pub fn command(&self) -> Option<Command> {
+        self.candidates()
+            .into_iter()
+            .nth(self.state.selected().unwrap_or_default())
+            .and_then(|entry| entry.command)
+    }
+    /// Returns [`Action::Back`] to close the palette, or [`Action::Enter`] when [`Self::command`] is ready to run.
+    pub fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => return Ok(Some(Action::Back)),
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                self.state.select_next();
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                self.state.select_previous();
+            }
+            (KeyCode::Enter, _) | (KeyCode::Char('m'), KeyModifiers::CONTROL) => {
+                let candidates = self.candidates();
+                let Some(entry) = candidates.get(self.state.selected().unwrap_or_default()) else {
+                    return Ok(None);
+                };
+                if entry.command.is_some() {
+                    return Ok(Some(Action::Enter));
+                }
+                // complete the name of a command that needs an argument
+                let name = entry.name.split(' ').next().unwrap_or_default();
+                self.input.select_all();
+                self.input.cut();
+                self.input.insert_str(format!("{name} "));
+                self.state.select(Some(0));
+            }
+            _ => {
+                if !self.input.input(key) {
+                    return Ok(None);
+                }
+                self.state.select(Some(0));
+            }
+        }
+        Ok(Some(Action::Render))
+    }
+    pub fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.state.select_next(),
+            MouseEventKind::ScrollUp => self.state.select_previous(),
+            _ => return Ok(None),
+        }
+        Ok(Some(Action::Render))
+    }
+    pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let area = area.inner(Margin::new(1, 1));
+        let block = Block::bordered()
+            .title("Command palette")
+            .padding(Padding::horizontal(1));
+        let [input, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(block.inner(area));
+
+        let candidates = self.candidates();
+        if let Some(selected) = self.state.selected() {
+            self.state
+                .select(Some(selected.min(candidates.len().saturating_sub(1))));
+        }
+        let mut items = candidates
+            .into_iter()
+            .map(|entry| {
+                let mut spans = vec![
+                    Span::from(entry.name),
+                    Span::from(" "),
+                    Span::styled(format!("[{}]", entry.section), self.theme.muted),
+                ];
+                if !entry.keys.is_empty() {
+                    spans.push(Span::from(" "));
+                    spans.push(Span::styled(entry.keys.join(", "), self.theme.facet));
+                }
+                ListItem::from(Line::from(spans))
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            items.push(ListItem::from(Line::styled(
+                "No matching commands",
+                self.theme.muted,
+            )));
+        }
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(&self.input, input);
+        f.render_stateful_widget(
+            List::new(items).highlight_style(Style::default().patch(self.theme.selected)),
+            list,
+            &mut self.state,
+        );
+        Ok(())
+    }
+}
+
+impl Entry {
+    fn action<A: Debug>(
+        bindings: &[Binding],
+        section: &'static str,
+        action: &A,
+        command: Command,
+    ) -> Self {
+        let name = format!("{action:?}");
+        Self {
+            keys: bindings
+                .iter()
+                .filter(|binding| binding.section == section && binding.action == name)
+                .map(|binding| binding.sequence.to_string())
+                .collect(),
+            name,
+            section,
+            command: Some(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy() {
+        assert_eq!(fuzzy_score("", "NextItem"), Some(0));
+        assert!(fuzzy_score("ni", "NextItem").is_some());
+        assert!(fuzzy_score("NI", "nextitem").is_some());
+        assert!(fuzzy_score("in", "NextItem").is_none());
+        assert!(fuzzy_score("next item", "NextItem").is_some());
+        // word starts score higher than the middle of a word
+        assert!(fuzzy_score("ni", "NextItem") > fuzzy_score("ni", "Download"));
+        // consecutive characters score higher
+        assert!(fuzzy_score("ref", "Refresh") > fuzzy_score("ref", "PrevField"));
+    }
+
+    #[test]
+    fn targets() {
+        let did = "did:plc:z72i7hdynmk6r22z27h6tvur";
+        for (input, expected) in [
+            (
+                format!("at://{did}/app.bsky.feed.post/3k44deefqdk2g"),
+                Some(Target::Post(did.into(), "3k44deefqdk2g".into())),
+            ),
+            (
+                format!("at://{did}/app.bsky.feed.generator/whats-hot"),
+                Some(Target::Feed(did.into(), "whats-hot".into())),
+            ),
+            (
+                format!("at://{did}/app.bsky.graph.list/3kflf2r3lwg2x"),
+                Some(Target::List(did.into(), "3kflf2r3lwg2x".into())),
+            ),
+            (format!("at://{did}"), Some(Target::Profile(did.into()))),
+            (
+                String::from("https://bsky.app/profile/bsky.app/post/3k44deefqdk2g"),
+                Some(Target::Post("bsky.app".into(), "3k44deefqdk2g".into())),
+            ),
+            (
+                String::from("https://bsky.app/profile/bsky.app/feed/whats-hot"),
+                Some(Target::Feed("bsky.app".into(), "whats-hot".into())),
+            ),
+            (
+                String::from("https://bsky.app/profile/bsky.app/lists/3kflf2r3lwg2x"),
+                Some(Target::List("bsky.app".into(), "3kflf2r3lwg2x".into())),
+            ),
+            (
+                String::from("https://bsky.app/profile/bsky.app/"),
+                Some(Target::Profile("bsky.app".into())),
+            ),
+            (
+                String::from("@bsky.app"),
+                Some(Target::Profile("bsky.app".into())),
+            ),
+            (String::from(did), Some(Target::Profile(did.into()))),
+            (format!("at://{did}/app.bsky.feed.like/3k44deefqdk2g"), None),
+            (String::from("https://example.com/post/1"), None),
+            (String::new(), None),
+        ] {
+            assert_eq!(parse_target(&input), expected, "{input}");
+        }
+    }
+}
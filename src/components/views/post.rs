@@ -1,7 +1,17 @@
+use super::super::mouse::ListAreas;
 use super::ViewComponent;
+use super::palette::Command;
 use super::types::{Action, Data, Transition, View};
-use super::utils::{counts, profile_name, profile_name_as_str};
+use super::utils::{
+    counts, download, facets, like, links, profile_name, profile_name_as_str, reply_ref, repost,
+    text_lines,
+};
+use crate::backend::client::Agent;
+use crate::backend::download::image_sources;
+use crate::backend::types::FeedSourceInfo;
 use crate::backend::{Watch, Watcher};
+use crate::config::{Config, DownloadConfig, KeybindingScope, Theme, TimestampFormat};
+use crate::types::Toast;
 use bsky_sdk::api::agent::atp_agent::AtpSession;
 use bsky_sdk::api::app::bsky::actor::defs::ProfileViewBasic;
 use bsky_sdk::api::app::bsky::embed::record::{self, ViewRecordRefs};
@@ -13,15 +23,12 @@ use bsky_sdk::api::app::bsky::feed::defs::{
 use bsky_sdk::api::app::bsky::feed::get_post_thread::OutputThreadRefs;
 use bsky_sdk::api::app::bsky::feed::post;
 use bsky_sdk::api::app::bsky::richtext::facet::MainFeaturesItem;
-use bsky_sdk::api::types::string::Datetime;
 use bsky_sdk::api::types::{TryFromUnknown, Union};
-use bsky_sdk::{BskyAgent, api};
-use chrono::Local;
 use color_eyre::Result;
-use indexmap::IndexSet;
+use crossterm::event::MouseEvent;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Layout, Margin, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
     Block, Borders, Cell, List, ListItem, ListState, Padding, Paragraph, Row, Table, TableState,
@@ -35,33 +42,50 @@ enum PostAction {
     Profile(Box<ProfileViewBasic>),
     Reply,
     Repost,
+    Unrepost(String),
     Like,
     Unlike(String),
     Delete,
     Open(String),
+    /// Text and DID of a mentioned account.
+    Mention(String, String),
+    Tag(String),
+    Download(usize),
     ViewRecord(Box<record::ViewRecord>),
 }
 
-impl<'a> From<&'a PostAction> for ListItem<'a> {
-    fn from(action: &'a PostAction) -> Self {
-        match action {
-            PostAction::Profile(profile) => Self::from(Line::from(vec![
+impl PostAction {
+    fn list_item(&self, theme: &Theme) -> ListItem<'_> {
+        match self {
+            PostAction::Profile(profile) => ListItem::from(Line::from(vec![
                 Span::from("Show "),
                 Span::from(profile_name_as_str(profile.as_ref())).bold(),
                 Span::from("'s profile"),
             ]))
             .dim(),
-            PostAction::Reply => Self::from("Reply").dim(),
-            PostAction::Repost => Self::from("Repost").dim(),
-            PostAction::Like => Self::from("Like"),
-            PostAction::Unlike(_) => Self::from("Unlike"),
-            PostAction::Delete => Self::from("Delete").red(),
-            PostAction::Open(uri) => Self::from(format!("Open {uri}")),
-            PostAction::ViewRecord(view_record) => Self::from(Line::from(vec![
+            PostAction::Reply => ListItem::from("Reply"),
+            PostAction::Repost => ListItem::from("Repost"),
+            PostAction::Unrepost(_) => ListItem::from("Undo repost"),
+            PostAction::Like => ListItem::from("Like"),
+            PostAction::Unlike(_) => ListItem::from("Unlike"),
+            PostAction::Delete => ListItem::from("Delete").style(theme.danger),
+            PostAction::Open(uri) => ListItem::from(format!("Open {uri}")),
+            PostAction::Mention(text, _) => ListItem::from(Line::from(vec![
+                Span::from("Open "),
+                Span::styled(text.as_str(), theme.facet),
+                Span::from("'s profile"),
+            ])),
+            PostAction::Tag(tag) => ListItem::from(Line::from(vec![
+                Span::from("Search "),
+                Span::styled(format!("#{tag}"), theme.facet),
+            ])),
+            PostAction::Download(1) => ListItem::from("Download 1 image"),
+            PostAction::Download(len) => ListItem::from(format!("Download {len} images")),
+            PostAction::ViewRecord(view_record) => ListItem::from(Line::from(vec![
                 Span::from("Show "),
-                Span::from("embedded record").yellow(),
+                Span::styled("embedded record", theme.embed),
                 Span::from(" "),
-                Span::from(view_record.uri.as_str()).underlined(),
+                Span::styled(view_record.uri.as_str(), theme.link),
             ])),
         }
     }
@@ -73,11 +97,15 @@ pub struct PostViewComponent {
     actions: Vec<PostAction>,
     table_state: TableState,
     list_state: ListState,
+    list_areas: ListAreas,
     action_tx: UnboundedSender<Action>,
-    agent: Arc<BskyAgent>,
+    agent: Arc<Agent>,
     watcher: Box<dyn Watch<Output = Union<OutputThreadRefs>>>,
     quit: Option<oneshot::Sender<()>>,
     session: Option<AtpSession>,
+    download: DownloadConfig,
+    theme: Theme,
+    timestamp: TimestampFormat,
 }
 
 impl PostViewComponent {
@@ -87,6 +115,7 @@ impl PostViewComponent {
         post_view: PostView,
         reply: Option<PostView>,
         session: Option<AtpSession>,
+        download: DownloadConfig,
     ) -> Self {
         let actions = Self::post_view_actions(&post_view, &session);
         let agent = watcher.agent.clone();
@@ -97,22 +126,31 @@ impl PostViewComponent {
             actions,
             table_state: TableState::default(),
             list_state: ListState::default(),
+            list_areas: ListAreas::default(),
             action_tx,
             agent,
             watcher,
             quit: None,
             session,
+            download,
+            theme: Theme::default(),
+            timestamp: TimestampFormat::default(),
         }
     }
     fn post_view_actions(post_view: &PostView, session: &Option<AtpSession>) -> Vec<PostAction> {
-        let mut liked = None;
+        let (mut reposted, mut liked) = (None, None);
         if let Some(viewer) = &post_view.viewer {
+            reposted = viewer.repost.as_ref();
             liked = viewer.like.as_ref();
         }
         let mut actions = vec![
             PostAction::Profile(Box::new(post_view.author.clone())),
             PostAction::Reply,
-            PostAction::Repost,
+            if let Some(uri) = reposted {
+                PostAction::Unrepost(uri.clone())
+            } else {
+                PostAction::Repost
+            },
             if let Some(uri) = liked {
                 PostAction::Unlike(uri.clone())
             } else {
@@ -122,62 +160,40 @@ impl PostViewComponent {
         if Some(&post_view.author.did) == session.as_ref().map(|s| &s.data.did) {
             actions.push(PostAction::Delete);
         }
-        let mut links = IndexSet::new();
-        if let Ok(record) = post::Record::try_from_unknown(post_view.record.clone()) {
-            if let Some(facets) = &record.facets {
-                for facet in facets {
-                    for feature in &facet.features {
-                        match feature {
-                            Union::Refs(MainFeaturesItem::Mention(_)) => {
-                                // TODO
-                            }
-                            Union::Refs(MainFeaturesItem::Link(link)) => {
-                                links.insert(link.uri.clone());
-                            }
-                            Union::Refs(MainFeaturesItem::Tag(_)) => {
-                                // TODO
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+        match &post_view.embed {
+            Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordView(record))) => {
+                actions.extend(Self::record_actions(record));
             }
+            Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(
+                record_with_media,
+            ))) => {
+                actions.extend(Self::record_actions(&record_with_media.record));
+            }
+            _ => {}
         }
-        if let Some(embed) = &post_view.embed {
-            match embed {
-                Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(images)) => {
-                    for image in &images.images {
-                        links.insert(image.fullsize.clone());
-                    }
-                }
-                Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(external)) => {
-                    links.insert(external.external.uri.clone());
-                }
-                Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordView(record)) => {
-                    actions.extend(Self::record_actions(record));
-                }
-                Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(
-                    record_with_media,
-                )) => {
-                    match &record_with_media.media {
-                        Union::Refs(ViewMediaRefs::AppBskyEmbedImagesView(images)) => {
-                            for image in &images.images {
-                                links.insert(image.fullsize.clone());
-                            }
-                        }
-                        Union::Refs(ViewMediaRefs::AppBskyEmbedExternalView(external)) => {
-                            links.insert(external.external.uri.clone());
-                        }
-                        _ => {}
-                    }
-                    actions.extend(Self::record_actions(&record_with_media.record));
+        // links in the facets are opened with the other links
+        if let Ok(record) = post::Record::try_from_unknown(post_view.record.clone()) {
+            for (range, feature) in facets(&record) {
+                match feature {
+                    MainFeaturesItem::Mention(mention) => actions.push(PostAction::Mention(
+                        record.text[range].to_string(),
+                        mention.did.as_ref().to_string(),
+                    )),
+                    MainFeaturesItem::Tag(tag) => actions.push(PostAction::Tag(tag.tag.clone())),
+                    MainFeaturesItem::Link(_) => {}
                 }
-                _ => {}
             }
         }
+        let images = image_sources(post_view).len();
+        if images > 0 {
+            actions.push(PostAction::Download(images));
+        }
         [
             actions,
-            links.into_iter().map(PostAction::Open).collect::<Vec<_>>(),
+            links(post_view)
+                .into_iter()
+                .map(PostAction::Open)
+                .collect::<Vec<_>>(),
         ]
         .concat()
     }
@@ -203,7 +219,12 @@ impl PostViewComponent {
         }
         actions
     }
-    fn post_view_rows(post_view: &PostView, width: u16) -> Option<Vec<Row<'_>>> {
+    fn post_view_rows<'a>(
+        post_view: &'a PostView,
+        width: u16,
+        theme: &Theme,
+        timestamp: &TimestampFormat,
+    ) -> Option<Vec<Row<'a>>> {
         let Ok(record) = post::Record::try_from_unknown(post_view.record.clone()) else {
             return None;
         };
@@ -218,52 +239,39 @@ impl PostViewComponent {
         }
         if let Some(labels) = post_view.author.labels.as_ref().filter(|v| !v.is_empty()) {
             for label in labels {
-                let mut spans = vec![Span::from(label.val.as_str()).magenta()];
+                let mut spans = vec![Span::styled(label.val.as_str(), theme.label)];
                 if !label.uri.ends_with("/self") {
                     spans.extend([Span::from(" "), format!("by {}", label.src.as_ref()).dim()]);
                 }
                 author_lines.push(Line::from(spans));
             }
         }
-        let text_lines = textwrap::wrap(&record.text, usize::from(width));
+        let body = text_lines(&record, usize::from(width), "", theme);
         let mut rows = vec![
             Row::new(vec![
-                Cell::from("CID:".gray().into_right_aligned_line()),
+                Cell::from(Span::styled("CID:", theme.muted).into_right_aligned_line()),
                 Cell::from(post_view.cid.as_ref().to_string()),
             ]),
             Row::new(vec![
-                Cell::from("IndexedAt:".gray().into_right_aligned_line()),
-                Cell::from(
-                    post_view
-                        .indexed_at
-                        .as_ref()
-                        .with_timezone(&Local)
-                        .format("%Y-%m-%d %H:%M:%S %z")
-                        .to_string(),
-                )
-                .green(),
+                Cell::from(Span::styled("IndexedAt:", theme.muted).into_right_aligned_line()),
+                Cell::from(timestamp.format(post_view.indexed_at.as_ref())).style(theme.timestamp),
             ]),
             Row::default().height(author_lines.len() as u16).cells(vec![
-                Cell::from("Author:".gray().into_right_aligned_line()),
+                Cell::from(Span::styled("Author:", theme.muted).into_right_aligned_line()),
                 Cell::from(Text::from(author_lines)),
             ]),
             Row::new(vec![
-                Cell::from("Counts:".gray().into_right_aligned_line()),
-                Cell::from(Line::from(counts(post_view, 0))),
+                Cell::from(Span::styled("Counts:", theme.muted).into_right_aligned_line()),
+                Cell::from(Line::from(counts(post_view, 0, theme))),
             ]),
-            Row::default().height(text_lines.len() as u16).cells(vec![
-                Cell::from("Text:".gray().into_right_aligned_line()),
-                Cell::from(
-                    text_lines
-                        .iter()
-                        .map(|s| Line::from(s.to_string()))
-                        .collect::<Vec<_>>(),
-                ),
+            Row::default().height(body.len() as u16).cells(vec![
+                Cell::from(Span::styled("Text:", theme.muted).into_right_aligned_line()),
+                Cell::from(body),
             ]),
         ];
         if let Some(langs) = record.langs.as_ref().filter(|v| !v.is_empty()) {
             rows.push(Row::new(vec![
-                Cell::from("Langs:".gray().into_right_aligned_line()),
+                Cell::from(Span::styled("Langs:", theme.muted).into_right_aligned_line()),
                 Cell::from(
                     langs
                         .iter()
@@ -276,47 +284,14 @@ impl PostViewComponent {
         if let Some(labels) = post_view.labels.as_ref().filter(|v| !v.is_empty()) {
             let mut lines = Vec::new();
             for label in labels {
-                let mut spans = vec![Span::from(label.val.as_str()).magenta()];
+                let mut spans = vec![Span::styled(label.val.as_str(), theme.label)];
                 if !label.uri.ends_with("/self") {
                     spans.extend([Span::from(" "), format!("by {}", label.src.as_ref()).dim()]);
                 }
                 lines.push(Line::from(spans));
             }
             rows.push(Row::default().height(lines.len() as u16).cells(vec![
-                Cell::from("Labels:".gray().into_right_aligned_line()),
-                Cell::from(lines),
-            ]));
-        }
-        if let Some(facets) = &record.facets {
-            let lines = facets
-                .iter()
-                .map(|f| {
-                    Line::from(vec![
-                        Span::from(format!("[{}-{}] ", f.index.byte_start, f.index.byte_end))
-                            .cyan(),
-                        Span::from(
-                            f.features
-                                .iter()
-                                .map(|f| match f {
-                                    Union::Refs(MainFeaturesItem::Mention(mention)) => {
-                                        format!("Mention({})", mention.did.as_ref())
-                                    }
-                                    Union::Refs(MainFeaturesItem::Link(link)) => {
-                                        format!("Link({})", link.uri)
-                                    }
-                                    Union::Refs(MainFeaturesItem::Tag(tag)) => {
-                                        format!("Tag({})", tag.tag)
-                                    }
-                                    Union::Unknown(_) => String::from("Unknown"),
-                                })
-                                .collect::<Vec<_>>()
-                                .join(", "),
-                        ),
-                    ])
-                })
-                .collect::<Vec<_>>();
-            rows.push(Row::default().height(facets.len() as u16).cells(vec![
-                Cell::from("Facets".gray().into_right_aligned_line()),
+                Cell::from(Span::styled("Labels:", theme.muted).into_right_aligned_line()),
                 Cell::from(lines),
             ]));
         }
@@ -324,86 +299,82 @@ impl PostViewComponent {
             let mut lines = Vec::new();
             match embed {
                 Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(images)) => {
-                    lines.push(Line::from("images").yellow());
-                    lines.extend(Self::images_lines(images))
+                    lines.push(Line::styled("images", theme.embed));
+                    lines.extend(Self::images_lines(images, theme))
                 }
                 Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(external)) => {
-                    lines.push(Line::from("external").yellow());
-                    lines.extend(Self::external_lines(external));
+                    lines.push(Line::styled("external", theme.embed));
+                    lines.extend(Self::external_lines(external, theme));
                 }
                 Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordView(record)) => {
-                    lines.push(Line::from("record").yellow());
-                    lines.extend(Self::record_lines(record, width));
+                    lines.push(Line::styled("record", theme.embed));
+                    lines.extend(Self::record_lines(record, width, theme, timestamp));
                 }
                 Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(
                     record_with_media,
                 )) => {
-                    lines.push(Line::from("recordWithMedia").yellow());
+                    lines.push(Line::styled("recordWithMedia", theme.embed));
                     match &record_with_media.media {
                         Union::Refs(ViewMediaRefs::AppBskyEmbedImagesView(images)) => {
-                            lines.extend(Self::images_lines(images))
+                            lines.extend(Self::images_lines(images, theme))
                         }
                         Union::Refs(ViewMediaRefs::AppBskyEmbedExternalView(external)) => {
-                            lines.extend(Self::external_lines(external));
+                            lines.extend(Self::external_lines(external, theme));
                         }
                         _ => {}
                     }
-                    lines.extend(Self::record_lines(&record_with_media.record, width));
+                    lines.extend(Self::record_lines(
+                        &record_with_media.record,
+                        width,
+                        theme,
+                        timestamp,
+                    ));
                 }
                 _ => {}
             }
             rows.push(Row::default().height(lines.len() as u16).cells(vec![
-                Cell::from("Embed:".gray().into_right_aligned_line()),
+                Cell::from(Span::styled("Embed:", theme.muted).into_right_aligned_line()),
                 Cell::from(lines),
             ]))
         }
         Some(rows)
     }
-    fn images_lines(images: &images::View) -> Vec<Line<'_>> {
+    fn images_lines<'a>(images: &'a images::View, theme: &Theme) -> Vec<Line<'a>> {
         images
             .images
             .iter()
             .map(|image| {
                 Line::from(vec![
                     Span::from(format!("[{}](", image.alt)),
-                    Span::from(image.fullsize.as_str()).underlined(),
+                    Span::styled(image.fullsize.as_str(), theme.link),
                     Span::from(")"),
                 ])
             })
             .collect()
     }
-    fn external_lines(external: &external::View) -> Vec<Line<'_>> {
+    fn external_lines<'a>(external: &'a external::View, theme: &Theme) -> Vec<Line<'a>> {
         vec![
-            Line::from(
-                Span::from(external.external.uri.as_str())
-                    .dim()
-                    .underlined(),
-            ),
+            Line::from(Span::styled(external.external.uri.as_str(), theme.link).dim()),
             Line::from(external.external.title.as_str()).bold(),
             Line::from(external.external.description.as_str()),
         ]
     }
-    fn record_lines(record: &record::View, width: u16) -> Vec<Line<'_>> {
+    fn record_lines<'a>(
+        record: &'a record::View,
+        width: u16,
+        theme: &Theme,
+        timestamp: &TimestampFormat,
+    ) -> Vec<Line<'a>> {
         match &record.record {
             Union::Refs(ViewRecordRefs::ViewRecord(view_record)) => {
                 if let Ok(record) = post::Record::try_from_unknown(view_record.value.clone()) {
                     return [
                         vec![
-                            Line::from(
-                                view_record
-                                    .indexed_at
-                                    .as_ref()
-                                    .with_timezone(&Local)
-                                    .format("%Y-%m-%d %H:%M:%S %z")
-                                    .to_string(),
-                            )
-                            .green(),
-                            Line::from(profile_name(&view_record.author)),
+                            Line::from(timestamp.format(view_record.indexed_at.as_ref()))
+                                .style(theme.timestamp),
+                            Line::from(profile_name(&view_record.author, theme)),
                         ],
-                        textwrap::wrap(&record.text, usize::from(width))
-                            .iter()
-                            .map(|s| Line::from(s.to_string()))
-                            .collect::<Vec<_>>(),
+                        text_lines(&record, usize::from(width), "", theme),
                     ]
                     .concat();
                 }
@@ -424,12 +395,238 @@ impl PostViewComponent {
         }
         Vec::new()
     }
+    fn perform(&self, action: PostAction) -> Result<Option<Action>> {
+        match action {
+            PostAction::Like => {
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                let mut viewer = self.post_view.viewer.clone().unwrap_or(
+                    ViewerStateData {
+                        bookmarked: None,
+                        embedding_disabled: None,
+                        like: None,
+                        pinned: None,
+                        reply_disabled: None,
+                        repost: None,
+                        thread_muted: None,
+                    }
+                    .into(),
+                );
+                let post_view = self.post_view.clone();
+                tokio::spawn(async move {
+                    match like(&agent, &post_view).await {
+                        Ok(uri) => {
+                            viewer.like = Some(uri);
+                            tx.send(Action::Update(Box::new(Data::ViewerState(Some(viewer)))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to create like record: {e}");
+                            tx.send(Action::Toast(Toast::error(format!("failed to like: {e}"))))
+                                .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Unlike(uri) => {
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                let mut viewer = self.post_view.viewer.clone();
+                tokio::spawn(async move {
+                    match agent.delete_record(uri).await {
+                        Ok(_) => {
+                            log::info!("deleted like record");
+                            if let Some(viewer) = viewer.as_mut() {
+                                viewer.like = None;
+                            }
+                            tx.send(Action::Update(Box::new(Data::ViewerState(viewer))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to delete like record: {e}");
+                            tx.send(Action::Toast(Toast::error(format!(
+                                "failed to unlike: {e}"
+                            ))))
+                            .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Repost => {
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                let mut viewer = self.post_view.viewer.clone().unwrap_or(
+                    ViewerStateData {
+                        bookmarked: None,
+                        embedding_disabled: None,
+                        like: None,
+                        pinned: None,
+                        reply_disabled: None,
+                        repost: None,
+                        thread_muted: None,
+                    }
+                    .into(),
+                );
+                let post_view = self.post_view.clone();
+                tokio::spawn(async move {
+                    match repost(&agent, &post_view).await {
+                        Ok(uri) => {
+                            viewer.repost = Some(uri);
+                            tx.send(Action::Update(Box::new(Data::ViewerState(Some(viewer)))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to create repost record: {e}");
+                            tx.send(Action::Toast(Toast::error(format!(
+                                "failed to repost: {e}"
+                            ))))
+                            .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Unrepost(uri) => {
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                let mut viewer = self.post_view.viewer.clone();
+                tokio::spawn(async move {
+                    match agent.delete_record(uri).await {
+                        Ok(_) => {
+                            log::info!("deleted repost record");
+                            if let Some(viewer) = viewer.as_mut() {
+                                viewer.repost = None;
+                            }
+                            tx.send(Action::Update(Box::new(Data::ViewerState(viewer))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to delete repost record: {e}");
+                            tx.send(Action::Toast(Toast::error(format!(
+                                "failed to undo the repost: {e}"
+                            ))))
+                            .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Reply => {
+                return Ok(Some(Action::Transition(Transition::Push(Box::new(
+                    View::NewPost(Some(Box::new(reply_ref(&self.post_view)))),
+                )))));
+            }
+            PostAction::Delete => {
+                // TODO: confirmation dialog
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                let at_uri = self.post_view.uri.clone();
+                tokio::spawn(async move {
+                    match agent.delete_record(at_uri).await {
+                        Ok(_) => {
+                            log::info!("deleted record");
+                            tx.send(Action::Toast(Toast::success("Deleted the post")))
+                                .ok();
+                            tx.send(Action::Transition(Transition::Pop)).ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to delete record: {e}");
+                            tx.send(Action::Toast(Toast::error(format!(
+                                "failed to delete the post: {e}"
+                            ))))
+                            .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Mention(_, did) => {
+                let command = Command::Profile(did);
+                let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
+                tokio::spawn(async move {
+                    match command.view(&agent).await {
+                        Ok(view) => {
+                            tx.send(Action::Transition(Transition::Push(Box::new(view))))
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("failed to open {command:?}: {e}");
+                            tx.send(Action::Toast(Toast::error(format!("failed to open: {e}"))))
+                                .ok();
+                        }
+                    }
+                });
+            }
+            PostAction::Tag(tag) => {
+                return Ok(Some(Action::Transition(Transition::Push(Box::new(
+                    View::Feed(Box::new(FeedSourceInfo::Search(format!("#{tag}")))),
+                )))));
+            }
+            PostAction::Open(uri) => {
+                if let Err(e) = open::that(&uri) {
+                    log::error!("failed to open: {e}");
+                    self.action_tx
+                        .send(Action::Toast(Toast::error(format!(
+                            "failed to open {uri}: {e}"
+                        ))))
+                        .ok();
+                }
+            }
+            PostAction::Download(_) => {
+                download(
+                    self.agent.clone(),
+                    self.post_view.clone(),
+                    &self.download,
+                    self.action_tx.clone(),
+                );
+            }
+            PostAction::ViewRecord(view_record) => {
+                return Ok(Some(Action::Transition(Transition::Push(Box::new(
+                    View::Post(Box::new((
+                        PostViewData {
+                            author: view_record.author.clone(),
+                            bookmark_count: None,
+                            cid: view_record.cid.clone(),
+                            embed: None,
+                            indexed_at: view_record.indexed_at.clone(),
+                            labels: view_record.labels.clone(),
+                            like_count: view_record.like_count,
+                            quote_count: view_record.quote_count,
+                            record: view_record.value.clone(),
+                            reply_count: view_record.reply_count,
+                            repost_count: view_record.repost_count,
+                            threadgate: None,
+                            uri: view_record.uri.clone(),
+                            viewer: None,
+                        }
+                        .into(),
+                        None,
+                    ))),
+                )))));
+            }
+            _ => {
+                // TODO
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl ViewComponent for PostViewComponent {
     fn view(&self) -> View {
         View::Post(Box::new((self.post_view.clone(), self.reply.clone())))
     }
+    fn keybinding_scope(&self) -> Option<KeybindingScope> {
+        Some(KeybindingScope::Post)
+    }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        self.timestamp = config.timestamp.post;
+        Ok(())
+    }
+    fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        let active = self.quit.is_some();
+        if active {
+            self.deactivate()?;
+        }
+        self.watcher = Box::new(watcher.post_thread(self.post_view.uri.clone()));
+        if active {
+            self.activate()?;
+        }
+        Ok(())
+    }
     fn activate(&mut self) -> Result<()> {
         let (tx, mut rx) = (self.action_tx.clone(), self.watcher.subscribe());
         let (quit_tx, mut quit_rx) = oneshot::channel();
@@ -466,7 +663,15 @@ impl ViewComponent for PostViewComponent {
         self.watcher.unsubscribe();
         Ok(())
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(self
+            .list_areas
+            .handle_mouse_events(mouse, &mut self.list_state))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.list_state) {
+            return Ok(Some(action));
+        }
         match action {
             Action::NextItem => {
                 self.list_state.select(Some(
@@ -485,118 +690,76 @@ impl ViewComponent for PostViewComponent {
                 return Ok(Some(Action::Render));
             }
             Action::Enter => {
-                if let Some(action) = self.list_state.selected().and_then(|i| self.actions.get(i)) {
-                    match action {
-                        PostAction::Like => {
-                            let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
-                            let mut viewer = self.post_view.viewer.clone().unwrap_or(
-                                ViewerStateData {
-                                    bookmarked: None,
-                                    embedding_disabled: None,
-                                    like: None,
-                                    pinned: None,
-                                    reply_disabled: None,
-                                    repost: None,
-                                    thread_muted: None,
-                                }
-                                .into(),
-                            );
-                            let record_data = api::app::bsky::feed::like::RecordData {
-                                created_at: Datetime::now(),
-                                subject: api::com::atproto::repo::strong_ref::MainData {
-                                    cid: self.post_view.cid.clone(),
-                                    uri: self.post_view.uri.clone(),
-                                }
-                                .into(),
-                                via: None,
-                            };
-                            tokio::spawn(async move {
-                                match agent.create_record(record_data).await {
-                                    Ok(output) => {
-                                        log::info!("created like record: {}", output.cid.as_ref());
-                                        viewer.like = Some(output.uri.clone());
-                                        tx.send(Action::Update(Box::new(Data::ViewerState(Some(
-                                            viewer,
-                                        )))))
-                                        .ok();
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to create like record: {e}");
-                                    }
-                                }
-                            });
-                        }
-                        PostAction::Unlike(uri) => {
-                            let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
-                            let mut viewer = self.post_view.viewer.clone();
-                            let at_uri = uri.clone();
-                            tokio::spawn(async move {
-                                match agent.delete_record(at_uri).await {
-                                    Ok(_) => {
-                                        log::info!("deleted like record");
-                                        if let Some(viewer) = viewer.as_mut() {
-                                            viewer.like = None;
-                                        }
-                                        tx.send(Action::Update(Box::new(Data::ViewerState(
-                                            viewer,
-                                        ))))
-                                        .ok();
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to create like record: {e}");
-                                    }
-                                }
-                            });
-                        }
-                        PostAction::Delete => {
-                            // TODO: confirmation dialog
-                            let (agent, tx) = (self.agent.clone(), self.action_tx.clone());
-                            let at_uri = self.post_view.uri.clone();
-                            tokio::spawn(async move {
-                                match agent.delete_record(at_uri).await {
-                                    Ok(_) => {
-                                        log::info!("deleted record");
-                                        tx.send(Action::Transition(Transition::Pop)).ok();
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to delete record: {e}");
-                                    }
-                                }
-                            });
-                        }
-                        PostAction::Open(uri) => {
-                            if let Err(e) = open::that(uri) {
-                                log::error!("failed to open: {e}");
-                            }
-                        }
-                        PostAction::ViewRecord(view_record) => {
-                            return Ok(Some(Action::Transition(Transition::Push(Box::new(
-                                View::Post(Box::new((
-                                    PostViewData {
-                                        author: view_record.author.clone(),
-                                        bookmark_count: None,
-                                        cid: view_record.cid.clone(),
-                                        embed: None,
-                                        indexed_at: view_record.indexed_at.clone(),
-                                        labels: view_record.labels.clone(),
-                                        like_count: view_record.like_count,
-                                        quote_count: view_record.quote_count,
-                                        record: view_record.value.clone(),
-                                        reply_count: view_record.reply_count,
-                                        repost_count: view_record.repost_count,
-                                        threadgate: None,
-                                        uri: view_record.uri.clone(),
-                                        viewer: None,
-                                    }
-                                    .into(),
-                                    None,
-                                ))),
-                            )))));
-                        }
-                        _ => {
-                            // TODO
-                        }
-                    }
+                if let Some(action) = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.actions.get(i))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::Like => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::Like | PostAction::Unlike(_)))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::Repost => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::Repost | PostAction::Unrepost(_)))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::Reply => {
+                return self.perform(PostAction::Reply);
+            }
+            Action::Delete => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::Delete))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::Download => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::Download(_)))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::OpenLink => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::Open(_)))
+                    .cloned()
+                {
+                    return self.perform(action);
+                }
+            }
+            Action::ViewRecord => {
+                if let Some(action) = self
+                    .actions
+                    .iter()
+                    .find(|action| matches!(action, PostAction::ViewRecord(_)))
+                    .cloned()
+                {
+                    return self.perform(action);
                 }
             }
             Action::Back => {
@@ -618,20 +781,18 @@ impl ViewComponent for PostViewComponent {
                         }
                     }
                     Data::ViewerState(viewer) => {
-                        let diff = i64::from(
-                            viewer
-                                .as_ref()
-                                .map(|v| v.like.is_some())
-                                .unwrap_or_default(),
-                        ) - i64::from(
-                            self.post_view
-                                .viewer
-                                .as_ref()
-                                .map(|v| v.like.is_some())
-                                .unwrap_or_default(),
-                        );
+                        let diff = |f: fn(&ViewerStateData) -> bool| {
+                            i64::from(viewer.as_deref().map(f).unwrap_or_default())
+                                - i64::from(
+                                    self.post_view.viewer.as_deref().map(f).unwrap_or_default(),
+                                )
+                        };
+                        let (likes, reposts) =
+                            (diff(|v| v.like.is_some()), diff(|v| v.repost.is_some()));
                         self.post_view.like_count =
-                            Some(self.post_view.like_count.unwrap_or_default() + diff);
+                            Some(self.post_view.like_count.unwrap_or_default() + likes);
+                        self.post_view.repost_count =
+                            Some(self.post_view.repost_count.unwrap_or_default() + reposts);
                         self.post_view.viewer.clone_from(viewer);
                     }
                     _ => return Ok(None),
@@ -652,14 +813,15 @@ impl ViewComponent for PostViewComponent {
 
         let mut rows = Vec::new();
         if let Some(reply) = &self.reply {
-            if let Some(r) = Self::post_view_rows(reply, width) {
-                rows.push(Row::new([" Reply to".blue()]));
+            if let Some(r) = Self::post_view_rows(reply, width, &self.theme, &self.timestamp) {
+                rows.push(Row::new([Span::styled(" Reply to", self.theme.reply)]));
                 rows.extend(r);
-                rows.push(Row::new([" --------- ".blue()]));
+                rows.push(Row::new([Span::styled(" --------- ", self.theme.reply)]));
             }
         }
         self.table_state.select(Some(rows.len()));
-        if let Some(r) = Self::post_view_rows(&self.post_view, width) {
+        if let Some(r) = Self::post_view_rows(&self.post_view, width, &self.theme, &self.timestamp)
+        {
             rows.extend(r);
         }
 
@@ -673,26 +835,34 @@ impl ViewComponent for PostViewComponent {
             Paragraph::new(self.post_view.uri.as_str()).bold().block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .border_style(Color::Gray)
+                    .border_style(self.theme.border)
                     .padding(Padding::horizontal(1)),
             ),
             layout[0],
         );
         f.render_stateful_widget(Table::new(rows, widths), layout[1], &mut self.table_state);
+        let items = self
+            .actions
+            .iter()
+            .map(|action| action.list_item(&self.theme))
+            .collect::<Vec<_>>();
+        let heights = items.iter().map(ListItem::height).collect::<Vec<_>>();
+        let block = Block::default()
+            .title("Actions")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_style(self.theme.border)
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(layout[2]);
         f.render_stateful_widget(
-            List::new(&self.actions)
-                .highlight_style(Style::default().reversed())
-                .block(
-                    Block::default()
-                        .title("Actions")
-                        .title_alignment(Alignment::Center)
-                        .borders(Borders::TOP)
-                        .border_style(Color::Gray)
-                        .padding(Padding::horizontal(1)),
-                ),
+            List::new(items)
+                .highlight_style(Style::default().reset().patch(self.theme.selected))
+                .block(block),
             layout[2],
             &mut self.list_state,
         );
+        self.list_areas
+            .update(inner, self.list_state.offset(), heights);
         Ok(())
     }
 }
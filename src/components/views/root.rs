@@ -1,10 +1,13 @@
+use super::super::mouse::ListAreas;
 use super::types::{Action, Transition, View};
 use super::utils::profile_name_as_str;
 use super::ViewComponent;
 use crate::backend::types::{FeedSourceInfo, PinnedFeed};
 use crate::backend::{Watch, Watcher};
 use crate::components::views::types::Data;
+use crate::config::{Config, Theme};
 use color_eyre::Result;
+use crossterm::event::MouseEvent;
 use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, List, ListState, Padding};
@@ -16,9 +19,11 @@ use tokio::sync::oneshot;
 pub struct RootComponent {
     items: Vec<PinnedFeed>,
     state: ListState,
+    list_areas: ListAreas,
     action_tx: UnboundedSender<Action>,
     watcher: Box<dyn Watch<Output = Vec<PinnedFeed>>>,
     quit: Option<oneshot::Sender<()>>,
+    theme: Theme,
 }
 
 impl RootComponent {
@@ -26,9 +31,11 @@ impl RootComponent {
         Self {
             items: Vec::new(),
             state: ListState::default(),
+            list_areas: ListAreas::default(),
             action_tx,
             watcher: Box::new(watcher.pinned_feeds()),
             quit: None,
+            theme: Theme::default(),
         }
     }
 }
@@ -37,6 +44,21 @@ impl ViewComponent for RootComponent {
     fn view(&self) -> View {
         View::Root
     }
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.theme = Theme::from(&config.theme);
+        Ok(())
+    }
+    fn replace_watcher(&mut self, watcher: Arc<Watcher>) -> Result<()> {
+        let active = self.quit.is_some();
+        if active {
+            self.deactivate()?;
+        }
+        self.watcher = Box::new(watcher.pinned_feeds());
+        if active {
+            self.activate()?;
+        }
+        Ok(())
+    }
     fn activate(&mut self) -> Result<()> {
         let (tx, mut rx) = (self.action_tx.clone(), self.watcher.subscribe());
         let (quit_tx, mut quit_rx) = oneshot::channel();
@@ -77,7 +99,13 @@ impl ViewComponent for RootComponent {
         self.watcher.unsubscribe();
         Ok(())
     }
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        Ok(self.list_areas.handle_mouse_events(mouse, &mut self.state))
+    }
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(action) = self.list_areas.navigate(&action, &mut self.state) {
+            return Ok(Some(action));
+        }
         match action {
             Action::NextItem if !self.items.is_empty() => {
                 self.state.select(Some(
@@ -134,7 +162,7 @@ impl ViewComponent for RootComponent {
             .map(|feed| match &feed.info {
                 FeedSourceInfo::Feed(generator_view) => Text::from(vec![
                     Line::from(vec![
-                        Span::from("[feed]").blue(),
+                        Span::styled("[feed]", self.theme.feed),
                         Span::from(" "),
                         Span::from(generator_view.display_name.clone()).bold(),
                         Span::from(" "),
@@ -142,7 +170,7 @@ impl ViewComponent for RootComponent {
                             "by {}",
                             profile_name_as_str(&generator_view.creator)
                         ))
-                        .gray(),
+                        .style(self.theme.muted),
                     ]),
                     Line::from(format!(
                         "  {}",
@@ -152,12 +180,12 @@ impl ViewComponent for RootComponent {
                 ]),
                 FeedSourceInfo::List(list_view) => Text::from(vec![
                     Line::from(vec![
-                        Span::from("[list]").yellow(),
+                        Span::styled("[list]", self.theme.list),
                         Span::from(" "),
                         Span::from(list_view.name.as_str()).bold(),
                         Span::from(" "),
                         Span::from(format!("by {}", profile_name_as_str(&list_view.creator)))
-                            .gray(),
+                            .style(self.theme.muted),
                     ]),
                     Line::from(format!(
                         "  {}",
@@ -167,24 +195,44 @@ impl ViewComponent for RootComponent {
                 ]),
                 FeedSourceInfo::Timeline(_) => Text::from(vec![
                     Line::from(vec![
-                        Span::from("[timeline]").green(),
+                        Span::styled("[timeline]", self.theme.timeline),
                         Span::from(" "),
                         Span::from("Following").bold(),
                     ]),
                     Line::from("  Your following feed").dim(),
                 ]),
+                FeedSourceInfo::Author(profile_view) => Text::from(vec![
+                    Line::from(vec![
+                        Span::styled("[posts]", self.theme.muted),
+                        Span::from(" "),
+                        Span::from(profile_name_as_str(profile_view.as_ref())).bold(),
+                    ]),
+                    Line::from(format!("  Posts by @{}", profile_view.handle.as_str())).dim(),
+                ]),
+                FeedSourceInfo::Search(query) => Text::from(vec![
+                    Line::from(vec![
+                        Span::styled("[search]", self.theme.muted),
+                        Span::from(" "),
+                        Span::from(query.as_str()).bold(),
+                    ]),
+                    Line::from("  Latest posts matching the query").dim(),
+                ]),
             })
             .collect::<Vec<_>>();
         if !items.is_empty() {
-            items.push(Text::from("Sign out").red());
+            items.push(Text::styled("Sign out", self.theme.danger));
         }
+        let heights = items.iter().map(Text::height).collect::<Vec<_>>();
+        let block = Block::default().padding(Padding::uniform(1));
+        let inner = block.inner(area);
         f.render_stateful_widget(
             List::new(items)
-                .block(Block::default().padding(Padding::uniform(1)))
-                .highlight_style(Style::default().reset().reversed()),
+                .block(block)
+                .highlight_style(Style::default().reset().patch(self.theme.selected)),
             area,
             &mut self.state,
         );
+        self.list_areas.update(inner, self.state.offset(), heights);
         Ok(())
     }
 }
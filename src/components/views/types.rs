@@ -1,24 +1,51 @@
+use crate::backend::client::Agent;
 use crate::backend::types::{FeedSourceInfo, PinnedFeed};
+use crate::backend::Watcher;
+use crate::types::Toast;
 use bsky_sdk::api::app::bsky::feed::defs::{FeedViewPost, PostView, ViewerState};
 use bsky_sdk::api::app::bsky::feed::get_post_thread::OutputThreadRefs;
+use bsky_sdk::api::app::bsky::feed::post::ReplyRef;
+use bsky_sdk::api::app::bsky::notification::list_notifications::Notification;
 use bsky_sdk::api::types::Union;
-use bsky_sdk::BskyAgent;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter, Result};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub enum Action {
     Render,
     NextItem,
     PrevItem,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+    Newest,
     Enter,
     Back,
     Refresh,
     NewPost,
     Menu,
-    Login(Box<BskyAgent>),
+    Help,
+    CommandPalette,
+    Like,
+    Repost,
+    Reply,
+    Delete,
+    Download,
+    OpenLink,
+    ViewRecord,
+    Embed,
+    Submit,
+    Login(Box<Agent>),
+    /// Sign in with the watcher of an account signed in to another column.
+    Share(Arc<Watcher>),
     Logout,
     Update(Box<Data>),
     Transition(Transition),
+    Toast(Toast),
 }
 
 impl Debug for Action {
@@ -27,15 +54,35 @@ impl Debug for Action {
             Action::Render => write!(f, "Render"),
             Action::NextItem => write!(f, "NextItem"),
             Action::PrevItem => write!(f, "PrevItem"),
+            Action::PageDown => write!(f, "PageDown"),
+            Action::PageUp => write!(f, "PageUp"),
+            Action::HalfPageDown => write!(f, "HalfPageDown"),
+            Action::HalfPageUp => write!(f, "HalfPageUp"),
+            Action::Top => write!(f, "Top"),
+            Action::Bottom => write!(f, "Bottom"),
+            Action::Newest => write!(f, "Newest"),
             Action::Enter => write!(f, "Enter"),
             Action::Back => write!(f, "Back"),
             Action::Refresh => write!(f, "Refresh"),
             Action::NewPost => write!(f, "NewPost"),
             Action::Menu => write!(f, "Menu"),
+            Action::Help => write!(f, "Help"),
+            Action::CommandPalette => write!(f, "CommandPalette"),
+            Action::Like => write!(f, "Like"),
+            Action::Repost => write!(f, "Repost"),
+            Action::Reply => write!(f, "Reply"),
+            Action::Delete => write!(f, "Delete"),
+            Action::Download => write!(f, "Download"),
+            Action::OpenLink => write!(f, "OpenLink"),
+            Action::ViewRecord => write!(f, "ViewRecord"),
+            Action::Embed => write!(f, "Embed"),
+            Action::Submit => write!(f, "Submit"),
             Action::Login(_) => write!(f, "Login"),
+            Action::Share(_) => write!(f, "Share"),
             Action::Logout => write!(f, "Logout"),
             Action::Update(_) => write!(f, "Update"),
             Action::Transition(arg) => f.debug_tuple("Transition").field(arg).finish(),
+            Action::Toast(arg) => f.debug_tuple("Toast").field(arg).finish(),
         }
     }
 }
@@ -46,6 +93,7 @@ pub enum Data {
     Feed(Vec<FeedViewPost>),
     PostThread(Union<OutputThreadRefs>),
     ViewerState(Option<ViewerState>),
+    Notifications(Vec<Notification>),
 }
 
 #[derive(Debug, Clone)]
@@ -55,11 +103,14 @@ pub enum Transition {
     Replace(Box<View>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum View {
     Login,
+    Accounts,
     Root,
-    NewPost,
+    /// Composer of a new post, or of a reply to the referenced post.
+    NewPost(Option<Box<ReplyRef>>),
     Feed(Box<FeedSourceInfo>),
     Post(Box<(PostView, Option<PostView>)>),
+    Notifications,
 }
@@ -1,7 +1,25 @@
-use bsky_sdk::api::app::bsky::actor::defs::{ProfileView, ProfileViewBasic};
-use bsky_sdk::api::app::bsky::feed::defs::PostView;
+use super::super::text;
+use super::types::Action;
+use crate::backend::client::Agent;
+use crate::backend::download::download_images;
+use crate::config::{DownloadConfig, Theme};
+use crate::types::Toast;
+use crate::utils::get_download_dir;
+use bsky_sdk::api;
+use bsky_sdk::api::app::bsky::actor::defs::{ProfileView, ProfileViewBasic, ProfileViewDetailed};
+use bsky_sdk::api::app::bsky::embed::record_with_media::ViewMediaRefs;
+use bsky_sdk::api::app::bsky::feed::defs::{PostView, PostViewEmbedRefs};
+use bsky_sdk::api::app::bsky::feed::post;
+use bsky_sdk::api::app::bsky::richtext::facet::MainFeaturesItem;
+use bsky_sdk::api::types::string::Datetime;
+use bsky_sdk::api::types::{TryFromUnknown, Union};
+use color_eyre::Result;
+use indexmap::IndexSet;
 use ratatui::style::{Style, Stylize};
-use ratatui::text::Span;
+use ratatui::text::{Line, Span};
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 
 pub trait Profile {
     fn display_name(&self) -> Option<&str>;
@@ -17,6 +35,15 @@ impl Profile for ProfileView {
     }
 }
 
+impl Profile for ProfileViewDetailed {
+    fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref().filter(|s| !s.is_empty())
+    }
+    fn handle(&self) -> &str {
+        self.handle.as_str()
+    }
+}
+
 impl Profile for ProfileViewBasic {
     fn display_name(&self) -> Option<&str> {
         self.display_name.as_deref().filter(|s| !s.is_empty())
@@ -30,19 +57,22 @@ pub fn profile_name_as_str(author: &dyn Profile) -> &str {
     author.display_name().unwrap_or(author.handle())
 }
 
-pub fn profile_name(author: &dyn Profile) -> Vec<Span<'_>> {
+pub fn profile_name<'a>(author: &'a dyn Profile, theme: &Theme) -> Vec<Span<'a>> {
     if let Some(display_name) = author.display_name() {
         vec![
-            Span::from(display_name.to_string()).bold(),
+            Span::styled(display_name.to_string(), theme.display_name),
             Span::from(" "),
-            format!("@{}", author.handle()).gray(),
+            Span::styled(format!("@{}", author.handle()), theme.handle),
         ]
     } else {
-        vec![format!("@{}", author.handle()).bold()]
+        vec![Span::styled(
+            format!("@{}", author.handle()),
+            theme.display_name,
+        )]
     }
 }
 
-pub fn counts(post_view: &PostView, pad: usize) -> Vec<Span<'_>> {
+pub fn counts<'a>(post_view: &'a PostView, pad: usize, theme: &Theme) -> Vec<Span<'a>> {
     let (mut reposted, mut liked) = (false, false);
     if let Some(viewer) = &post_view.viewer {
         reposted = viewer.repost.is_some();
@@ -65,7 +95,7 @@ pub fn counts(post_view: &PostView, pad: usize) -> Vec<Span<'_>> {
         Span::from(format!("{replies:pad$} replies")).style(style(replies > 0)),
         Span::from(", ").dim(),
         Span::from(format!("{reposts:pad$}")).style(if reposted {
-            Style::default().green()
+            theme.reposted
         } else {
             style(reposts > 0)
         }),
@@ -75,10 +105,408 @@ pub fn counts(post_view: &PostView, pad: usize) -> Vec<Span<'_>> {
         Span::from(" quotes").style(style(reposts > 0)),
         Span::from(", ").dim(),
         Span::from(format!("{likes:pad$}")).style(if liked {
-            Style::default().red()
+            theme.liked
         } else {
             style(likes > 0)
         }),
         Span::from(" likes").style(style(likes > 0)),
     ]
 }
+
+/// Byte ranges of the facets in the text of the post with their first features, in order.
+///
+/// Facets out of the text, not on character boundaries or overlapping the previous ones are skipped.
+pub fn facets(record: &post::Record) -> Vec<(Range<usize>, &MainFeaturesItem)> {
+    let mut facets = record
+        .facets
+        .iter()
+        .flatten()
+        .filter_map(|facet| {
+            let range = facet.index.byte_start..facet.index.byte_end;
+            let feature = facet.features.iter().find_map(|feature| match feature {
+                Union::Refs(item) => Some(item),
+                Union::Unknown(_) => None,
+            })?;
+            (range.start < range.end
+                && record.text.is_char_boundary(range.start)
+                && record.text.is_char_boundary(range.end))
+            .then_some((range, feature))
+        })
+        .collect::<Vec<_>>();
+    facets.sort_by_key(|(range, _)| range.start);
+    let mut end = 0;
+    facets.retain(|(range, _)| {
+        let retain = range.start >= end;
+        if retain {
+            end = range.end;
+        }
+        retain
+    });
+    facets
+}
+
+pub fn facet_style(feature: &MainFeaturesItem, theme: &Theme) -> Style {
+    match feature {
+        MainFeaturesItem::Link(_) => theme.link.underlined(),
+        MainFeaturesItem::Mention(_) | MainFeaturesItem::Tag(_) => theme.facet,
+    }
+}
+
+/// Wrap the text of the post into lines of `width` cells starting with the `indent`,
+/// with the mentions, links and tags styled inline.
+pub fn text_lines(
+    record: &post::Record,
+    width: usize,
+    indent: &str,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let facets = facets(record);
+    text::wrap_ranges(&record.text, width.saturating_sub(text::width(indent)))
+        .into_iter()
+        .map(|line| {
+            let mut spans = vec![Span::from(indent.to_string())];
+            let mut pos = line.start;
+            for (range, feature) in &facets {
+                let (start, end) = (range.start.max(pos), range.end.min(line.end));
+                if start >= end {
+                    continue;
+                }
+                if pos < start {
+                    spans.push(Span::from(record.text[pos..start].to_string()));
+                }
+                spans.push(Span::styled(
+                    record.text[start..end].to_string(),
+                    facet_style(feature, theme),
+                ));
+                pos = end;
+            }
+            if pos < line.end {
+                spans.push(Span::from(record.text[pos..line.end].to_string()));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Create a like record for the post, and return its URI.
+pub async fn like(agent: &Agent, post_view: &PostView) -> Result<String> {
+    let output = agent
+        .create_record(api::app::bsky::feed::like::RecordData {
+            created_at: Datetime::now(),
+            subject: api::com::atproto::repo::strong_ref::MainData {
+                cid: post_view.cid.clone(),
+                uri: post_view.uri.clone(),
+            }
+            .into(),
+            via: None,
+        })
+        .await?;
+    log::info!("created like record: {}", output.cid.as_ref());
+    Ok(output.data.uri)
+}
+
+/// Create a repost record for the post, and return its URI.
+pub async fn repost(agent: &Agent, post_view: &PostView) -> Result<String> {
+    let output = agent
+        .create_record(api::app::bsky::feed::repost::RecordData {
+            created_at: Datetime::now(),
+            subject: api::com::atproto::repo::strong_ref::MainData {
+                cid: post_view.cid.clone(),
+                uri: post_view.uri.clone(),
+            }
+            .into(),
+            via: None,
+        })
+        .await?;
+    log::info!("created repost record: {}", output.cid.as_ref());
+    Ok(output.data.uri)
+}
+
+/// Reference to reply to the post, in the thread of its root.
+pub fn reply_ref(post_view: &PostView) -> post::ReplyRef {
+    let parent = api::com::atproto::repo::strong_ref::Main::from(
+        api::com::atproto::repo::strong_ref::MainData {
+            cid: post_view.cid.clone(),
+            uri: post_view.uri.clone(),
+        },
+    );
+    let root = post::Record::try_from_unknown(post_view.record.clone())
+        .ok()
+        .and_then(|record| record.data.reply)
+        .map_or_else(|| parent.clone(), |reply| reply.data.root);
+    post::ReplyRefData { parent, root }.into()
+}
+
+/// Download the images of the post into the configured directory,
+/// and report the result with a toast, as well as a missing download directory.
+pub fn download(
+    agent: Arc<Agent>,
+    post_view: PostView,
+    config: &DownloadConfig,
+    tx: UnboundedSender<Action>,
+) {
+    let dir = match &config.dir {
+        Some(dir) => Ok(dir.clone()),
+        None => get_download_dir(),
+    };
+    let dir = match dir {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("failed to get download directory: {e}");
+            tx.send(Action::Toast(Toast::error(format!(
+                "failed to get download directory: {e}"
+            ))))
+            .ok();
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        match download_images(&agent, &post_view, &dir).await {
+            Ok(paths) => {
+                log::info!("downloaded {} images to {dir:?}", paths.len());
+                tx.send(Action::Toast(Toast::success(format!(
+                    "Downloaded {} images to {}",
+                    paths.len(),
+                    dir.display()
+                ))))
+                .ok();
+            }
+            Err(e) => {
+                log::error!("failed to download images: {e}");
+                tx.send(Action::Toast(Toast::error(format!(
+                    "failed to download images: {e}"
+                ))))
+                .ok();
+            }
+        }
+    });
+}
+
+/// Collect the links in the facets and the embed of the post.
+pub fn links(post_view: &PostView) -> IndexSet<String> {
+    let mut links = IndexSet::new();
+    if let Ok(record) = post::Record::try_from_unknown(post_view.record.clone()) {
+        if let Some(facets) = &record.facets {
+            for facet in facets {
+                for feature in &facet.features {
+                    match feature {
+                        Union::Refs(MainFeaturesItem::Mention(_)) => {
+                            // TODO
+                        }
+                        Union::Refs(MainFeaturesItem::Link(link)) => {
+                            links.insert(link.uri.clone());
+                        }
+                        Union::Refs(MainFeaturesItem::Tag(_)) => {
+                            // TODO
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    match &post_view.embed {
+        Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(images))) => {
+            for image in &images.images {
+                links.insert(image.fullsize.clone());
+            }
+        }
+        Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(external))) => {
+            links.insert(external.external.uri.clone());
+        }
+        Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(
+            record_with_media,
+        ))) => match &record_with_media.media {
+            Union::Refs(ViewMediaRefs::AppBskyEmbedImagesView(images)) => {
+                for image in &images.images {
+                    links.insert(image.fullsize.clone());
+                }
+            }
+            Union::Refs(ViewMediaRefs::AppBskyEmbedExternalView(external)) => {
+                links.insert(external.external.uri.clone());
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{self, FakeServer, post_view, profile};
+    use bsky_sdk::api::app::bsky::richtext::facet;
+    use bsky_sdk::api::types::TryIntoUnknown;
+    use serde_json::json;
+
+    fn record(text: &str, facets: &[(usize, usize, MainFeaturesItem)]) -> post::Record {
+        post::RecordData {
+            created_at: Datetime::now(),
+            embed: None,
+            entities: None,
+            facets: Some(
+                facets
+                    .iter()
+                    .map(|(byte_start, byte_end, feature)| {
+                        facet::MainData {
+                            features: vec![Union::Refs(feature.clone())],
+                            index: facet::ByteSliceData {
+                                byte_end: *byte_end,
+                                byte_start: *byte_start,
+                            }
+                            .into(),
+                        }
+                        .into()
+                    })
+                    .collect(),
+            ),
+            labels: None,
+            langs: None,
+            reply: None,
+            tags: None,
+            text: text.to_string(),
+        }
+        .into()
+    }
+
+    fn tag(tag: &str) -> MainFeaturesItem {
+        MainFeaturesItem::Tag(Box::new(
+            facet::TagData {
+                tag: tag.to_string(),
+            }
+            .into(),
+        ))
+    }
+
+    #[test]
+    fn styled_text_lines() {
+        let theme = Theme::default();
+        let link = MainFeaturesItem::Link(Box::new(
+            facet::LinkData {
+                uri: String::from("https://example.com"),
+            }
+            .into(),
+        ));
+        // "日本語 #タグ example.com", with an invalid facet inside a character
+        let record = record(
+            "日本語 #タグ example.com",
+            &[(18, 29, link), (10, 17, tag("タグ")), (1, 2, tag("x"))],
+        );
+        assert_eq!(facets(&record).len(), 2);
+        let lines = text_lines(&record, 14, "  ", &theme);
+        let spans = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| (span.content.as_ref(), span.style))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                vec![
+                    ("  ", Style::default()),
+                    ("日本語 ", Style::default()),
+                    ("#タグ", theme.facet),
+                ],
+                vec![
+                    ("  ", Style::default()),
+                    ("example.com", theme.link.underlined()),
+                ],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn like_post() {
+        let server = FakeServer::default();
+        let uri = format!("at://{}/app.bsky.feed.like/3klike", fake::DID);
+        server.respond(
+            "com.atproto.repo.createRecord",
+            json!({
+                "uri": uri,
+                "cid": "bafyreidfayvfuwqa7qlnopdjiqrxzs6blmoeu4rujcjtnci5beludirz2a",
+            }),
+        );
+        let agent = server.agent().await;
+        let post_view = post_view(
+            profile("bob.test", None),
+            "3kpost",
+            "hello",
+            "2024-10-01T00:00:00.000Z",
+        );
+        assert_eq!(like(&agent, &post_view).await.expect("failed to like"), uri);
+        let writes = server.writes();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].nsid, "com.atproto.repo.createRecord");
+        let input = writes[0].input.as_ref().expect("no input");
+        assert_eq!(input["repo"], fake::DID);
+        assert_eq!(input["collection"], "app.bsky.feed.like");
+        assert_eq!(input["record"]["subject"]["uri"], post_view.uri.as_str());
+    }
+
+    #[tokio::test]
+    async fn repost_post() {
+        let server = FakeServer::default();
+        let uri = format!("at://{}/app.bsky.feed.repost/3krepost", fake::DID);
+        server.respond(
+            "com.atproto.repo.createRecord",
+            json!({
+                "uri": uri,
+                "cid": "bafyreidfayvfuwqa7qlnopdjiqrxzs6blmoeu4rujcjtnci5beludirz2a",
+            }),
+        );
+        let agent = server.agent().await;
+        let post_view = post_view(
+            profile("bob.test", None),
+            "3kpost",
+            "hello",
+            "2024-10-01T00:00:00.000Z",
+        );
+        assert_eq!(
+            repost(&agent, &post_view).await.expect("failed to repost"),
+            uri
+        );
+        let writes = server.writes();
+        assert_eq!(writes.len(), 1);
+        let input = writes[0].input.as_ref().expect("no input");
+        assert_eq!(input["collection"], "app.bsky.feed.repost");
+        assert_eq!(input["record"]["subject"]["uri"], post_view.uri.as_str());
+        assert_eq!(
+            input["record"]["subject"]["cid"],
+            post_view.cid.as_ref().to_string()
+        );
+    }
+
+    #[test]
+    fn reply_in_thread() {
+        let root = post_view(
+            profile("bob.test", None),
+            "3kroot",
+            "root",
+            "2024-10-01T00:00:00.000Z",
+        );
+        // a reply to a post starts a thread at the post
+        let reply = reply_ref(&root);
+        assert_eq!(reply.parent.uri, root.uri);
+        assert_eq!(reply.root.uri, root.uri);
+        // and a reply to a reply stays in the thread of its root
+        let mut child = post_view(
+            profile("carol.test", None),
+            "3kchild",
+            "child",
+            "2024-10-01T00:01:00.000Z",
+        );
+        let mut record =
+            post::Record::try_from_unknown(child.record.clone()).expect("invalid record");
+        record.reply = Some(reply);
+        child.record = record.try_into_unknown().expect("failed to convert record");
+        let reply = reply_ref(&child);
+        assert_eq!(reply.parent.uri, child.uri);
+        assert_eq!(reply.parent.cid, child.cid);
+        assert_eq!(reply.root.uri, root.uri);
+    }
+}
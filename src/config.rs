@@ -1,181 +1,346 @@
 use crate::backend::config::Config as WatcherConfig;
 use crate::components::views::types::Action as ViewAction;
 use crate::types::Action as AppAction;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+mod conflicts;
+mod file;
+mod key;
+mod keymap;
+mod layout;
+mod theme;
+mod timestamp;
+
+pub use self::conflicts::{Binding, Conflict};
+pub use self::file::{ConfigFile, ParseError};
+pub use self::key::{Key, KeySequence, KeyStroke, ParseKeyError};
+pub use self::keymap::{KeyBuffer, Keymap, Lookup};
+pub use self::layout::{ColumnConfig, StartView, column_widths};
+pub use self::theme::{Theme, ThemeConfig, ThemePreset};
+pub use self::timestamp::{TimestampConfig, TimestampFormat, Timezone};
+
+/// Maximum number of redraws per second if `max_fps` is not set.
+pub const DEFAULT_MAX_FPS: u32 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Config {
     pub num_columns: Option<usize>,
+    /// Capture the mouse to focus columns, scroll lists and click items and buttons.
+    #[serde(default)]
+    pub mouse: bool,
+    /// Maximum number of redraws per second, [`DEFAULT_MAX_FPS`] if not set.
+    pub max_fps: Option<u32>,
     #[serde(default)]
     pub keybindings: Keybindings,
     #[serde(default)]
     pub watcher: WatcherConfig,
+    #[serde(default)]
+    pub download: DownloadConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub timestamp: TimestampConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<ColumnConfig>,
 }
 
 impl Config {
+    /// Settings of the column at the index, or the default settings if it is not in `[[columns]]`.
+    pub fn column(&self, index: usize) -> ColumnConfig {
+        self.columns.get(index).cloned().unwrap_or_default()
+    }
+    /// Minimum interval between redraws.
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.max_fps.unwrap_or(DEFAULT_MAX_FPS).max(1)
+    }
     pub fn set_default_keybindings(&mut self) {
         // global: Ctrl-q to Quit
         self.keybindings
             .global
-            .entry(Key(KeyCode::Char('q'), KeyModifiers::CONTROL))
+            .entry(Key(KeyCode::Char('q'), KeyModifiers::CONTROL).into())
             .or_insert(GlobalAction::Quit);
         // global: Ctrl-o to NextFocus
         self.keybindings
             .global
-            .entry(Key(KeyCode::Char('o'), KeyModifiers::CONTROL))
+            .entry(Key(KeyCode::Char('o'), KeyModifiers::CONTROL).into())
             .or_insert(GlobalAction::NextFocus);
         // global: Ctrl-z to Suspend
         #[cfg(not(windows))]
         self.keybindings
             .global
-            .entry(Key(KeyCode::Char('z'), KeyModifiers::CONTROL))
+            .entry(Key(KeyCode::Char('z'), KeyModifiers::CONTROL).into())
             .or_insert(GlobalAction::Suspend);
         // column: Down to NextItem
         self.keybindings
             .column
-            .entry(Key(KeyCode::Down, KeyModifiers::NONE))
+            .entry(Key(KeyCode::Down, KeyModifiers::NONE).into())
             .or_insert(ColumnAction::NextItem);
         // column: Up to PrevItem
         self.keybindings
             .column
-            .entry(Key(KeyCode::Up, KeyModifiers::NONE))
+            .entry(Key(KeyCode::Up, KeyModifiers::NONE).into())
             .or_insert(ColumnAction::PrevItem);
+        // column: PageDown to PageDown
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::PageDown, KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::PageDown);
+        // column: PageUp to PageUp
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::PageUp, KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::PageUp);
+        // column: Ctrl-d to HalfPageDown
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::Char('d'), KeyModifiers::CONTROL).into())
+            .or_insert(ColumnAction::HalfPageDown);
+        // column: Ctrl-u to HalfPageUp
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::Char('u'), KeyModifiers::CONTROL).into())
+            .or_insert(ColumnAction::HalfPageUp);
+        // column: Home to Top
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::Home, KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::Top);
+        // column: End to Bottom
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::End, KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::Bottom);
         // column: Enter to Enter
         self.keybindings
             .column
-            .entry(Key(KeyCode::Enter, KeyModifiers::NONE))
+            .entry(Key(KeyCode::Enter, KeyModifiers::NONE).into())
             .or_insert(ColumnAction::Enter);
         // column: Backspace to Back
         self.keybindings
             .column
-            .entry(Key(KeyCode::Backspace, KeyModifiers::NONE))
+            .entry(Key(KeyCode::Backspace, KeyModifiers::NONE).into())
             .or_insert(ColumnAction::Back);
         // column: Ctrl-r to Refresh
         self.keybindings
             .column
-            .entry(Key(KeyCode::Char('r'), KeyModifiers::CONTROL))
+            .entry(Key(KeyCode::Char('r'), KeyModifiers::CONTROL).into())
             .or_insert(ColumnAction::Refresh);
         self.keybindings
             .column
-            .entry(Key(KeyCode::Char('x'), KeyModifiers::CONTROL))
+            .entry(Key(KeyCode::Char('x'), KeyModifiers::CONTROL).into())
             .or_insert(ColumnAction::Menu);
+        // column: ? to Help
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::Char('?'), KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::Help);
+        // column: : to CommandPalette
+        self.keybindings
+            .column
+            .entry(Key(KeyCode::Char(':'), KeyModifiers::NONE).into())
+            .or_insert(ColumnAction::CommandPalette);
+        // feed: l to Like
+        self.keybindings
+            .feed
+            .entry(Key(KeyCode::Char('l'), KeyModifiers::NONE).into())
+            .or_insert(FeedAction::Like);
+        // feed: o to OpenLink
+        self.keybindings
+            .feed
+            .entry(Key(KeyCode::Char('o'), KeyModifiers::NONE).into())
+            .or_insert(FeedAction::OpenLink);
+        // post: l to Like
+        self.keybindings
+            .post
+            .entry(Key(KeyCode::Char('l'), KeyModifiers::NONE).into())
+            .or_insert(PostAction::Like);
+        // post: o to OpenLink
+        self.keybindings
+            .post
+            .entry(Key(KeyCode::Char('o'), KeyModifiers::NONE).into())
+            .or_insert(PostAction::OpenLink);
+        // modal: Esc to Cancel
+        self.keybindings
+            .modal
+            .entry(Key(KeyCode::Esc, KeyModifiers::NONE).into())
+            .or_insert(ModalAction::Cancel);
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct DownloadConfig {
+    /// Directory to save downloaded media into.
+    /// Defaults to `tuisky` under the user's download directory.
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Keybindings {
-    pub global: HashMap<Key, GlobalAction>,
-    pub column: HashMap<Key, ColumnAction>,
+    /// Key substituted for `<leader>` in key sequences.
+    #[serde(default = "default_leader")]
+    pub leader: Key,
+    /// Milliseconds to wait for the next key of an incomplete key sequence.
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    #[serde(default)]
+    pub global: HashMap<KeySequence, GlobalAction>,
+    #[serde(default)]
+    pub column: HashMap<KeySequence, ColumnAction>,
+    #[serde(default)]
+    pub feed: HashMap<KeySequence, FeedAction>,
+    #[serde(default)]
+    pub post: HashMap<KeySequence, PostAction>,
+    #[serde(default)]
+    pub composer: HashMap<KeySequence, ComposerAction>,
+    #[serde(default)]
+    pub modal: HashMap<KeySequence, ModalAction>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct Key(KeyCode, KeyModifiers);
+impl Keybindings {
+    pub fn global_keymap(&self) -> Keymap<GlobalAction> {
+        Keymap::new(&self.global, &self.leader)
+    }
+    /// Build the keymap of a column, with the bindings of `scope` taking precedence over `column`.
+    pub fn view_keymap(&self, scope: Option<KeybindingScope>) -> Keymap<ViewAction> {
+        fn convert<'a, A: 'a>(
+            bindings: &'a HashMap<KeySequence, A>,
+        ) -> impl Iterator<Item = (KeySequence, ViewAction)> + 'a
+        where
+            &'a A: Into<ViewAction>,
+        {
+            bindings.iter().map(|(k, v)| (k.clone(), v.into()))
+        }
 
-impl From<KeyEvent> for Key {
-    fn from(event: KeyEvent) -> Self {
-        Self(event.code, event.modifiers)
+        let mut bindings = convert(&self.column).collect::<HashMap<_, _>>();
+        match scope {
+            Some(KeybindingScope::Feed) => bindings.extend(convert(&self.feed)),
+            Some(KeybindingScope::Post) => bindings.extend(convert(&self.post)),
+            Some(KeybindingScope::Composer) => bindings.extend(convert(&self.composer)),
+            Some(KeybindingScope::Modal) => bindings.extend(convert(&self.modal)),
+            None => {}
+        }
+        Keymap::new(&bindings, &self.leader)
+    }
+    /// Build the keymap of the `modal` bindings only, for the modals that take text input.
+    pub fn modal_keymap(&self) -> Keymap<ViewAction> {
+        let bindings = self
+            .modal
+            .iter()
+            .map(|(k, v)| (k.clone(), v.into()))
+            .collect::<HashMap<_, ViewAction>>();
+        Keymap::new(&bindings, &self.leader)
+    }
+    pub fn key_buffer(&self) -> KeyBuffer {
+        KeyBuffer::new(Duration::from_millis(self.timeout))
     }
 }
 
-#[allow(clippy::non_canonical_partial_ord_impl)]
-impl PartialOrd for Key {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.1.partial_cmp(&other.1) {
-            Some(std::cmp::Ordering::Equal) => self.0.partial_cmp(&other.0),
-            o => o,
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            leader: default_leader(),
+            timeout: default_timeout(),
+            global: HashMap::new(),
+            column: HashMap::new(),
+            feed: HashMap::new(),
+            post: HashMap::new(),
+            composer: HashMap::new(),
+            modal: HashMap::new(),
         }
     }
 }
 
-impl Ord for Key {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
-    }
+/// Views that have their own section of keybindings in addition to `column`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeybindingScope {
+    Feed,
+    Post,
+    Composer,
+    Modal,
 }
 
-impl Serialize for Key {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let key_code = match self.0 {
-            KeyCode::Char(c) => c.to_string(),
-            _ => format!("{:?}", self.0),
-        };
-        if self.1 == KeyModifiers::NONE {
-            key_code.serialize(serializer)
-        } else {
-            let modifier = match self.1 {
-                KeyModifiers::CONTROL => "Ctrl",
-                KeyModifiers::SHIFT => "Shift",
-                _ => return Err(serde::ser::Error::custom("unsupported key modifier")),
-            };
-            format!("{modifier}-{key_code}").serialize(serializer)
+impl KeybindingScope {
+    /// Name of the section in `[keybindings]`.
+    pub fn section(&self) -> &'static str {
+        match self {
+            Self::Feed => "feed",
+            Self::Post => "post",
+            Self::Composer => "composer",
+            Self::Modal => "modal",
         }
     }
 }
 
-impl<'de> Deserialize<'de> for Key {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        if let Some((modifier, code)) = s.split_once('-') {
-            let mut chars = code.chars();
-            if let (Some(c), None) = (chars.next(), chars.next()) {
-                Ok(Self(
-                    KeyCode::Char(c),
-                    match modifier {
-                        "Ctrl" => KeyModifiers::CONTROL,
-                        "Shift" => KeyModifiers::SHIFT,
-                        _ => return Err(serde::de::Error::custom("invalid key modifier")),
-                    },
-                ))
-            } else {
-                Err(serde::de::Error::custom("invalid key"))
-            }
-        } else {
-            let key_code = match s.as_str() {
-                "Backspace" => KeyCode::Backspace,
-                "Enter" => KeyCode::Enter,
-                "Left" => KeyCode::Left,
-                "Right" => KeyCode::Right,
-                "Up" => KeyCode::Up,
-                "Down" => KeyCode::Down,
-                "Home" => KeyCode::Home,
-                "End" => KeyCode::End,
-                "PageUp" => KeyCode::PageUp,
-                "PageDown" => KeyCode::PageDown,
-                "Tab" => KeyCode::Tab,
-                "BackTab" => KeyCode::BackTab,
-                "Delete" => KeyCode::Delete,
-                "Insert" => KeyCode::Insert,
-                "Esc" => KeyCode::Esc,
-                _ if s.len() == 1 => KeyCode::Char(s.chars().next().unwrap()),
-                _ => return Err(serde::de::Error::custom("unsupported key code")),
-            };
-            Ok(Self(key_code, KeyModifiers::NONE))
-        }
-    }
+fn default_leader() -> Key {
+    Key(KeyCode::Char('\\'), KeyModifiers::NONE)
+}
+
+fn default_timeout() -> u64 {
+    1000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum GlobalAction {
     NextFocus,
     PrevFocus,
+    NewColumn,
+    CloseColumn,
+    MoveColumnLeft,
+    MoveColumnRight,
+    DuplicateColumn,
+    ToggleZoom,
+    ToastHistory,
     Quit,
     #[cfg(not(windows))]
     Suspend,
 }
 
+impl GlobalAction {
+    #[cfg(not(windows))]
+    pub const ALL: &[Self] = &[
+        Self::NextFocus,
+        Self::PrevFocus,
+        Self::NewColumn,
+        Self::CloseColumn,
+        Self::MoveColumnLeft,
+        Self::MoveColumnRight,
+        Self::DuplicateColumn,
+        Self::ToggleZoom,
+        Self::ToastHistory,
+        Self::Quit,
+        Self::Suspend,
+    ];
+    #[cfg(windows)]
+    pub const ALL: &[Self] = &[
+        Self::NextFocus,
+        Self::PrevFocus,
+        Self::NewColumn,
+        Self::CloseColumn,
+        Self::MoveColumnLeft,
+        Self::MoveColumnRight,
+        Self::DuplicateColumn,
+        Self::ToggleZoom,
+        Self::ToastHistory,
+        Self::Quit,
+    ];
+}
+
 impl From<&GlobalAction> for AppAction {
     fn from(action: &GlobalAction) -> Self {
         match action {
             GlobalAction::NextFocus => Self::NextFocus,
             GlobalAction::PrevFocus => Self::PrevFocus,
+            GlobalAction::NewColumn => Self::NewColumn,
+            GlobalAction::CloseColumn => Self::CloseColumn,
+            GlobalAction::MoveColumnLeft => Self::MoveColumnLeft,
+            GlobalAction::MoveColumnRight => Self::MoveColumnRight,
+            GlobalAction::DuplicateColumn => Self::DuplicateColumn,
+            GlobalAction::ToggleZoom => Self::ToggleZoom,
+            GlobalAction::ToastHistory => Self::ToastHistory,
             GlobalAction::Quit => Self::Quit,
             #[cfg(not(windows))]
             GlobalAction::Suspend => Self::Suspend,
@@ -187,11 +352,41 @@ impl From<&GlobalAction> for AppAction {
 pub enum ColumnAction {
     NextItem,
     PrevItem,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+    Newest,
     Enter,
     Back,
     Refresh,
     NewPost,
     Menu,
+    Help,
+    CommandPalette,
+}
+
+impl ColumnAction {
+    pub const ALL: &[Self] = &[
+        Self::NextItem,
+        Self::PrevItem,
+        Self::PageDown,
+        Self::PageUp,
+        Self::HalfPageDown,
+        Self::HalfPageUp,
+        Self::Top,
+        Self::Bottom,
+        Self::Newest,
+        Self::Enter,
+        Self::Back,
+        Self::Refresh,
+        Self::NewPost,
+        Self::Menu,
+        Self::Help,
+        Self::CommandPalette,
+    ];
 }
 
 impl From<&ColumnAction> for ViewAction {
@@ -199,11 +394,144 @@ impl From<&ColumnAction> for ViewAction {
         match action {
             ColumnAction::NextItem => Self::NextItem,
             ColumnAction::PrevItem => Self::PrevItem,
+            ColumnAction::PageDown => Self::PageDown,
+            ColumnAction::PageUp => Self::PageUp,
+            ColumnAction::HalfPageDown => Self::HalfPageDown,
+            ColumnAction::HalfPageUp => Self::HalfPageUp,
+            ColumnAction::Top => Self::Top,
+            ColumnAction::Bottom => Self::Bottom,
+            ColumnAction::Newest => Self::Newest,
             ColumnAction::Enter => Self::Enter,
             ColumnAction::Back => Self::Back,
             ColumnAction::Refresh => Self::Refresh,
             ColumnAction::NewPost => Self::NewPost,
             ColumnAction::Menu => Self::Menu,
+            ColumnAction::Help => Self::Help,
+            ColumnAction::CommandPalette => Self::CommandPalette,
+        }
+    }
+}
+
+/// Actions on the selected post of a feed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FeedAction {
+    Like,
+    Repost,
+    Reply,
+    Download,
+    OpenLink,
+}
+
+impl FeedAction {
+    pub const ALL: &[Self] = &[
+        Self::Like,
+        Self::Repost,
+        Self::Reply,
+        Self::Download,
+        Self::OpenLink,
+    ];
+}
+
+impl From<&FeedAction> for ViewAction {
+    fn from(action: &FeedAction) -> Self {
+        match action {
+            FeedAction::Like => Self::Like,
+            FeedAction::Repost => Self::Repost,
+            FeedAction::Reply => Self::Reply,
+            FeedAction::Download => Self::Download,
+            FeedAction::OpenLink => Self::OpenLink,
+        }
+    }
+}
+
+/// Actions on the post shown in a post view.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PostAction {
+    Like,
+    Repost,
+    Reply,
+    Delete,
+    Download,
+    OpenLink,
+    ViewRecord,
+}
+
+impl PostAction {
+    pub const ALL: &[Self] = &[
+        Self::Like,
+        Self::Repost,
+        Self::Reply,
+        Self::Delete,
+        Self::Download,
+        Self::OpenLink,
+        Self::ViewRecord,
+    ];
+}
+
+impl From<&PostAction> for ViewAction {
+    fn from(action: &PostAction) -> Self {
+        match action {
+            PostAction::Like => Self::Like,
+            PostAction::Repost => Self::Repost,
+            PostAction::Reply => Self::Reply,
+            PostAction::Delete => Self::Delete,
+            PostAction::Download => Self::Download,
+            PostAction::OpenLink => Self::OpenLink,
+            PostAction::ViewRecord => Self::ViewRecord,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ComposerAction {
+    NextField,
+    PrevField,
+    Embed,
+    Submit,
+    Discard,
+}
+
+impl ComposerAction {
+    pub const ALL: &[Self] = &[
+        Self::NextField,
+        Self::PrevField,
+        Self::Embed,
+        Self::Submit,
+        Self::Discard,
+    ];
+}
+
+impl From<&ComposerAction> for ViewAction {
+    fn from(action: &ComposerAction) -> Self {
+        match action {
+            ComposerAction::NextField => Self::NextItem,
+            ComposerAction::PrevField => Self::PrevItem,
+            ComposerAction::Embed => Self::Embed,
+            ComposerAction::Submit => Self::Submit,
+            ComposerAction::Discard => Self::Back,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ModalAction {
+    NextItem,
+    PrevItem,
+    Enter,
+    Cancel,
+}
+
+impl ModalAction {
+    pub const ALL: &[Self] = &[Self::NextItem, Self::PrevItem, Self::Enter, Self::Cancel];
+}
+
+impl From<&ModalAction> for ViewAction {
+    fn from(action: &ModalAction) -> Self {
+        match action {
+            ModalAction::NextItem => Self::NextItem,
+            ModalAction::PrevItem => Self::PrevItem,
+            ModalAction::Enter => Self::Enter,
+            ModalAction::Cancel => Self::Back,
         }
     }
 }
@@ -238,55 +566,222 @@ feed = 20
             config,
             Config {
                 num_columns: None,
+                mouse: false,
+                max_fps: None,
                 keybindings: Keybindings {
                     global: HashMap::from_iter([(
-                        Key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                        Key(KeyCode::Char('c'), KeyModifiers::CONTROL).into(),
                         GlobalAction::Quit
                     )]),
                     column: HashMap::from_iter([
                         (
-                            Key(KeyCode::Char('n'), KeyModifiers::CONTROL),
+                            Key(KeyCode::Char('n'), KeyModifiers::CONTROL).into(),
                             ColumnAction::NextItem
                         ),
                         (
-                            Key(KeyCode::Char('p'), KeyModifiers::CONTROL),
+                            Key(KeyCode::Char('p'), KeyModifiers::CONTROL).into(),
                             ColumnAction::PrevItem
                         ),
-                        (Key(KeyCode::Left, KeyModifiers::NONE), ColumnAction::Back)
+                        (
+                            Key(KeyCode::Left, KeyModifiers::NONE).into(),
+                            ColumnAction::Back
+                        )
                     ]),
+                    ..Default::default()
                 },
                 watcher: WatcherConfig {
                     intervals: Intervals {
                         preferences: 600,
                         feed: 20,
                         post_thread: 60,
+                        notifications: 30,
                     }
-                }
+                },
+                download: DownloadConfig::default(),
+                theme: ThemeConfig::default(),
+                timestamp: TimestampConfig::default(),
+                columns: Vec::new(),
             }
         )
     }
 
+    #[test]
+    fn deserialize_sequences() {
+        let input = r#"
+[keybindings]
+leader = "Space"
+timeout = 500
+
+[keybindings.column]
+"g g" = "Refresh"
+"<leader> n" = "NewPost"
+"#;
+        let config = toml::from_str::<Config>(input).expect("failed to deserialize config");
+        assert_eq!(
+            config.keybindings,
+            Keybindings {
+                leader: Key(KeyCode::Char(' '), KeyModifiers::NONE),
+                timeout: 500,
+                global: HashMap::new(),
+                column: HashMap::from_iter([
+                    (
+                        KeySequence(vec![
+                            KeyStroke::Key(Key(KeyCode::Char('g'), KeyModifiers::NONE)),
+                            KeyStroke::Key(Key(KeyCode::Char('g'), KeyModifiers::NONE)),
+                        ]),
+                        ColumnAction::Refresh
+                    ),
+                    (
+                        KeySequence(vec![
+                            KeyStroke::Leader,
+                            KeyStroke::Key(Key(KeyCode::Char('n'), KeyModifiers::NONE)),
+                        ]),
+                        ColumnAction::NewPost
+                    ),
+                ]),
+                ..Default::default()
+            }
+        );
+        assert!(toml::from_str::<Config>("[keybindings.column]\n\"g Foo-x\" = \"Back\"").is_err());
+    }
+
+    #[test]
+    fn view_keymap() {
+        let input = r#"
+[keybindings.column]
+l = "NextItem"
+Ctrl-r = "Refresh"
+
+[keybindings.feed]
+l = "Like"
+
+[keybindings.modal]
+Esc = "Cancel"
+"#;
+        let config = toml::from_str::<Config>(input).expect("failed to deserialize config");
+        let l = [Key(KeyCode::Char('l'), KeyModifiers::NONE)];
+        let esc = [Key(KeyCode::Esc, KeyModifiers::NONE)];
+        let refresh = [Key(KeyCode::Char('r'), KeyModifiers::CONTROL)];
+
+        let keymap = config.keybindings.view_keymap(None);
+        assert!(matches!(
+            keymap.get(&l),
+            Lookup::Matched(ViewAction::NextItem)
+        ));
+        assert!(matches!(keymap.get(&esc), Lookup::NotFound));
+
+        let keymap = config.keybindings.view_keymap(Some(KeybindingScope::Feed));
+        assert!(matches!(keymap.get(&l), Lookup::Matched(ViewAction::Like)));
+        assert!(matches!(
+            keymap.get(&refresh),
+            Lookup::Matched(ViewAction::Refresh)
+        ));
+        assert!(matches!(keymap.get(&esc), Lookup::NotFound));
+
+        let keymap = config.keybindings.view_keymap(Some(KeybindingScope::Modal));
+        assert!(matches!(
+            keymap.get(&l),
+            Lookup::Matched(ViewAction::NextItem)
+        ));
+        assert!(matches!(
+            keymap.get(&esc),
+            Lookup::Matched(ViewAction::Back)
+        ));
+    }
+
     #[test]
     fn serialize() {
         let config = Config {
             num_columns: None,
+            mouse: true,
+            max_fps: Some(60),
             keybindings: Keybindings {
                 global: HashMap::from_iter([(
-                    Key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                    Key(KeyCode::Char('c'), KeyModifiers::CONTROL).into(),
                     GlobalAction::Quit,
                 )]),
-                column: HashMap::new(),
+                column: HashMap::from_iter([(
+                    "<leader> g g"
+                        .parse()
+                        .expect("failed to parse key sequence"),
+                    ColumnAction::NewPost,
+                )]),
+                feed: HashMap::from_iter([(
+                    Key(KeyCode::Char('l'), KeyModifiers::NONE).into(),
+                    FeedAction::Like,
+                )]),
+                post: HashMap::from_iter([(
+                    Key(KeyCode::Char('d'), KeyModifiers::NONE).into(),
+                    PostAction::Download,
+                )]),
+                composer: HashMap::from_iter([(
+                    Key(KeyCode::Char('s'), KeyModifiers::CONTROL).into(),
+                    ComposerAction::Submit,
+                )]),
+                modal: HashMap::from_iter([(
+                    Key(KeyCode::Esc, KeyModifiers::NONE).into(),
+                    ModalAction::Cancel,
+                )]),
+                leader: Key(KeyCode::Char(' '), KeyModifiers::NONE),
+                timeout: 500,
             },
             watcher: WatcherConfig {
                 intervals: Intervals {
                     feed: 10,
                     preferences: 10,
                     post_thread: 180,
+                    notifications: 60,
                 },
             },
+            download: DownloadConfig {
+                dir: Some(PathBuf::from("/tmp/tuisky")),
+            },
+            theme: ThemeConfig {
+                preset: ThemePreset::Light,
+                ..Default::default()
+            },
+            timestamp: TimestampConfig {
+                feed: TimestampFormat {
+                    relative: true,
+                    ..Default::default()
+                },
+                post: TimestampFormat {
+                    relative: false,
+                    timezone: "+09:00".parse().expect("failed to parse timezone"),
+                    format: String::from("%H:%M"),
+                },
+            },
+            columns: vec![
+                ColumnConfig {
+                    account: Some(String::from("alice.bsky.social")),
+                    view: Some(StartView::Feed(String::from(
+                        "at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.generator/whats-hot",
+                    ))),
+                    weight: Some(2),
+                    min_width: None,
+                },
+                ColumnConfig {
+                    view: Some(StartView::Notifications),
+                    min_width: Some(60),
+                    ..Default::default()
+                },
+            ],
         };
         let s = toml::to_string(&config).expect("failed to serialize config");
         let deserialized = toml::from_str::<Config>(&s).expect("failed to deserialize config");
         assert_eq!(deserialized, config);
     }
+
+    #[test]
+    fn frame_interval() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.frame_interval(),
+            Duration::from_secs(1) / DEFAULT_MAX_FPS
+        );
+        config.max_fps = Some(60);
+        assert_eq!(config.frame_interval(), Duration::from_secs(1) / 60);
+        config.max_fps = Some(0);
+        assert_eq!(config.frame_interval(), Duration::from_secs(1));
+    }
 }
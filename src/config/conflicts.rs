@@ -0,0 +1,220 @@
+use super::Keybindings;
+use super::key::{Key, KeySequence, KeyStroke};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// A key binding in a section of `[keybindings]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub section: &'static str,
+    pub sequence: KeySequence,
+    pub action: String,
+    keys: Vec<Key>,
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` ({}) in [keybindings.{}]",
+            self.sequence, self.action, self.section
+        )
+    }
+}
+
+/// A pair of key bindings that do not work as they are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Two sequences of the same section resolve to the same keys, and only one of them is used.
+    Duplicate(Binding, Binding),
+    /// A global binding and a column binding are both triggered by the same keys.
+    Overlap(Binding, Binding),
+    /// The first binding is never triggered because the second one takes precedence.
+    Shadowed(Binding, Binding),
+    /// The first binding of `column` is replaced in a view by the second one, and still works in the other views.
+    Overridden(Binding, Binding),
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(a, b) => write!(f, "{a} and {b} are the same keys"),
+            Self::Overlap(a, b) => write!(f, "{a} and {b} are both triggered by the same keys"),
+            Self::Shadowed(a, b) => write!(f, "{a} is never triggered because of {b}"),
+            Self::Overridden(a, b) => write!(
+                f,
+                "{a} is overridden in [keybindings.{}] by `{}` ({})",
+                b.section, b.sequence, b.action
+            ),
+        }
+    }
+}
+
+impl Keybindings {
+    /// All bindings sorted by keys, grouped by section in the order of `global`, `column`, `feed`, `post`, `composer` and `modal`.
+    pub fn bindings(&self) -> Vec<Binding> {
+        [
+            self.section_bindings("global", &self.global),
+            self.section_bindings("column", &self.column),
+            self.section_bindings("feed", &self.feed),
+            self.section_bindings("post", &self.post),
+            self.section_bindings("composer", &self.composer),
+            self.section_bindings("modal", &self.modal),
+        ]
+        .concat()
+    }
+    /// Find the bindings that conflict with or shadow each other.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let global = self.section_bindings("global", &self.global);
+        let column = self.section_bindings("column", &self.column);
+        let scopes = [
+            self.section_bindings("feed", &self.feed),
+            self.section_bindings("post", &self.post),
+            self.section_bindings("composer", &self.composer),
+            self.section_bindings("modal", &self.modal),
+        ];
+
+        let mut conflicts = Vec::new();
+        for section in [&global, &column].into_iter().chain(&scopes) {
+            for (i, a) in section.iter().enumerate() {
+                for b in &section[i + 1..] {
+                    if a.keys == b.keys {
+                        conflicts.push(Conflict::Duplicate(a.clone(), b.clone()));
+                    } else if b.keys.starts_with(&a.keys) {
+                        conflicts.push(Conflict::Shadowed(b.clone(), a.clone()));
+                    } else if a.keys.starts_with(&b.keys) {
+                        conflicts.push(Conflict::Shadowed(a.clone(), b.clone()));
+                    }
+                }
+            }
+        }
+        // the bindings of a view take precedence over `column` in the view
+        for scope in &scopes {
+            for a in scope {
+                for b in &column {
+                    if b.keys.starts_with(&a.keys) {
+                        conflicts.push(Conflict::Overridden(b.clone(), a.clone()));
+                    } else if a.keys.starts_with(&b.keys) {
+                        conflicts.push(Conflict::Shadowed(a.clone(), b.clone()));
+                    }
+                }
+            }
+        }
+        // global keys are handled in addition to the keys of the focused column
+        for a in &global {
+            for b in column.iter().chain(scopes.iter().flatten()) {
+                if a.keys.starts_with(&b.keys) || b.keys.starts_with(&a.keys) {
+                    conflicts.push(Conflict::Overlap(a.clone(), b.clone()));
+                }
+            }
+        }
+        conflicts
+    }
+    fn section_bindings<A: Debug>(
+        &self,
+        section: &'static str,
+        bindings: &HashMap<KeySequence, A>,
+    ) -> Vec<Binding> {
+        let mut bindings = bindings
+            .iter()
+            .map(|(sequence, action)| Binding {
+                section,
+                sequence: sequence.clone(),
+                action: format!("{action:?}"),
+                keys: sequence
+                    .strokes()
+                    .iter()
+                    .map(|stroke| match stroke {
+                        KeyStroke::Leader => self.leader.clone(),
+                        KeyStroke::Key(key) => key.clone(),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        bindings.sort_by_key(|binding| binding.sequence.to_string());
+        bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Config;
+
+    fn conflicts(input: &str) -> Vec<String> {
+        let mut config = toml::from_str::<Config>(input).expect("failed to deserialize config");
+        config.set_default_keybindings();
+        config
+            .keybindings
+            .conflicts()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn no_conflicts() {
+        assert!(conflicts("").is_empty());
+    }
+
+    #[test]
+    fn detect() {
+        let input = r#"
+[keybindings.global]
+"Ctrl-o g" = "PrevFocus"
+
+[keybindings.column]
+"<leader> n" = "NewPost"
+"\\ n" = "Refresh"
+"g" = "Back"
+"g g" = "Refresh"
+
+[keybindings.feed]
+"g" = "Like"
+"#;
+        assert_eq!(
+            conflicts(input),
+            vec![
+                "`Ctrl-o g` (PrevFocus) in [keybindings.global] is never triggered because of `Ctrl-o` (NextFocus) in [keybindings.global]",
+                "`<leader> n` (NewPost) in [keybindings.column] and `\\ n` (Refresh) in [keybindings.column] are the same keys",
+                "`g g` (Refresh) in [keybindings.column] is never triggered because of `g` (Back) in [keybindings.column]",
+                "`g` (Back) in [keybindings.column] is overridden in [keybindings.feed] by `g` (Like)",
+                "`g g` (Refresh) in [keybindings.column] is overridden in [keybindings.feed] by `g` (Like)",
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowed_by_column() {
+        let input = r#"
+[keybindings.column]
+"g" = "Back"
+
+[keybindings.post]
+"g g" = "Like"
+"#;
+        // the binding of the view exists only in the view, where the binding of `column` comes first
+        assert_eq!(
+            conflicts(input),
+            vec![
+                "`g g` (Like) in [keybindings.post] is never triggered because of `g` (Back) in [keybindings.column]",
+            ]
+        );
+    }
+
+    #[test]
+    fn overlap() {
+        let input = r#"
+[keybindings.global]
+"Ctrl-n" = "NextFocus"
+
+[keybindings.modal]
+"Ctrl-n Ctrl-n" = "NextItem"
+"#;
+        assert_eq!(
+            conflicts(input),
+            vec![
+                "`Ctrl-n` (NextFocus) in [keybindings.global] and `Ctrl-n Ctrl-n` (NextItem) in [keybindings.modal] are both triggered by the same keys",
+            ]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use super::Config;
+use color_eyre::Result;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The config file to load on startup and to reload whenever it is modified.
+#[derive(Debug)]
+pub struct ConfigFile {
+    path: PathBuf,
+    num_columns: Option<usize>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigFile {
+    /// `num_columns` given on the command line overrides the one in the file.
+    pub fn new(path: PathBuf, num_columns: Option<usize>) -> Self {
+        Self {
+            path,
+            num_columns,
+            modified: None,
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Read the config with the default keybindings, or the default config if the file does not exist.
+    pub fn load(&mut self) -> Result<Config> {
+        self.modified = modified(&self.path);
+        let mut config = if self.path.exists() {
+            let content = fs::read_to_string(&self.path)?;
+            toml::from_str(&content).map_err(|e| ParseError::new(&content, e))?
+        } else {
+            Config::default()
+        };
+        config.set_default_keybindings();
+        if let Some(num_columns) = self.num_columns {
+            config.num_columns = Some(num_columns);
+        }
+        Ok(config)
+    }
+    /// Returns `true` if the file has been modified, created or removed since the last [`load`](Self::load).
+    pub fn is_modified(&self) -> bool {
+        modified(&self.path) != self.modified
+    }
+}
+
+/// An invalid syntax or value in the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line and column of the error.
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(content: &str, error: toml::de::Error) -> Self {
+        let location = error.span().map(|span| {
+            let before = &content[..span.start];
+            let line = before.matches('\n').count() + 1;
+            let column = before
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .count()
+                + 1;
+            (line, column)
+        });
+        Self {
+            location,
+            message: error
+                .message()
+                .trim_end()
+                .lines()
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "line {line}, column {column}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::process;
+    use std::time::Duration;
+
+    #[test]
+    fn reload() {
+        let dir = env::temp_dir().join(format!("tuisky-config-{}", process::id()));
+        fs::create_dir_all(&dir).expect("failed to create temp dir");
+        let path = dir.join("config.toml");
+        let touch = |content: &str, secs: u64| {
+            fs::write(&path, content).expect("failed to write config");
+            File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|f| f.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)))
+                .expect("failed to set mtime");
+        };
+
+        let mut file = ConfigFile::new(path.clone(), Some(3));
+        assert_eq!(file.load().ok().map(|c| c.num_columns), Some(Some(3)));
+        assert!(!file.is_modified());
+
+        touch("[watcher.intervals]\nfeed = 10\n", 1);
+        assert!(file.is_modified());
+        let config = file.load().expect("failed to load config");
+        assert_eq!(config.watcher.intervals.feed, 10);
+        assert_eq!(config.num_columns, Some(3));
+        assert!(!file.is_modified());
+
+        touch("[watcher.intervals]\nfeed = \"10\"\n", 2);
+        assert!(file.is_modified());
+        let err = file.load().expect_err("loaded an invalid config");
+        assert_eq!(
+            err.downcast_ref::<ParseError>().map(|e| e.location),
+            Some(Some((2, 8)))
+        );
+        assert!(!file.is_modified());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
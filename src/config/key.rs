@@ -0,0 +1,470 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode, ModifierKeyCode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Modifier names in the order they are written.
+const MODIFIERS: [(KeyModifiers, &str); 6] = [
+    (KeyModifiers::CONTROL, "Ctrl"),
+    (KeyModifiers::ALT, "Alt"),
+    (KeyModifiers::SHIFT, "Shift"),
+    (KeyModifiers::SUPER, "Super"),
+    (KeyModifiers::HYPER, "Hyper"),
+    (KeyModifiers::META, "Meta"),
+];
+
+const NAMED_KEYS: [(KeyCode, &str); 24] = [
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::BackTab, "BackTab"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Char(' '), "Space"),
+    (KeyCode::CapsLock, "CapsLock"),
+    (KeyCode::ScrollLock, "ScrollLock"),
+    (KeyCode::NumLock, "NumLock"),
+    (KeyCode::PrintScreen, "PrintScreen"),
+    (KeyCode::Pause, "Pause"),
+    (KeyCode::Menu, "Menu"),
+    (KeyCode::KeypadBegin, "KeypadBegin"),
+    (KeyCode::Null, "Null"),
+];
+
+const MEDIA_KEYS: [(MediaKeyCode, &str); 13] = [
+    (MediaKeyCode::Play, "MediaPlay"),
+    (MediaKeyCode::Pause, "MediaPause"),
+    (MediaKeyCode::PlayPause, "MediaPlayPause"),
+    (MediaKeyCode::Reverse, "MediaReverse"),
+    (MediaKeyCode::Stop, "MediaStop"),
+    (MediaKeyCode::FastForward, "MediaFastForward"),
+    (MediaKeyCode::Rewind, "MediaRewind"),
+    (MediaKeyCode::TrackNext, "MediaTrackNext"),
+    (MediaKeyCode::TrackPrevious, "MediaTrackPrevious"),
+    (MediaKeyCode::Record, "MediaRecord"),
+    (MediaKeyCode::LowerVolume, "MediaLowerVolume"),
+    (MediaKeyCode::RaiseVolume, "MediaRaiseVolume"),
+    (MediaKeyCode::MuteVolume, "MediaMuteVolume"),
+];
+
+const MODIFIER_KEYS: [(ModifierKeyCode, &str); 14] = [
+    (ModifierKeyCode::LeftShift, "LeftShift"),
+    (ModifierKeyCode::LeftControl, "LeftCtrl"),
+    (ModifierKeyCode::LeftAlt, "LeftAlt"),
+    (ModifierKeyCode::LeftSuper, "LeftSuper"),
+    (ModifierKeyCode::LeftHyper, "LeftHyper"),
+    (ModifierKeyCode::LeftMeta, "LeftMeta"),
+    (ModifierKeyCode::RightShift, "RightShift"),
+    (ModifierKeyCode::RightControl, "RightCtrl"),
+    (ModifierKeyCode::RightAlt, "RightAlt"),
+    (ModifierKeyCode::RightSuper, "RightSuper"),
+    (ModifierKeyCode::RightHyper, "RightHyper"),
+    (ModifierKeyCode::RightMeta, "RightMeta"),
+    (ModifierKeyCode::IsoLevel3Shift, "IsoLevel3Shift"),
+    (ModifierKeyCode::IsoLevel5Shift, "IsoLevel5Shift"),
+];
+
+/// A key with its modifiers, written as `[Modifier-]*Code` (e.g. `Ctrl-Shift-x`, `Alt-Enter`, `F5`).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Key(pub(crate) KeyCode, pub(crate) KeyModifiers);
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self(event.code, event.modifiers)
+    }
+}
+
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.1.partial_cmp(&other.1) {
+            Some(Ordering::Equal) => self.0.partial_cmp(&other.0),
+            o => o,
+        }
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in MODIFIERS {
+            if self.1.contains(modifier) {
+                write!(f, "{name}-")?;
+            }
+        }
+        match self.0 {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Media(media) => {
+                let (_, name) = MEDIA_KEYS
+                    .iter()
+                    .find(|(code, _)| *code == media)
+                    .ok_or(fmt::Error)?;
+                write!(f, "{name}")
+            }
+            KeyCode::Modifier(modifier) => {
+                let (_, name) = MODIFIER_KEYS
+                    .iter()
+                    .find(|(code, _)| *code == modifier)
+                    .ok_or(fmt::Error)?;
+                write!(f, "{name}")
+            }
+            code => {
+                let (_, name) = NAMED_KEYS
+                    .iter()
+                    .find(|(c, _)| *c == code)
+                    .ok_or(fmt::Error)?;
+                write!(f, "{name}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseKeyError {
+    Empty,
+    UnknownModifier(String),
+    DuplicateModifier(String),
+    UnknownKey(String),
+}
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty key"),
+            Self::UnknownModifier(s) => write!(
+                f,
+                "unknown modifier `{s}` (expected one of Ctrl, Alt, Shift, Super, Hyper, Meta)"
+            ),
+            Self::DuplicateModifier(s) => write!(f, "duplicate modifier `{s}`"),
+            Self::UnknownKey(s) => write!(f, "unknown key code `{s}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseKeyError::Empty);
+        }
+        // The key code itself may be `-`, as in `Ctrl--`.
+        let (modifiers, code) = if s == "-" {
+            ("", s)
+        } else if let Some(prefix) = s.strip_suffix("--") {
+            (prefix, "-")
+        } else if let Some((prefix, code)) = s.rsplit_once('-') {
+            (prefix, code)
+        } else {
+            ("", s)
+        };
+        let mut key_modifiers = KeyModifiers::NONE;
+        if !modifiers.is_empty() {
+            for name in modifiers.split('-') {
+                let Some((modifier, _)) =
+                    MODIFIERS.iter().find(|(_, n)| n.eq_ignore_ascii_case(name))
+                else {
+                    return Err(ParseKeyError::UnknownModifier(name.to_string()));
+                };
+                if key_modifiers.contains(*modifier) {
+                    return Err(ParseKeyError::DuplicateModifier(name.to_string()));
+                }
+                key_modifiers |= *modifier;
+            }
+        }
+        Ok(Self(parse_key_code(code)?, key_modifiers))
+    }
+}
+
+fn parse_key_code(s: &str) -> Result<KeyCode, ParseKeyError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => return Err(ParseKeyError::Empty),
+        (Some(c), None) => return Ok(KeyCode::Char(c)),
+        _ => {}
+    }
+    if let Some(n) = s.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=24).contains(&n) {
+            return Ok(KeyCode::F(n));
+        }
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(s))
+        .map(|(code, _)| *code)
+        .or_else(|| {
+            MEDIA_KEYS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(s))
+                .map(|(code, _)| KeyCode::Media(*code))
+        })
+        .or_else(|| {
+            MODIFIER_KEYS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(s))
+                .map(|(code, _)| KeyCode::Modifier(*code))
+        })
+        .ok_or_else(|| ParseKeyError::UnknownKey(s.to_string()))
+}
+
+impl Serialize for Key {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|e| serde::de::Error::custom(format!("invalid key `{s}`: {e}")))
+    }
+}
+
+/// A single step of a [`KeySequence`]: either a key or the configured leader key.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum KeyStroke {
+    Leader,
+    Key(Key),
+}
+
+impl Display for KeyStroke {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Leader => write!(f, "<leader>"),
+            Self::Key(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+impl FromStr for KeyStroke {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("<leader>") {
+            Ok(Self::Leader)
+        } else {
+            s.parse().map(Self::Key)
+        }
+    }
+}
+
+/// Keys to be pressed in order, separated by whitespace (e.g. `g g`, `<leader> n`, `Ctrl-x Ctrl-s`).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct KeySequence(pub(crate) Vec<KeyStroke>);
+
+impl KeySequence {
+    pub fn strokes(&self) -> &[KeyStroke] {
+        &self.0
+    }
+}
+
+impl From<Key> for KeySequence {
+    fn from(key: Key) -> Self {
+        Self(vec![KeyStroke::Key(key)])
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, stroke) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{stroke}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let strokes = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if strokes.is_empty() {
+            return Err(ParseKeyError::Empty);
+        }
+        Ok(Self(strokes))
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|e| serde::de::Error::custom(format!("invalid key sequence `{s}`: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for (input, expected) in [
+            ("a", Key(KeyCode::Char('a'), KeyModifiers::NONE)),
+            ("-", Key(KeyCode::Char('-'), KeyModifiers::NONE)),
+            ("Ctrl-x", Key(KeyCode::Char('x'), KeyModifiers::CONTROL)),
+            ("Ctrl--", Key(KeyCode::Char('-'), KeyModifiers::CONTROL)),
+            ("Shift-A", Key(KeyCode::Char('A'), KeyModifiers::SHIFT)),
+            ("Alt-x", Key(KeyCode::Char('x'), KeyModifiers::ALT)),
+            (
+                "Ctrl-Shift-x",
+                Key(
+                    KeyCode::Char('x'),
+                    KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                ),
+            ),
+            (
+                "shift-ctrl-X",
+                Key(
+                    KeyCode::Char('X'),
+                    KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                ),
+            ),
+            ("F5", Key(KeyCode::F(5), KeyModifiers::NONE)),
+            ("Ctrl-Enter", Key(KeyCode::Enter, KeyModifiers::CONTROL)),
+            ("Ctrl-Up", Key(KeyCode::Up, KeyModifiers::CONTROL)),
+            ("Space", Key(KeyCode::Char(' '), KeyModifiers::NONE)),
+            (
+                "MediaPlayPause",
+                Key(KeyCode::Media(MediaKeyCode::PlayPause), KeyModifiers::NONE),
+            ),
+            (
+                "Super-Hyper-Meta-Esc",
+                Key(
+                    KeyCode::Esc,
+                    KeyModifiers::SUPER | KeyModifiers::HYPER | KeyModifiers::META,
+                ),
+            ),
+        ] {
+            assert_eq!(input.parse::<Key>(), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (input, expected) in [
+            ("", ParseKeyError::Empty),
+            ("Foo-x", ParseKeyError::UnknownModifier("Foo".into())),
+            (
+                "Ctrl-Ctrl-x",
+                ParseKeyError::DuplicateModifier("Ctrl".into()),
+            ),
+            ("Ctrl-Foo", ParseKeyError::UnknownKey("Foo".into())),
+            ("F25", ParseKeyError::UnknownKey("F25".into())),
+            ("Ctrl-", ParseKeyError::Empty),
+        ] {
+            assert_eq!(input.parse::<Key>(), Err(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [
+            "a",
+            "-",
+            "Ctrl--",
+            "Ctrl-x",
+            "Ctrl-Alt-Shift-x",
+            "Alt-Enter",
+            "Shift-BackTab",
+            "F12",
+            "Ctrl-F1",
+            "Space",
+            "Ctrl-Space",
+            "MediaTrackNext",
+            "RightCtrl",
+            "Pause",
+        ] {
+            let key = input.parse::<Key>().expect("failed to parse key");
+            assert_eq!(key.to_string(), input);
+            let json = serde_json::to_string(&key).expect("failed to serialize key");
+            assert_eq!(serde_json::from_str::<Key>(&json).ok(), Some(key));
+        }
+    }
+
+    #[test]
+    fn parse_sequence() {
+        for (input, expected) in [
+            (
+                "g g",
+                vec![
+                    KeyStroke::Key(Key(KeyCode::Char('g'), KeyModifiers::NONE)),
+                    KeyStroke::Key(Key(KeyCode::Char('g'), KeyModifiers::NONE)),
+                ],
+            ),
+            (
+                "<leader> n",
+                vec![
+                    KeyStroke::Leader,
+                    KeyStroke::Key(Key(KeyCode::Char('n'), KeyModifiers::NONE)),
+                ],
+            ),
+            (
+                " Ctrl-x   Ctrl-s ",
+                vec![
+                    KeyStroke::Key(Key(KeyCode::Char('x'), KeyModifiers::CONTROL)),
+                    KeyStroke::Key(Key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+                ],
+            ),
+            (
+                "Space",
+                vec![KeyStroke::Key(Key(KeyCode::Char(' '), KeyModifiers::NONE))],
+            ),
+        ] {
+            assert_eq!(input.parse(), Ok(KeySequence(expected)), "{input}");
+        }
+        assert_eq!("".parse::<KeySequence>(), Err(ParseKeyError::Empty));
+        assert_eq!(
+            "g Foo-x".parse::<KeySequence>(),
+            Err(ParseKeyError::UnknownModifier("Foo".into()))
+        );
+        for input in ["g g", "<leader> n", "Ctrl-x Ctrl-s", "<leader> <leader>"] {
+            let sequence = input.parse::<KeySequence>().expect("failed to parse");
+            assert_eq!(sequence.to_string(), input);
+        }
+    }
+}
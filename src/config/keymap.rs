@@ -0,0 +1,209 @@
+use super::key::{Key, KeySequence, KeyStroke};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Result of looking up the keys pressed so far.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Lookup<'a, A> {
+    Matched(&'a A),
+    Pending,
+    NotFound,
+}
+
+#[derive(Debug)]
+struct Node<A> {
+    action: Option<A>,
+    children: HashMap<Key, Node<A>>,
+}
+
+impl<A> Default for Node<A> {
+    fn default() -> Self {
+        Self {
+            action: None,
+            children: HashMap::new(),
+        }
+    }
+}
+
+/// A prefix tree of key sequences, with `<leader>` resolved to the configured leader key.
+#[derive(Debug)]
+pub struct Keymap<A> {
+    root: Node<A>,
+}
+
+impl<A: Clone> Keymap<A> {
+    pub fn new(bindings: &HashMap<KeySequence, A>, leader: &Key) -> Self {
+        let mut root = Node::default();
+        for (sequence, action) in bindings {
+            let mut node = &mut root;
+            for stroke in sequence.strokes() {
+                let key = match stroke {
+                    KeyStroke::Leader => leader.clone(),
+                    KeyStroke::Key(key) => key.clone(),
+                };
+                node = node.children.entry(key).or_default();
+            }
+            node.action = Some(action.clone());
+        }
+        Self { root }
+    }
+}
+
+impl<A> Keymap<A> {
+    pub fn get(&self, keys: &[Key]) -> Lookup<'_, A> {
+        let mut node = &self.root;
+        for key in keys {
+            let Some(child) = node.children.get(key) else {
+                return Lookup::NotFound;
+            };
+            node = child;
+        }
+        // A complete binding takes precedence over longer sequences sharing its prefix.
+        match &node.action {
+            Some(action) => Lookup::Matched(action),
+            None if !node.children.is_empty() => Lookup::Pending,
+            None => Lookup::NotFound,
+        }
+    }
+    /// Push `key` to `buffer` and return the bound action if a sequence is completed.
+    ///
+    /// If the buffered keys do not lead to any binding, they are discarded and `key` is looked up on its own.
+    pub fn feed(&self, buffer: &mut KeyBuffer, key: Key) -> Option<&A> {
+        buffer.push(key.clone());
+        loop {
+            match self.get(buffer.keys()) {
+                Lookup::Matched(action) => {
+                    buffer.clear();
+                    return Some(action);
+                }
+                Lookup::Pending => return None,
+                Lookup::NotFound if buffer.keys().len() > 1 => {
+                    buffer.clear();
+                    buffer.push(key.clone());
+                }
+                Lookup::NotFound => {
+                    buffer.clear();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Keys pressed so far for an incomplete key sequence.
+#[derive(Debug)]
+pub struct KeyBuffer {
+    keys: Vec<Key>,
+    updated_at: Instant,
+    timeout: Duration,
+}
+
+impl KeyBuffer {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            keys: Vec::new(),
+            updated_at: Instant::now(),
+            timeout,
+        }
+    }
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+    pub fn push(&mut self, key: Key) {
+        if self.is_expired() {
+            self.keys.clear();
+        }
+        self.keys.push(key);
+        self.updated_at = Instant::now();
+    }
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+    pub fn is_expired(&self) -> bool {
+        self.updated_at.elapsed() >= self.timeout
+    }
+    /// Discard the pending keys if the timeout has elapsed. Returns `true` if any keys were discarded.
+    pub fn expire(&mut self) -> bool {
+        if !self.keys.is_empty() && self.is_expired() {
+            self.keys.clear();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(c: char) -> Key {
+        Key(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn keymap() -> Keymap<&'static str> {
+        let bindings = HashMap::from_iter([
+            ("Ctrl-r".parse().expect("invalid key sequence"), "refresh"),
+            ("g g".parse().expect("invalid key sequence"), "top"),
+            ("g e".parse().expect("invalid key sequence"), "bottom"),
+            (
+                "<leader> n".parse().expect("invalid key sequence"),
+                "new post",
+            ),
+            ("z".parse().expect("invalid key sequence"), "z"),
+            ("z z".parse().expect("invalid key sequence"), "zz"),
+        ]);
+        Keymap::new(&bindings, &key('\\'))
+    }
+
+    #[test]
+    fn lookup() {
+        let keymap = keymap();
+        assert_eq!(
+            keymap.get(&[Key(KeyCode::Char('r'), KeyModifiers::CONTROL)]),
+            Lookup::Matched(&"refresh")
+        );
+        assert_eq!(keymap.get(&[key('g')]), Lookup::Pending);
+        assert_eq!(keymap.get(&[key('g'), key('g')]), Lookup::Matched(&"top"));
+        assert_eq!(keymap.get(&[key('g'), key('x')]), Lookup::NotFound);
+        assert_eq!(keymap.get(&[key('\\')]), Lookup::Pending);
+        assert_eq!(
+            keymap.get(&[key('\\'), key('n')]),
+            Lookup::Matched(&"new post")
+        );
+        assert_eq!(keymap.get(&[key('z')]), Lookup::Matched(&"z"));
+        assert_eq!(keymap.get(&[key('x')]), Lookup::NotFound);
+    }
+
+    #[test]
+    fn feed() {
+        let keymap = keymap();
+        let mut buffer = KeyBuffer::new(Duration::from_secs(60));
+        assert_eq!(keymap.feed(&mut buffer, key('g')), None);
+        assert_eq!(buffer.keys(), &[key('g')]);
+        assert_eq!(keymap.feed(&mut buffer, key('e')), Some(&"bottom"));
+        assert!(buffer.keys().is_empty());
+        // an unbound sequence falls back to the last key
+        assert_eq!(keymap.feed(&mut buffer, key('g')), None);
+        assert_eq!(keymap.feed(&mut buffer, key('z')), Some(&"z"));
+        assert!(buffer.keys().is_empty());
+        assert_eq!(keymap.feed(&mut buffer, key('g')), None);
+        assert_eq!(keymap.feed(&mut buffer, key('\\')), None);
+        assert_eq!(buffer.keys(), &[key('\\')]);
+        assert_eq!(keymap.feed(&mut buffer, key('x')), None);
+        assert!(buffer.keys().is_empty());
+    }
+
+    #[test]
+    fn timeout() {
+        let keymap = keymap();
+        let mut buffer = KeyBuffer::new(Duration::ZERO);
+        assert_eq!(keymap.feed(&mut buffer, key('g')), None);
+        assert!(buffer.expire());
+        assert!(!buffer.expire());
+        assert_eq!(keymap.feed(&mut buffer, key('g')), None);
+        assert_eq!(keymap.feed(&mut buffer, key('e')), None);
+        assert!(buffer.keys().is_empty());
+    }
+}
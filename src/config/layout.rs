@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+/// Minimum width of a column unless `min_width` is set.
+const DEFAULT_MIN_WIDTH: u16 = 75;
+
+/// Settings of a column in `[[columns]]`, in the order from left to right.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// Handle or DID of the signed-in account to use.
+    pub account: Option<String>,
+    /// View to open after signing in, instead of the list of pinned feeds.
+    pub view: Option<StartView>,
+    /// Share of the width left after every column gets its minimum width.
+    pub weight: Option<u16>,
+    pub min_width: Option<u16>,
+}
+
+impl ColumnConfig {
+    pub fn weight(&self) -> u16 {
+        self.weight.unwrap_or(1)
+    }
+    pub fn min_width(&self) -> u16 {
+        self.min_width.unwrap_or(DEFAULT_MIN_WIDTH)
+    }
+    /// The account without a leading `@`.
+    pub fn account(&self) -> Option<&str> {
+        self.account
+            .as_deref()
+            .map(|account| account.strip_prefix('@').unwrap_or(account))
+    }
+}
+
+/// Written as `"timeline"`, `"notifications"` or a table such as `{ feed = "at://..." }`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartView {
+    Timeline,
+    Notifications,
+    /// AT URI or bsky.app URL of a feed generator.
+    Feed(String),
+    /// AT URI or bsky.app URL of a list.
+    List(String),
+    /// Posts of the account of the handle or DID.
+    Author(String),
+    /// Posts matching the search query.
+    Search(String),
+}
+
+/// Widths of the columns: each gets its minimum width, and the rest is shared by their weights.
+///
+/// The widths never sum to more than `width`.
+pub fn column_widths(width: u16, columns: &[ColumnConfig]) -> Vec<u16> {
+    let min_total = columns
+        .iter()
+        .fold(0, |acc: u16, column| acc.saturating_add(column.min_width()));
+    let rest = u32::from(width.saturating_sub(min_total));
+    let weights = columns
+        .iter()
+        .map(|column| u32::from(column.weight()))
+        .sum::<u32>();
+    let mut widths = columns
+        .iter()
+        .map(|column| {
+            let share = (rest * u32::from(column.weight()))
+                .checked_div(weights)
+                .unwrap_or_default();
+            column.min_width().saturating_add(share as u16)
+        })
+        .collect::<Vec<_>>();
+    let total = widths
+        .iter()
+        .fold(0, |acc: u16, width| acc.saturating_add(*width));
+    if total <= width {
+        // the remainder of the division goes to the last column
+        if let Some(last) = widths.last_mut() {
+            *last += width - total;
+        }
+    } else {
+        // narrower than the minimum widths: the columns are shrunk from the last one
+        let mut excess = total - width;
+        for w in widths.iter_mut().rev() {
+            let shrink = excess.min(*w);
+            *w -= shrink;
+            excess -= shrink;
+        }
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths() {
+        let column = |weight, min_width| ColumnConfig {
+            weight,
+            min_width,
+            ..Default::default()
+        };
+        assert_eq!(column_widths(150, &[column(None, None)]), vec![150]);
+        assert_eq!(
+            column_widths(160, &[column(None, None), column(None, None)]),
+            vec![80, 80]
+        );
+        assert_eq!(
+            column_widths(161, &[column(None, None), column(None, None)]),
+            vec![80, 81]
+        );
+        assert_eq!(
+            column_widths(200, &[column(Some(2), Some(40)), column(None, Some(60))]),
+            vec![40 + 66, 60 + 34]
+        );
+        assert_eq!(
+            column_widths(200, &[column(Some(0), Some(50)), column(Some(0), Some(50))]),
+            vec![50, 150]
+        );
+        // narrower than the minimum width
+        assert_eq!(column_widths(60, &[column(None, None)]), vec![60]);
+        assert_eq!(
+            column_widths(100, &[column(None, Some(60)), column(None, Some(60))]),
+            vec![60, 40]
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        #[derive(Debug, Deserialize)]
+        struct Columns {
+            columns: Vec<ColumnConfig>,
+        }
+
+        let input = r#"
+[[columns]]
+account = "@alice.bsky.social"
+view = "timeline"
+weight = 2
+
+[[columns]]
+view = { feed = "at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.generator/whats-hot" }
+min_width = 60
+
+[[columns]]
+view = { search = "tuisky" }
+"#;
+        let columns = toml::from_str::<Columns>(input)
+            .expect("failed to deserialize")
+            .columns;
+        assert_eq!(
+            columns,
+            vec![
+                ColumnConfig {
+                    account: Some(String::from("@alice.bsky.social")),
+                    view: Some(StartView::Timeline),
+                    weight: Some(2),
+                    min_width: None,
+                },
+                ColumnConfig {
+                    account: None,
+                    view: Some(StartView::Feed(String::from(
+                        "at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.generator/whats-hot"
+                    ))),
+                    weight: None,
+                    min_width: Some(60),
+                },
+                ColumnConfig {
+                    account: None,
+                    view: Some(StartView::Search(String::from("tuisky"))),
+                    weight: None,
+                    min_width: None,
+                },
+            ]
+        );
+        assert_eq!(columns[0].account(), Some("alice.bsky.social"));
+        assert_eq!(columns[0].weight(), 2);
+        assert_eq!(columns[1].min_width(), 60);
+        assert!(toml::from_str::<Columns>("[[columns]]\nview = \"home\"").is_err());
+    }
+}
@@ -0,0 +1,451 @@
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::env;
+use std::str::FromStr;
+
+/// Defines the style slots of [`Theme`] and the corresponding keys of [`Slot`].
+macro_rules! slots {
+    ($($(#[$meta:meta])* $name:ident => $variant:ident,)*) => {
+        /// Styles used to render the views, resolved from [`ThemeConfig`].
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct Theme {
+            $($(#[$meta])* pub $name: Style,)*
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum Slot {
+            $($variant,)*
+        }
+
+        impl Theme {
+            fn slot_mut(&mut self, slot: Slot) -> &mut Style {
+                match slot {
+                    $(Slot::$variant => &mut self.$name,)*
+                }
+            }
+            fn styles_mut(&mut self) -> [&mut Style; [$(Slot::$variant),*].len()] {
+                [$(&mut self.$name),*]
+            }
+        }
+    };
+}
+
+slots! {
+    /// Dates and times of posts.
+    timestamp => Timestamp,
+    display_name => DisplayName,
+    handle => Handle,
+    /// Secondary text such as field names and feed creators.
+    muted => Muted,
+    /// Separators between headers and contents.
+    border => Border,
+    /// Border of the focused column.
+    focused => Focused,
+    /// Highlighted item of lists.
+    selected => Selected,
+    label => Label,
+    /// "Reply to" markers.
+    reply => Reply,
+    /// "Reposted by" markers.
+    repost => Repost,
+    /// Embedded images, links and records.
+    embed => Embed,
+    facet => Facet,
+    link => Link,
+    /// Like count of posts liked by the user.
+    liked => Liked,
+    /// Repost count of posts reposted by the user.
+    reposted => Reposted,
+    /// Buttons such as "Submit" and "OK".
+    button => Button,
+    /// Destructive actions such as "Delete" and "Sign out".
+    danger => Danger,
+    /// Inputs with a valid value.
+    valid => Valid,
+    /// Inputs with an invalid value, and error messages.
+    invalid => Invalid,
+    /// Custom feeds in the root view.
+    feed => Feed,
+    /// Lists in the root view.
+    list => List,
+    /// The following timeline in the root view.
+    timeline => Timeline,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            timestamp: Style::new().green(),
+            display_name: Style::new().bold(),
+            handle: Style::new().gray(),
+            muted: Style::new().gray(),
+            border: Style::new().gray(),
+            focused: Style::new().bold(),
+            selected: Style::new().reversed(),
+            label: Style::new().magenta(),
+            reply: Style::new().blue(),
+            repost: Style::new().blue(),
+            embed: Style::new().yellow(),
+            facet: Style::new().cyan(),
+            link: Style::new().underlined(),
+            liked: Style::new().red(),
+            reposted: Style::new().green(),
+            button: Style::new().blue(),
+            danger: Style::new().red(),
+            valid: Style::new().green(),
+            invalid: Style::new().red(),
+            feed: Style::new().blue(),
+            list: Style::new().yellow(),
+            timeline: Style::new().green(),
+        }
+    }
+    pub fn light() -> Self {
+        let (green, yellow, cyan) = (Color::Indexed(28), Color::Indexed(136), Color::Indexed(30));
+        Self {
+            timestamp: Style::new().fg(green),
+            display_name: Style::new().bold(),
+            handle: Style::new().dark_gray(),
+            muted: Style::new().dark_gray(),
+            border: Style::new().dark_gray(),
+            focused: Style::new().bold(),
+            selected: Style::new().reversed(),
+            label: Style::new().magenta(),
+            reply: Style::new().blue(),
+            repost: Style::new().blue(),
+            embed: Style::new().fg(yellow),
+            facet: Style::new().fg(cyan),
+            link: Style::new().underlined(),
+            liked: Style::new().red(),
+            reposted: Style::new().fg(green),
+            button: Style::new().blue(),
+            danger: Style::new().red(),
+            valid: Style::new().fg(green),
+            invalid: Style::new().red(),
+            feed: Style::new().blue(),
+            list: Style::new().fg(yellow),
+            timeline: Style::new().fg(green),
+        }
+    }
+    pub fn high_contrast() -> Self {
+        Self {
+            timestamp: Style::new().light_green(),
+            display_name: Style::new().white().bold(),
+            handle: Style::new().white(),
+            muted: Style::new().white(),
+            border: Style::new().white(),
+            focused: Style::new().white().bold(),
+            selected: Style::new().reversed().bold(),
+            label: Style::new().light_magenta().bold(),
+            reply: Style::new().light_blue().bold(),
+            repost: Style::new().light_blue().bold(),
+            embed: Style::new().light_yellow().bold(),
+            facet: Style::new().light_cyan(),
+            link: Style::new().underlined().bold(),
+            liked: Style::new().light_red().bold(),
+            reposted: Style::new().light_green().bold(),
+            button: Style::new().light_blue().bold(),
+            danger: Style::new().light_red().bold(),
+            valid: Style::new().light_green(),
+            invalid: Style::new().light_red().bold(),
+            feed: Style::new().light_blue(),
+            list: Style::new().light_yellow(),
+            timeline: Style::new().light_green(),
+        }
+    }
+    /// Remove all colors, keeping only the modifiers.
+    fn without_colors(mut self) -> Self {
+        for style in self.styles_mut() {
+            style.fg = None;
+            style.bg = None;
+            style.underline_color = None;
+        }
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from(&ThemeConfig::default())
+    }
+}
+
+impl From<&ThemeConfig> for Theme {
+    /// Colors are disabled if the `NO_COLOR` environment variable is set to a non-empty value.
+    fn from(config: &ThemeConfig) -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        config.resolve(no_color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    /// Styles overriding the ones of the preset.
+    #[serde(flatten)]
+    pub styles: BTreeMap<Slot, StyleConfig>,
+}
+
+impl<'de> Deserialize<'de> for ThemeConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // not `#[serde(flatten)]`, which loses the locations of invalid values
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = ThemeConfig;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a table of `preset` and styles")
+            }
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut config = ThemeConfig::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "preset" {
+                        config.preset = map.next_value()?;
+                    } else {
+                        let slot = Slot::deserialize(serde::de::value::StrDeserializer::new(&key))?;
+                        config.styles.insert(slot, map.next_value()?);
+                    }
+                }
+                Ok(config)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+impl ThemeConfig {
+    pub fn resolve(&self, no_color: bool) -> Theme {
+        let mut theme = match self.preset {
+            ThemePreset::Dark => Theme::dark(),
+            ThemePreset::Light => Theme::light(),
+            ThemePreset::HighContrast => Theme::high_contrast(),
+        };
+        for (slot, style) in &self.styles {
+            let current = theme.slot_mut(*slot);
+            *current = match style {
+                StyleConfig::Color(color) => current.fg(color.0),
+                StyleConfig::Style { fg, bg, modifiers } => {
+                    let mut style = Style::new();
+                    style.fg = fg.map(|c| c.0);
+                    style.bg = bg.map(|c| c.0);
+                    style.add_modifier(modifiers.iter().fold(Modifier::empty(), |acc, m| acc | m.0))
+                }
+            };
+        }
+        if no_color {
+            theme.without_colors()
+        } else {
+            theme
+        }
+    }
+}
+
+/// A color (replacing only the foreground color of the preset), or a complete style.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum StyleConfig {
+    Color(ColorValue),
+    Style {
+        fg: Option<ColorValue>,
+        bg: Option<ColorValue>,
+        #[serde(default)]
+        modifiers: Vec<ModifierValue>,
+    },
+}
+
+impl<'de> Deserialize<'de> for StyleConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Style {
+            fg: Option<ColorValue>,
+            bg: Option<ColorValue>,
+            #[serde(default)]
+            modifiers: Vec<ModifierValue>,
+        }
+
+        // not `#[serde(untagged)]`, to report which color or field is invalid
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = StyleConfig;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a color or a table of `fg`, `bg` and `modifiers`")
+            }
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                ColorValue::deserialize(serde::de::value::StrDeserializer::new(v))
+                    .map(StyleConfig::Color)
+            }
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let style = Style::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+                Ok(StyleConfig::Style {
+                    fg: style.fg,
+                    bg: style.bg,
+                    modifiers: style.modifiers,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// A color name (`red`, `light-blue`, ...), a 256-color index (`0`-`255`) or a truecolor value (`#rrggbb`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorValue(pub Color);
+
+impl Serialize for ColorValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Color::from_str(&s)
+            .map(Self)
+            .map_err(|_| serde::de::Error::custom(format!("invalid color `{s}`")))
+    }
+}
+
+const MODIFIERS: [(Modifier, &str); 8] = [
+    (Modifier::BOLD, "bold"),
+    (Modifier::DIM, "dim"),
+    (Modifier::ITALIC, "italic"),
+    (Modifier::UNDERLINED, "underlined"),
+    (Modifier::SLOW_BLINK, "slow-blink"),
+    (Modifier::RAPID_BLINK, "rapid-blink"),
+    (Modifier::REVERSED, "reversed"),
+    (Modifier::CROSSED_OUT, "crossed-out"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModifierValue(pub Modifier);
+
+impl Serialize for ModifierValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (_, name) = MODIFIERS
+            .iter()
+            .find(|(m, _)| *m == self.0)
+            .ok_or_else(|| serde::ser::Error::custom(format!("invalid modifier {:?}", self.0)))?;
+        name.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ModifierValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        MODIFIERS
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(&s))
+            .map(|(m, _)| Self(*m))
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid modifier `{s}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let input = r##"
+preset = "light"
+timestamp = "#ff8800"
+label = "201"
+handle = { fg = "blue", modifiers = ["italic"] }
+"##;
+        let config = toml::from_str::<ThemeConfig>(input).expect("failed to deserialize theme");
+        let theme = config.resolve(false);
+        assert_eq!(
+            theme.timestamp,
+            Style::new().fg(Color::Rgb(0xff, 0x88, 0x00))
+        );
+        assert_eq!(theme.label, Style::new().fg(Color::Indexed(201)));
+        assert_eq!(theme.handle, Style::new().blue().italic());
+        assert_eq!(theme.muted, Theme::light().muted);
+
+        let theme = config.resolve(true);
+        assert_eq!(theme.timestamp, Style::new());
+        assert_eq!(theme.handle, Style::new().italic());
+        assert_eq!(theme.selected, Style::new().reversed());
+    }
+
+    #[test]
+    fn presets() {
+        for (input, expected) in [
+            ("", Theme::dark()),
+            (r#"preset = "dark""#, Theme::dark()),
+            (r#"preset = "light""#, Theme::light()),
+            (r#"preset = "high-contrast""#, Theme::high_contrast()),
+        ] {
+            let config = toml::from_str::<ThemeConfig>(input).expect("failed to deserialize theme");
+            assert_eq!(config.resolve(false), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for input in [
+            r#"preset = "solarized""#,
+            r#"timestamp = "not-a-color""#,
+            r#"unknown = "red""#,
+            r#"handle = { modifiers = ["blinking"] }"#,
+        ] {
+            assert!(toml::from_str::<ThemeConfig>(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = r##"
+preset = "high-contrast"
+timestamp = "#ff8800"
+handle = { fg = "blue", bg = "16", modifiers = ["bold", "crossed-out"] }
+"##;
+        let config = toml::from_str::<ThemeConfig>(input).expect("failed to deserialize theme");
+        let s = toml::to_string(&config).expect("failed to serialize theme");
+        assert_eq!(toml::from_str::<ThemeConfig>(&s).ok(), Some(config));
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// How the times of posts are shown, set separately for the feed list and the post view.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct TimestampConfig {
+    #[serde(default)]
+    pub feed: TimestampFormat,
+    #[serde(default)]
+    pub post: TimestampFormat,
+}
+
+impl TimestampConfig {
+    /// Whether any of the timestamps changes as time passes.
+    pub fn is_relative(&self) -> bool {
+        self.feed.relative || self.post.relative
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TimestampFormat {
+    /// Show the times of the last week relative to now, such as `3m`, `2h` or `yesterday`.
+    pub relative: bool,
+    pub timezone: Timezone,
+    /// strftime format of absolute times.
+    #[serde(deserialize_with = "deserialize_format")]
+    pub format: String,
+}
+
+impl Default for TimestampFormat {
+    fn default() -> Self {
+        Self {
+            relative: false,
+            timezone: Timezone::Local,
+            format: String::from("%Y-%m-%d %H:%M:%S %z"),
+        }
+    }
+}
+
+impl TimestampFormat {
+    pub fn format(&self, datetime: &DateTime<FixedOffset>) -> String {
+        self.format_at(datetime, Utc::now())
+    }
+    fn format_at(&self, datetime: &DateTime<FixedOffset>, now: DateTime<Utc>) -> String {
+        let datetime = self.timezone.convert(datetime);
+        if self.relative {
+            let elapsed = now.signed_duration_since(datetime);
+            if elapsed < TimeDelta::minutes(1) {
+                return String::from("now");
+            }
+            if elapsed < TimeDelta::hours(1) {
+                return format!("{}m", elapsed.num_minutes());
+            }
+            if elapsed < TimeDelta::days(1) {
+                return format!("{}h", elapsed.num_hours());
+            }
+            // count the days by the dates in the timezone
+            let days = (self.timezone.convert(&now.fixed_offset()).date_naive()
+                - datetime.date_naive())
+            .num_days();
+            if days == 1 {
+                return String::from("yesterday");
+            }
+            if days < 7 {
+                return format!("{days}d");
+            }
+        }
+        datetime.format(&self.format).to_string()
+    }
+}
+
+/// Timezone to show times in: `local`, `UTC` or a fixed offset such as `+09:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    Local,
+    Utc,
+    Fixed(FixedOffset),
+}
+
+impl Timezone {
+    fn convert(&self, datetime: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self {
+            Self::Local => datetime.with_timezone(&Local).fixed_offset(),
+            Self::Utc => datetime.with_timezone(&Utc).fixed_offset(),
+            Self::Fixed(offset) => datetime.with_timezone(offset),
+        }
+    }
+}
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Utc => write!(f, "UTC"),
+            Self::Fixed(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("local") {
+            Ok(Self::Local)
+        } else if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            Ok(Self::Utc)
+        } else {
+            s.parse().map(Self::Fixed).map_err(|_| {
+                format!(
+                    "invalid timezone `{s}`, expected `local`, `UTC` or an offset like `+09:00`"
+                )
+            })
+        }
+    }
+}
+
+impl Serialize for Timezone {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timezone {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+fn deserialize_format<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    // formatting with an invalid specifier fails at runtime, so reject it here
+    if StrftimeItems::new(&s).any(|item| matches!(item, Item::Error)) {
+        return Err(serde::de::Error::custom(format!("invalid format `{s}`")));
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).expect("invalid datetime")
+    }
+
+    #[test]
+    fn absolute() {
+        let now = datetime("2024-08-01T12:00:00Z").to_utc();
+        let format = TimestampFormat {
+            timezone: Timezone::Utc,
+            ..Default::default()
+        };
+        assert_eq!(
+            format.format_at(&datetime("2024-08-01T20:30:00+09:00"), now),
+            "2024-08-01 11:30:00 +0000"
+        );
+        let format = TimestampFormat {
+            timezone: "+09:00".parse().expect("invalid timezone"),
+            format: String::from("%m/%d %H:%M"),
+            ..Default::default()
+        };
+        assert_eq!(
+            format.format_at(&datetime("2024-08-01T11:30:00Z"), now),
+            "08/01 20:30"
+        );
+    }
+
+    #[test]
+    fn relative() {
+        let now = datetime("2024-08-01T12:00:00Z").to_utc();
+        let format = TimestampFormat {
+            relative: true,
+            timezone: Timezone::Utc,
+            format: String::from("%Y-%m-%d"),
+        };
+        for (input, expected) in [
+            ("2024-08-01T11:59:30Z", "now"),
+            ("2024-08-01T12:00:10Z", "now"),
+            ("2024-08-01T11:57:00Z", "3m"),
+            ("2024-08-01T10:00:00Z", "2h"),
+            ("2024-07-31T13:00:00Z", "23h"),
+            ("2024-07-31T11:00:00Z", "yesterday"),
+            ("2024-07-30T23:00:00Z", "2d"),
+            ("2024-07-26T00:00:00Z", "6d"),
+            ("2024-07-25T23:00:00Z", "2024-07-25"),
+        ] {
+            assert_eq!(format.format_at(&datetime(input), now), expected, "{input}");
+        }
+        // the days are counted in the timezone
+        let format = TimestampFormat {
+            timezone: "+09:00".parse().expect("invalid timezone"),
+            ..format
+        };
+        assert_eq!(
+            format.format_at(&datetime("2024-07-31T02:00:00Z"), now),
+            "yesterday"
+        );
+        assert_eq!(
+            format.format_at(&datetime("2024-07-30T16:00:00Z"), now),
+            "yesterday"
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let input = r#"
+[feed]
+relative = true
+
+[post]
+timezone = "UTC"
+format = "%H:%M"
+"#;
+        assert_eq!(
+            toml::from_str::<TimestampConfig>(input).expect("failed to deserialize"),
+            TimestampConfig {
+                feed: TimestampFormat {
+                    relative: true,
+                    ..Default::default()
+                },
+                post: TimestampFormat {
+                    relative: false,
+                    timezone: Timezone::Utc,
+                    format: String::from("%H:%M"),
+                },
+            }
+        );
+        for input in [
+            "[feed]\ntimezone = \"Asia/Tokyo\"",
+            "[post]\nformat = \"%Y-%Q\"",
+        ] {
+            assert!(toml::from_str::<TimestampConfig>(input).is_err(), "{input}");
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::types::Event;
 use color_eyre::Result;
-use crossterm::event::{Event as CrosstermEvent, EventStream, KeyEventKind};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, EventStream, KeyEventKind,
+};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{cursor, execute};
@@ -26,6 +28,7 @@ where
     task: Option<JoinHandle<()>>,
     event_tx: UnboundedSender<Event>,
     event_rx: UnboundedReceiver<Event>,
+    mouse: bool,
 }
 
 impl<B> Tui<B>
@@ -39,10 +42,14 @@ where
             task: None,
             event_tx,
             event_rx,
+            mouse: false,
         }
     }
     pub fn start(&mut self) -> Result<()> {
         init()?;
+        if self.mouse {
+            execute!(io(), EnableMouseCapture)?;
+        }
         let event_tx = self.event_tx.clone();
         self.task = Some(tokio::spawn(async move {
             let mut reader = EventStream::new();
@@ -64,6 +71,16 @@ where
         }));
         Ok(())
     }
+    /// Enable or disable capturing mouse events, also after resuming from suspend.
+    pub fn set_mouse(&mut self, mouse: bool) -> Result<()> {
+        if mouse {
+            execute!(io(), EnableMouseCapture)?;
+        } else {
+            execute!(io(), DisableMouseCapture)?;
+        }
+        self.mouse = mouse;
+        Ok(())
+    }
     #[cfg(not(windows))]
     pub fn suspend(&mut self) -> Result<()> {
         restore()?;
@@ -94,6 +111,9 @@ where
                 CrosstermEvent::Key(key) if key.kind != KeyEventKind::Release => {
                     tx.send(Event::Key(key)).unwrap();
                 }
+                CrosstermEvent::Resize(width, height) => {
+                    tx.send(Event::Resize(width, height)).unwrap();
+                }
                 _ => {
                     // TODO
                 }
@@ -135,7 +155,12 @@ fn init() -> Result<()> {
 
 /// Restore the terminal to its original state
 pub(crate) fn restore() -> Result<()> {
-    execute!(io(), LeaveAlternateScreen, cursor::Show)?;
+    execute!(
+        io(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        cursor::Show
+    )?;
     disable_raw_mode()?;
     Ok(())
 }
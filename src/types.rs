@@ -1,5 +1,5 @@
+use crate::backend::client::Agent;
 use crate::components::views::types::Action as ViewAction;
-use bsky_sdk::BskyAgent;
 use crossterm::event::{KeyEvent, MouseEvent};
 use std::fmt::{Debug, Formatter, Result};
 
@@ -15,10 +15,19 @@ pub enum Action {
     Resume,
     Tick(usize),
     Render,
+    Resize(u16, u16),
     NextFocus,
     PrevFocus,
+    NewColumn,
+    CloseColumn,
+    MoveColumnLeft,
+    MoveColumnRight,
+    DuplicateColumn,
+    ToggleZoom,
+    ToastHistory,
+    Toast(Toast),
     View((IdType, ViewAction)),
-    Login((IdType, Box<BskyAgent>)),
+    Login((IdType, Box<Agent>)),
 }
 
 impl Debug for Action {
@@ -28,8 +37,19 @@ impl Debug for Action {
             Self::Quit => write!(f, "Quit"),
             Self::Tick(arg) => f.debug_tuple("Tick").field(arg).finish(),
             Self::Render => write!(f, "Render"),
+            Self::Resize(width, height) => {
+                f.debug_tuple("Resize").field(width).field(height).finish()
+            }
             Self::NextFocus => write!(f, "NextFocus"),
             Self::PrevFocus => write!(f, "PrevFocus"),
+            Self::NewColumn => write!(f, "NewColumn"),
+            Self::CloseColumn => write!(f, "CloseColumn"),
+            Self::MoveColumnLeft => write!(f, "MoveColumnLeft"),
+            Self::MoveColumnRight => write!(f, "MoveColumnRight"),
+            Self::DuplicateColumn => write!(f, "DuplicateColumn"),
+            Self::ToggleZoom => write!(f, "ToggleZoom"),
+            Self::ToastHistory => write!(f, "ToastHistory"),
+            Self::Toast(arg) => f.debug_tuple("Toast").field(arg).finish(),
             Self::View(arg) => f.debug_tuple("View").field(arg).finish(),
             Self::Login((arg, _)) => f.debug_tuple("Login").field(arg).finish(),
             #[cfg(not(windows))]
@@ -40,10 +60,46 @@ impl Debug for Action {
     }
 }
 
+/// A notification shown to the user for a while, and kept in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub level: ToastLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Error,
+}
+
+impl Toast {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Info,
+            message: message.into(),
+        }
+    }
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Success,
+            message: message.into(),
+        }
+    }
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            level: ToastLevel::Error,
+            message: message.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Event {
     Tick(usize),
     Key(KeyEvent),
     Mouse(MouseEvent),
+    Resize(u16, u16),
     Error(String),
 }
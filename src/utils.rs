@@ -1,6 +1,6 @@
 use crate::tui;
 use color_eyre::{config::HookBuilder, eyre, Result};
-use directories::ProjectDirs;
+use directories::{ProjectDirs, UserDirs};
 use std::{panic, path::PathBuf, process};
 
 pub fn initialize_panic_handler() -> Result<()> {
@@ -24,6 +24,14 @@ pub fn get_config_dir() -> Result<PathBuf> {
     Ok(project_dirs()?.config_dir().to_path_buf())
 }
 
+pub fn get_download_dir() -> Result<PathBuf> {
+    if let Some(dir) = UserDirs::new().as_ref().and_then(UserDirs::download_dir) {
+        Ok(dir.join("tuisky"))
+    } else {
+        Ok(get_data_dir()?.join("downloads"))
+    }
+}
+
 fn project_dirs() -> Result<ProjectDirs> {
     ProjectDirs::from("com", "sugyan", "tuisky")
         .ok_or_else(|| eyre::eyre!("failed to get project directories"))